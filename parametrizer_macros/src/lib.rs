@@ -0,0 +1,669 @@
+//! Companion crate to parametrizer providing the `parametrize!` macro, which parses a parametric
+//! function string literal at compile time and expands to a closure computing it natively. Invalid
+//! strings are reported as compile errors, and the resulting closure has no parsing or dynamic
+//! dispatch overhead.
+//!
+//! # Example Usage
+//!
+//! ```
+//! use parametrizer_macros::parametrize;
+//!
+//! let polynomial = parametrize!("1+5*t+25*t*t");
+//! let integer = parametrize!(i32, "13-t");
+//!
+//! assert_eq!(241.0, polynomial(3.0));
+//! assert_eq!(10, integer(3));
+//! ```
+//!
+//! The number type is given first and defaults to f64. Sine and cosine are available as "sin" and
+//! "cos", as in Parametrizer::new, and additional functions can be bound to paths of functions
+//! with the signature fn(f64) -> f64:
+//!
+//! ```
+//! use parametrizer_macros::parametrize;
+//!
+//! fn square(t: f64) -> f64
+//! {
+//!
+//!     return t * t;
+//!
+//! }
+//!
+//! let functions = parametrize!(f32, "Log( square(t) + 3 ) + sin(t)", log = f64::ln, square = square);
+//! let piecewise = parametrize!("p[10]t>0|t*t-16>5");
+//!
+//! assert_eq!(7.0_f64.ln() as f32 + 2.0_f64.sin() as f32, functions(2.0));
+//! assert_eq!(4.0, piecewise(4.0));
+//! assert_eq!(48.0, piecewise(18.0));
+//! assert_eq!(3.0, piecewise(23.0));
+//! ```
+//!
+//! Strings which fail to parse do not compile:
+//!
+//! ```compile_fail
+//! use parametrizer_macros::parametrize;
+//!
+//! let missing = parametrize!("2t+1");
+//! ```
+//!
+//! Integer arithmetic is checked for overflow, matching the default OverflowPolicy of Parametrizer:
+//!
+//! ```should_panic
+//! use parametrizer_macros::parametrize;
+//!
+//! let square = parametrize!(u8, "t*t");
+//!
+//! assert_eq!(225, square(15));
+//!
+//! square(16);
+//! ```
+//!
+//! Dynamic random values ("rd(") are generated each time the closure is called, exactly like
+//! RandomTerm. Computed random values ("rc(") are computed once when the string is parsed, which for
+//! this macro means at compile time.
+//!
+//! ```
+//! use parametrizer_macros::parametrize;
+//!
+//! let dynamic_rand = parametrize!(i32, "rd(2+t<4*t)");
+//! let computed_rand = parametrize!(i32, "rc(4<8)");
+//!
+//! assert_eq!(computed_rand(2), computed_rand(4));
+//! assert!(4 <= dynamic_rand(2));
+//! assert!(16 > dynamic_rand(4));
+//! ```
+//!
+//! The bounds of computed random values may be constant expressions such as "rc(1<pi+1)", but may
+//! not call functions, as even sine and cosine are only evaluated when the closure is called:
+//!
+//! ```compile_fail
+//! use parametrizer_macros::parametrize;
+//!
+//! let computed_rand = parametrize!("rc(1<sin(1)+1)");
+//! ```
+//!
+//! ```compile_fail
+//! use parametrizer_macros::parametrize;
+//!
+//! fn double(t: f64) -> f64
+//! {
+//!
+//!     return 2.0 * t;
+//!
+//! }
+//!
+//! let computed_rand = parametrize!("t + rc(0<double(3))", double = double);
+//! ```
+//!
+//! Noise is seeded when the string is parsed, so is fixed at compile time:
+//!
+//! ```
+//! use parametrizer_macros::parametrize;
+//!
+//! let noise = parametrize!("fbm(t*0.5, 4)");
+//!
+//! assert_eq!(noise(1.3), noise(1.3));
+//! assert!(noise(1.3).abs() <= 1.0);
+//! ```
+//!
+//! Random distributions and choices are drawn each time the closure is called:
+//!
+//! ```
+//! use parametrizer_macros::parametrize;
+//!
+//! let triangular = parametrize!("triangular(t, 2*t, t)");
+//! let choice = parametrize!(i32, "choice(0:1, t:2)");
+//!
+//! assert!(3.0 <= triangular(3.0));
+//! assert!(6.0 >= triangular(3.0));
+//! assert_eq!(2, choice(1));
+//! ```
+
+extern crate proc_macro;
+
+use std::cell::Cell;
+use std::panic;
+
+use proc_macro2::Literal;
+use proc_macro2::Span;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse::Parse;
+use syn::parse::ParseStream;
+use syn::parse_macro_input;
+use syn::Ident;
+use syn::LitStr;
+use syn::Path;
+use syn::Token;
+
+use parametrizer::term::create_parametrization;
+use parametrizer::term::sequenceterm::SequenceOperations;
+use parametrizer::term::Term;
+use parametrizer::term::TermView;
+use parametrizer::Number;
+use parametrizer::ParametrizerFunction;
+
+//A number type which can be written out as a Rust literal
+trait LiteralNumber: Number
+{
+
+    fn tokens(self) -> TokenStream;
+
+}
+
+macro_rules! integer_literals
+{
+
+    ($($number:ty => $suffixed:ident),*) =>
+    {
+
+        $(impl LiteralNumber for $number
+        {
+
+            fn tokens(self) -> TokenStream
+            {
+
+                let literal = Literal::$suffixed(self);
+
+                return quote!((#literal));
+
+            }
+
+        })*
+
+    }
+
+}
+
+macro_rules! float_literals
+{
+
+    ($($number:ident => $suffixed:ident),*) =>
+    {
+
+        $(impl LiteralNumber for $number
+        {
+
+            fn tokens(self) -> TokenStream
+            {
+
+                if self.is_nan()
+                {
+
+                    return quote!($number::NAN);
+
+                }
+                else if self.is_infinite()
+                {
+
+                    return if self > 0.0 { quote!($number::INFINITY) } else { quote!($number::NEG_INFINITY) };
+
+                }
+
+                let literal = Literal::$suffixed(self);
+
+                return quote!((#literal));
+
+            }
+
+        })*
+
+    }
+
+}
+
+integer_literals!(i8 => i8_suffixed, i16 => i16_suffixed, i32 => i32_suffixed, i64 => i64_suffixed, i128 => i128_suffixed, isize => isize_suffixed, u8 => u8_suffixed, u16 => u16_suffixed, u32 => u32_suffixed, u64 => u64_suffixed, u128 => u128_suffixed, usize => usize_suffixed);
+float_literals!(f32 => f32_suffixed, f64 => f64_suffixed);
+
+//An identifier from the param string bound to the path of a function
+struct FunctionBinding
+{
+
+    identifier: Ident,
+    path: Path
+
+}
+
+struct MacroInput
+{
+
+    number: Ident,
+    param: LitStr,
+    functions: Vec<FunctionBinding>
+
+}
+
+impl Parse for MacroInput
+{
+
+    fn parse(input: ParseStream) -> syn::Result<MacroInput>
+    {
+
+        let number = if input.peek(Ident)
+        {
+
+            let number = input.parse()?;
+            input.parse::<Token![,]>()?;
+
+            number
+
+        }
+        else
+        {
+
+            Ident::new("f64", Span::call_site())
+
+        };
+
+        let param = input.parse()?;
+
+        let mut functions = Vec::new();
+
+        while !input.is_empty()
+        {
+
+            input.parse::<Token![,]>()?;
+
+            if input.is_empty() //Allow a trailing comma
+            {
+
+                break;
+
+            }
+
+            let identifier = input.parse()?;
+            input.parse::<Token![=]>()?;
+            let path = input.parse()?;
+
+            functions.push(FunctionBinding { identifier, path });
+
+        }
+
+        return Ok(MacroInput { number, param, functions });
+
+    }
+
+}
+
+thread_local!
+{
+
+    //Set when a placeholder is called, i.e. when the parser needs the value of a function, such as
+    //in the bounds of a computed random value, which cannot be known at compile time
+    static EVALUATED : Cell<bool> = const { Cell::new(false) };
+
+}
+
+//Stands in for the bound functions while parsing, as their bodies are unknown at compile time.
+//FunctionTerms remember their identifiers, which is all the generator needs.
+fn placeholder(t: f64) -> f64
+{
+
+    EVALUATED.with(|evaluated| { evaluated.set(true); });
+
+    return t;
+
+}
+
+//Walks a parsed term tree and writes out the equivalent Rust expression in terms of t
+struct Generator<'a>
+{
+
+    number: &'a Ident,
+    functions: Vec<(String, TokenStream)>,
+    uses_variable: bool,
+    integer: bool
+
+}
+
+impl Generator<'_>
+{
+
+    //Integer arithmetic is checked for overflow like Parametrizer's default OverflowPolicy, while
+    //floats use the plain operators
+    fn arithmetic(&self, operation: SequenceOperations, l: TokenStream, r: TokenStream) -> TokenStream
+    {
+
+        return match (self.integer, operation)
+        {
+
+            (false, SequenceOperations::Addition) => quote!(#l + #r),
+            (false, SequenceOperations::Multiplication) => quote!(#l * #r),
+            (true, SequenceOperations::Addition) => quote!(::parametrizer::OverflowPolicy::Checked.add(#l, #r).expect("Integer overflow in parametrized function.")),
+            (true, SequenceOperations::Multiplication) => quote!(::parametrizer::OverflowPolicy::Checked.multiply(#l, #r).expect("Integer overflow in parametrized function."))
+
+        };
+
+    }
+
+    fn generate<T: LiteralNumber>(&mut self, term: &dyn Term<T>) -> Result<TokenStream, String>
+    {
+
+        let number = self.number;
+
+        match term.view()
+        {
+
+            TermView::Constant(c) => return Ok(c.tokens()),
+            TermView::Variable =>
+            {
+
+                self.uses_variable = true;
+
+                return Ok(quote!(t));
+
+            },
+            TermView::Sequence(operation, terms) =>
+            {
+
+                let mut computed = None;
+
+                for subterm in terms
+                {
+
+                    let value = self.generate(&subterm)?;
+
+                    computed = match computed
+                    {
+
+                        None => Some(value),
+                        Some(c) => Some(self.arithmetic(operation, c, value))
+
+                    };
+
+                }
+
+                return match (computed, operation)
+                {
+
+                    (Some(c), _) => Ok(quote!((#c))),
+                    (None, SequenceOperations::Addition) => Ok(T::zero().tokens()),
+                    (None, SequenceOperations::Multiplication) => Ok(T::one().tokens())
+
+                };
+
+            },
+            TermView::Scalar(subterm, scale) =>
+            {
+
+                let scale = scale.tokens();
+                let value = self.generate(&subterm)?;
+
+                let product = self.arithmetic(SequenceOperations::Multiplication, scale, value);
+
+                return Ok(quote!((#product)));
+
+            },
+            TermView::Fraction(numerator, denominator) =>
+            {
+
+                //Evaluated in the same order as FractionTerm::evaluate
+                let denominator = self.generate(&denominator)?;
+                let numerator = self.generate(&numerator)?;
+
+                return Ok(quote!({
+
+                    let denominator = #denominator;
+
+                    ::parametrizer::term::fractionterm::FractionTerm::<#number>::divide(#numerator, denominator, ::parametrizer::OverflowPolicy::Checked)
+
+                }));
+
+            },
+            TermView::Function(subterm, _, identifier) =>
+            {
+
+                let identifier = identifier.ok_or("Function terms must have an identifier.")?;
+
+                let function = match self.functions.iter().find(|(i, _)| { return i == identifier; })
+                {
+
+                    Some((_, path)) => path.clone(),
+                    None => return Err(format!("No function is bound to the identifier {}.", identifier))
+
+                };
+
+                let value = self.generate(&subterm)?;
+
+                return Ok(quote!(::parametrizer::term::functionterm::FunctionTerm::<#number>::apply(#function, #value)));
+
+            },
+            TermView::Random(min, max) =>
+            {
+
+                let min = self.generate(&min)?;
+                let max = self.generate(&max)?;
+
+                return Ok(quote!(::parametrizer::term::randomterm::RandomTerm::<#number>::generate(#min, #max)));
+
+            },
+            TermView::Distribution(distribution, parameters) =>
+            {
+
+                let variant = Ident::new(&format!("{:?}", distribution), Span::call_site());
+                let parameters = parameters.iter().map(|p| { return self.generate(p); }).collect::<Result<Vec<TokenStream>, String>>()?;
+
+                return Ok(quote!(::parametrizer::term::distributionterm::DistributionTerm::<#number>::generate(::parametrizer::term::distributionterm::Distribution::#variant, &[#(#parameters),*])));
+
+            },
+            TermView::Noise(subterm, noise) =>
+            {
+
+                let kind = Ident::new(&format!("{:?}", noise.kind()), Span::call_site());
+                let seed = Literal::u64_suffixed(noise.seed());
+                let octaves = Literal::u32_suffixed(noise.octaves());
+                let lacunarity = Literal::f64_suffixed(noise.lacunarity());
+                let gain = Literal::f64_suffixed(noise.gain());
+
+                let value = self.generate(&subterm)?;
+
+                //The noise is created on first use, so that its permutation is only computed once
+                return Ok(quote!({
+
+                    static NOISE: ::std::sync::OnceLock<::parametrizer::term::noiseterm::Noise> = ::std::sync::OnceLock::new();
+
+                    let noise = NOISE.get_or_init(|| ::parametrizer::term::noiseterm::Noise::fractal(::parametrizer::term::noiseterm::NoiseKind::#kind, #seed, #octaves, #lacunarity, #gain));
+
+                    ::parametrizer::term::noiseterm::NoiseTerm::<#number>::apply(noise, #value)
+
+                }));
+
+            },
+            TermView::Choice(choices) =>
+            {
+
+                let mut weights = Vec::new();
+                let mut branches = Vec::new();
+
+                for (i, (weight, choice)) in choices.iter().enumerate()
+                {
+
+                    let index = Literal::usize_unsuffixed(i);
+
+                    weights.push(self.generate(weight)?);
+
+                    let value = self.generate(choice)?;
+
+                    branches.push(quote!(#index => #value));
+
+                }
+
+                //As in ChoiceTerm::evaluate, every weight is evaluated and then only the chosen term
+                return Ok(quote!(match ::parametrizer::term::choiceterm::ChoiceTerm::<#number>::choose(&[#(#weights),*])
+                {
+
+                    #(#branches,)*
+                    _ => unreachable!()
+
+                }));
+
+            },
+            TermView::Piecewise(parts, cycle) =>
+            {
+
+                //Build the branches from the last part backwards, so that each part is used when t
+                //has not reached the next part's after value, as in PiecewiseTerm::evaluate
+                let mut branches = match parts.last()
+                {
+
+                    Some((last, _)) => self.generate(last)?,
+                    None => return Ok(T::zero().tokens())
+
+                };
+
+                for i in (1..parts.len()).rev()
+                {
+
+                    let after = parts[i].1.tokens();
+                    let previous = self.generate(&parts[i - 1].0)?;
+
+                    branches = quote!(if t >= #after { #branches } else { #previous });
+
+                }
+
+                self.uses_variable = true;
+
+                return match cycle
+                {
+
+                    Some(c) =>
+                    {
+
+                        let c = c.tokens();
+
+                        Ok(quote!({
+
+                            let t = if t > #c { t % #c } else { t };
+
+                            #branches
+
+                        }))
+
+                    },
+                    None => Ok(quote!({ #branches }))
+
+                };
+
+            },
+            TermView::Opaque => return Err("Encountered a term which cannot be written out as Rust code.".to_string())
+
+        }
+
+    }
+
+}
+
+fn expand_number<T: LiteralNumber>(input: &MacroInput) -> syn::Result<TokenStream>
+{
+
+    let mut functions = vec![
+
+        ParametrizerFunction::new("sin".to_string(), placeholder),
+        ParametrizerFunction::new("cos".to_string(), placeholder)
+
+    ];
+
+    //User bindings take priority over the default sine and cosine
+    let mut paths = Vec::new();
+
+    for binding in &input.functions
+    {
+
+        let function = ParametrizerFunction::new(binding.identifier.to_string(), placeholder);
+        let path = &binding.path;
+
+        paths.push((function.identifier().to_string(), quote!(#path)));
+        functions.push(function);
+
+    }
+
+    paths.push(("sin".to_string(), quote!(f64::sin)));
+    paths.push(("cos".to_string(), quote!(f64::cos)));
+
+    let param = input.param.value();
+
+    //Parsing may panic, e.g. if a value cannot be represented by the number type, which should be a
+    //compile error rather than a crash of the macro
+    EVALUATED.with(|evaluated| { evaluated.set(false); });
+
+    let parsed = panic::catch_unwind(|| { return create_parametrization::<T>(&param, &functions); });
+
+    //The value the placeholder returned is wrong, so any result depending on it is too
+    if EVALUATED.with(|evaluated| { return evaluated.get(); })
+    {
+
+        return Err(syn::Error::new(input.param.span(), "The bounds of computed random values (\"rc(\") cannot call functions, as functions are not evaluated at compile time."));
+
+    }
+
+    let term = match parsed
+    {
+
+        Ok(Ok(term)) => term,
+        Ok(Err(e)) => return Err(syn::Error::new(input.param.span(), e)),
+        Err(_) => return Err(syn::Error::new(input.param.span(), "Parametrizer panicked while parsing the string."))
+
+    };
+
+    //Integer division truncates, which distinguishes integer types from floats
+    let integer = T::one() / (T::one() + T::one()) == T::zero();
+
+    let mut generator = Generator { number: &input.number, functions: paths, uses_variable: false, integer };
+
+    let body = generator.generate(&*term).map_err(|e| { return syn::Error::new(input.param.span(), e); })?;
+
+    let number = &input.number;
+    let variable = if generator.uses_variable { quote!(t) } else { quote!(_t) };
+
+    //The generated code mirrors the term tree rather than idiomatic Rust, so lints are silenced
+    return Ok(quote!({
+
+        #[allow(clippy::all)]
+        let parametrized = |#variable: #number| -> #number { #body };
+
+        parametrized
+
+    }));
+
+}
+
+///Parses a parametric function string at compile time and expands to a closure taking and returning
+///the given number type (f64 if omitted). Sine and cosine are available by default, and further
+///functions can be bound by listing `identifier = path` pairs after the string. See the crate
+///documentation for examples.
+#[proc_macro]
+pub fn parametrize(input: proc_macro::TokenStream) -> proc_macro::TokenStream
+{
+
+    let input = parse_macro_input!(input as MacroInput);
+
+    let expanded = match input.number.to_string().as_str()
+    {
+
+        "f32" => expand_number::<f32>(&input),
+        "f64" => expand_number::<f64>(&input),
+        "i8" => expand_number::<i8>(&input),
+        "i16" => expand_number::<i16>(&input),
+        "i32" => expand_number::<i32>(&input),
+        "i64" => expand_number::<i64>(&input),
+        "i128" => expand_number::<i128>(&input),
+        "isize" => expand_number::<isize>(&input),
+        "u8" => expand_number::<u8>(&input),
+        "u16" => expand_number::<u16>(&input),
+        "u32" => expand_number::<u32>(&input),
+        "u64" => expand_number::<u64>(&input),
+        "u128" => expand_number::<u128>(&input),
+        "usize" => expand_number::<usize>(&input),
+        _ => Err(syn::Error::new(input.number.span(), "Unsupported number type. Expected a primitive integer or float type."))
+
+    };
+
+    return match expanded
+    {
+
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into()
+
+    };
+
+}
@@ -0,0 +1,523 @@
+use crate::Number;
+use crate::OverflowPolicy;
+use crate::DerivativeError;
+use crate::DerivativeErrorKind;
+use crate::overflow;
+use crate::standard;
+use crate::standard::Expression;
+use crate::term;
+use crate::term::Term;
+use crate::term::Subterm;
+use crate::term::TermView;
+use crate::term::arenaterm::ArenaTerm;
+use crate::term::arenaterm::ArenaNode;
+use crate::term::sequenceterm::SequenceOperations;
+
+//The highest degree of polynomial which is integrated term by term. Higher degrees are integrated
+//like any other product.
+const MAX_DEGREE : usize = 32;
+
+//Builds the antiderivative of a term into a new arena
+struct Integrator<T: Number>
+{
+
+    arena: ArenaTerm<T>
+
+}
+
+//Integrates the whole of the arena, returning a new arena with the same policies. No constant of
+//integration is added, so polynomial terms are 0 at 0 but integrated standard functions, such as
+//-cos(t) for sin(t), need not be.
+pub(crate) fn antiderivative<T: Number>(source: &ArenaTerm<T>) -> Result<ArenaTerm<T>, DerivativeError>
+{
+
+    let mut integrator = Integrator { arena: source.derived() };
+
+    let root = integrator.integrate(source.root())?;
+
+    //The root of an arena is its last node, which an antiderivative reusing an earlier node may not be
+    if root + 1 != integrator.arena.node_count()
+    {
+
+        integrator.arena.add(ArenaNode::Sequence(SequenceOperations::Addition, vec![root]));
+
+    }
+
+    return Ok(integrator.arena);
+
+}
+
+fn unsupported() -> DerivativeError
+{
+
+    return DerivativeError::new(DerivativeErrorKind::Unsupported);
+
+}
+
+//The value of a term which does not depend on t
+fn constant_value<T: Number>(term: Subterm<'_, T>) -> Option<T>
+{
+
+    if !term::is_constant(term)
+    {
+
+        return None;
+
+    }
+
+    return term.try_evaluate(T::zero()).ok();
+
+}
+
+//Adds two polynomials, given by their coefficients from the constant upwards
+fn add<T: Number>(l: Vec<T>, r: Vec<T>) -> Option<Vec<T>>
+{
+
+    let (mut long, short) = if l.len() >= r.len() { (l, r) } else { (r, l) };
+
+    for (i, c) in short.into_iter().enumerate()
+    {
+
+        long[i] = OverflowPolicy::Checked.add(long[i], c)?;
+
+    }
+
+    return Some(long);
+
+}
+
+fn multiply<T: Number>(l: Vec<T>, r: Vec<T>) -> Option<Vec<T>>
+{
+
+    if l.len() + r.len() > MAX_DEGREE + 2
+    {
+
+        return None;
+
+    }
+
+    let mut product = vec![T::zero(); l.len() + r.len() - 1];
+
+    for (i, a) in l.iter().enumerate()
+    {
+
+        for (j, b) in r.iter().enumerate()
+        {
+
+            product[i + j] = OverflowPolicy::Checked.add(product[i + j], OverflowPolicy::Checked.multiply(*a, *b)?)?;
+
+        }
+
+    }
+
+    return Some(product);
+
+}
+
+//The coefficients of the term if it is a polynomial in t, or None otherwise. Dividing by a constant
+//is only a polynomial for types which do not truncate division.
+fn polynomial<T: Number>(term: Subterm<'_, T>) -> Option<Vec<T>>
+{
+
+    if let Some(c) = constant_value(term)
+    {
+
+        return Some(vec![c]);
+
+    }
+
+    match term.to_view()
+    {
+
+        TermView::Variable => return Some(vec![T::zero(), T::one()]),
+        TermView::Sequence(SequenceOperations::Addition, terms) => return terms.into_iter().try_fold(vec![T::zero()], |sum, term| { return add(sum, polynomial(term)?); }),
+        TermView::Sequence(SequenceOperations::Multiplication, terms) => return terms.into_iter().try_fold(vec![T::one()], |product, term| { return multiply(product, polynomial(term)?); }),
+        TermView::Scalar(subterm, scale) => return multiply(vec![scale], polynomial(subterm)?),
+        TermView::Fraction(numerator, denominator) =>
+        {
+
+            let d = constant_value(denominator)?;
+
+            if overflow::is_integer::<T>() || d == T::zero()
+            {
+
+                return None;
+
+            }
+
+            return multiply(vec![T::one() / d], polynomial(numerator)?);
+
+        },
+        _ => return None
+
+    }
+
+}
+
+fn lcm(a: u128, b: u128) -> Option<u128>
+{
+
+    let (mut x, mut y) = (a, b);
+
+    while y != 0
+    {
+
+        (x, y) = (y, x % y);
+
+    }
+
+    return (a / x).checked_mul(b);
+
+}
+
+impl<T: Number> Integrator<T>
+{
+
+    fn add(&mut self, node: ArenaNode<T>) -> usize
+    {
+
+        return self.arena.add(node);
+
+    }
+
+    fn copy(&mut self, term: Subterm<'_, T>) -> Result<usize, DerivativeError>
+    {
+
+        return self.arena.copy(term).ok_or(DerivativeError::new(DerivativeErrorKind::Opaque));
+
+    }
+
+    fn sum(&mut self, nodes: Vec<usize>) -> usize
+    {
+
+        if nodes.len() == 1
+        {
+
+            return nodes[0];
+
+        }
+
+        return self.add(ArenaNode::Sequence(SequenceOperations::Addition, nodes));
+
+    }
+
+    //Adds the polynomial with the given coefficients, skipping the constant
+    fn build_polynomial(&mut self, coefficients: &[T]) -> usize
+    {
+
+        let variable = self.add(ArenaNode::Variable);
+        let mut terms = Vec::new();
+
+        for (k, c) in coefficients.iter().enumerate().skip(1)
+        {
+
+            if *c == T::zero()
+            {
+
+                continue;
+
+            }
+
+            let power = if k == 1 { variable } else { self.add(ArenaNode::Sequence(SequenceOperations::Multiplication, vec![variable; k])) };
+
+            terms.push(if *c == T::one() { power } else { self.add(ArenaNode::Scalar(power, *c)) });
+
+        }
+
+        if terms.is_empty()
+        {
+
+            return self.add(ArenaNode::Constant(T::zero()));
+
+        }
+
+        return self.sum(terms);
+
+    }
+
+    //Raises the power of every term of the polynomial. For integer types, every coefficient is put
+    //over a common denominator so that the result is only truncated once.
+    fn integrate_polynomial(&mut self, coefficients: Vec<T>) -> Result<usize, DerivativeError>
+    {
+
+        let mut raised = vec![T::zero()];
+
+        if !overflow::is_integer::<T>()
+        {
+
+            for (k, c) in coefficients.into_iter().enumerate()
+            {
+
+                raised.push(c / T::from_usize(k + 1).ok_or_else(unsupported)?);
+
+            }
+
+            return Ok(self.build_polynomial(&raised));
+
+        }
+
+        let denominator = (1..=coefficients.len() as u128).try_fold(1, lcm).ok_or_else(unsupported)?;
+
+        for (k, c) in coefficients.into_iter().enumerate()
+        {
+
+            let scale = T::from_u128(denominator / (k as u128 + 1)).ok_or_else(unsupported)?;
+
+            raised.push(OverflowPolicy::Checked.multiply(c, scale).ok_or_else(unsupported)?);
+
+        }
+
+        let numerator = self.build_polynomial(&raised);
+
+        if denominator == 1
+        {
+
+            return Ok(numerator);
+
+        }
+
+        let denominator = self.add(ArenaNode::Constant(T::from_u128(denominator).ok_or_else(unsupported)?));
+
+        return Ok(self.add(ArenaNode::Fraction(numerator, denominator)));
+
+    }
+
+    fn integrate(&mut self, term: Subterm<'_, T>) -> Result<usize, DerivativeError>
+    {
+
+        if let Some(coefficients) = polynomial(term)
+        {
+
+            return self.integrate_polynomial(coefficients);
+
+        }
+
+        match term.to_view()
+        {
+
+            TermView::Sequence(SequenceOperations::Addition, terms) =>
+            {
+
+                let mut integrated = Vec::new();
+
+                for (i, subterm) in terms.into_iter().enumerate()
+                {
+
+                    integrated.push(self.integrate(subterm).map_err(|e| { return e.within(i); })?);
+
+                }
+
+                return Ok(self.sum(integrated));
+
+            },
+            TermView::Sequence(SequenceOperations::Multiplication, terms) =>
+            {
+
+                //Only products with a single factor depending on t are supported, as integrating
+                //by parts has no general closed form
+                let variable : Vec<usize> = (0..terms.len()).filter(|i| { return !term::is_constant(terms[*i]); }).collect();
+
+                if variable.len() != 1
+                {
+
+                    return Err(unsupported());
+
+                }
+
+                let mut factors = Vec::new();
+
+                for (i, subterm) in terms.iter().enumerate()
+                {
+
+                    factors.push(if i == variable[0] { self.integrate(*subterm) } else { self.copy(*subterm) }.map_err(|e| { return e.within(i); })?);
+
+                }
+
+                return Ok(self.add(ArenaNode::Sequence(SequenceOperations::Multiplication, factors)));
+
+            },
+            TermView::Scalar(subterm, scale) =>
+            {
+
+                let integrated = self.integrate(subterm).map_err(|e| { return e.within(0); })?;
+
+                return Ok(self.add(ArenaNode::Scalar(integrated, scale)));
+
+            },
+            TermView::Fraction(numerator, denominator) =>
+            {
+
+                if !term::is_constant(denominator)
+                {
+
+                    return Err(unsupported());
+
+                }
+
+                let integrated = self.integrate(numerator).map_err(|e| { return e.within(0); })?;
+                let d = self.copy(denominator).map_err(|e| { return e.within(1); })?;
+
+                return Ok(self.add(ArenaNode::Fraction(integrated, d)));
+
+            },
+            TermView::Function(subterm, _, Some(identifier)) => return self.integrate_function(subterm, identifier),
+            TermView::Piecewise(parts, None) =>
+            {
+
+                //Each part is offset so that it starts where the previous part ended, making the
+                //whole antiderivative continuous
+                let mut integrated = Vec::new();
+                let mut offset = T::zero();
+                let mut previous : Option<usize> = None;
+
+                for (i, part) in parts.into_iter().enumerate()
+                {
+
+                    let node = self.integrate(part.0).map_err(|e| { return e.within(i); })?;
+
+                    if let Some(previous) = previous
+                    {
+
+                        let end = self.arena.try_evaluate_node(previous, part.1).map_err(|_| { return unsupported().within(i); })?;
+                        let start = self.arena.try_evaluate_node(node, part.1).map_err(|_| { return unsupported().within(i); })?;
+
+                        offset = OverflowPolicy::Checked.subtract(OverflowPolicy::Checked.add(end, offset).ok_or_else(unsupported)?, start).ok_or_else(unsupported)?;
+
+                    }
+
+                    previous = Some(node);
+
+                    let shifted = if offset == T::zero()
+                    {
+
+                        node
+
+                    }
+                    else
+                    {
+
+                        let c = self.add(ArenaNode::Constant(offset));
+
+                        self.sum(vec![node, c])
+
+                    };
+
+                    integrated.push((shifted, part.1));
+
+                }
+
+                return Ok(self.add(ArenaNode::Piecewise(integrated, None)));
+
+            },
+            TermView::Random(_, _) | TermView::Distribution(_, _) | TermView::Choice(_) => return Err(DerivativeError::new(DerivativeErrorKind::Random)),
+            TermView::Noise(_, _) => return Err(DerivativeError::new(DerivativeErrorKind::Noise)),
+            TermView::Opaque => return Err(DerivativeError::new(DerivativeErrorKind::Opaque)),
+            _ => return Err(unsupported())
+
+        }
+
+    }
+
+    //Integrates sin, cos, or exp of a linear function of t, which are assumed to be the standard
+    //functions
+    fn integrate_function(&mut self, subterm: Subterm<'_, T>, identifier: &str) -> Result<usize, DerivativeError>
+    {
+
+        let slope = match polynomial(subterm)
+        {
+
+            Some(p) if p.len() == 2 && p[1] != T::zero() => p[1],
+            _ => return Err(unsupported())
+
+        };
+
+        let argument = self.copy(subterm).map_err(|e| { return e.within(0); })?;
+
+        let integrated = match standard::find(identifier).and_then(|standard| { return standard.antiderivative; })
+        {
+
+            Some(Expression::Scaled(scale, g)) =>
+            {
+
+                let g = self.add(ArenaNode::Function(argument, standard::function(g), Some(g.to_string())));
+
+                if scale == 1.0
+                {
+
+                    g
+
+                }
+                else
+                {
+
+                    //Only negative scales cannot be represented, by unsigned types
+                    let scale = T::from_f64(scale).ok_or(DerivativeError::new(DerivativeErrorKind::Unsigned))?;
+
+                    self.add(ArenaNode::Scalar(g, scale))
+
+                }
+
+            },
+            _ => return Err(unsupported())
+
+        };
+
+        if slope == T::one()
+        {
+
+            return Ok(integrated);
+
+        }
+
+        let slope = self.add(ArenaNode::Constant(slope));
+
+        return Ok(self.add(ArenaNode::Fraction(integrated, slope)));
+
+    }
+
+}
+
+#[cfg(test)]
+mod antiderivative_tests
+{
+
+    use crate::Parametrizer;
+    use crate::ParametrizerFunction;
+    use crate::IntegrationMethod;
+
+    #[test]
+    fn test_antiderivative_matches_integral ()
+    {
+
+        let cases = ["5", "t*t*t + 2*t", "-3*t*t", "t/4", "4*(t+1)*(t-1)", "sin(2*t)", "3*cos(t/2 - 1)", "exp(-t)", "2*exp(3*t)/5 + t", "p t*t>0|3-t>2|sin(t)>4"];
+
+        for case in cases.iter()
+        {
+
+            let functions = vec![
+
+                ParametrizerFunction::new("sin".to_string(), f64::sin),
+                ParametrizerFunction::new("cos".to_string(), f64::cos),
+                ParametrizerFunction::new("exp".to_string(), f64::exp)
+
+            ];
+
+            let function = Parametrizer::<f64>::new_functions(case, functions).unwrap();
+            let antiderivative = function.antiderivative().unwrap_or_else(|e| { panic!("Failed to integrate {}: {}", case, e); });
+
+            for i in 0..20
+            {
+
+                let t = 0.3 + f64::from(i) * 0.3;
+                let expected = function.integrate(0.3, t, IntegrationMethod::GaussKronrod { tolerance: 1e-12, max_depth: 16 }).unwrap();
+                let computed = antiderivative.evaluate(t) - antiderivative.evaluate(0.3);
+
+                assert!((computed - expected.value).abs() < 1e-8, "Antiderivative of {} at {} gave {} rather than {}", case, t, computed, expected.value);
+
+            }
+
+        }
+
+    }
+
+}
@@ -0,0 +1,298 @@
+use crate::Number;
+use crate::EvalError;
+use crate::EvalErrorKind;
+use crate::dual;
+use crate::term::Term;
+use crate::term::Subterm;
+use crate::term::TermView;
+use crate::term::piecewiseterm;
+
+//The tolerance used by Policy::require_smoothness
+pub(crate) const POLICY_TOLERANCE : f64 = 1e-9;
+//The smallest relative tolerance for slopes, which are approximated by central differences
+const SLOPE_TOLERANCE : f64 = 1e-6;
+
+///How smooth a function is at a point. Continuity of the values is called C0, and continuity of
+///both the values and slopes is called C1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Smoothness
+{
+
+    ///The values are continuous
+    C0,
+    ///The values and slopes are continuous
+    C1
+
+}
+
+///A point where a piecewise term is not smooth, found by Parametrizer::discontinuities
+#[derive(Debug, Clone, PartialEq)]
+pub struct Discontinuity
+{
+
+    ///The smoothness which is broken, i.e. C0 if the values jump, or C1 if the values are
+    ///continuous but the slopes jump
+    pub kind: Smoothness,
+    ///The path to the piecewise term, given in the same way as for EvalError
+    pub path: Vec<usize>,
+    ///The input of the piecewise term at which it is not smooth. This is t unless the term is
+    ///within another piecewise term which loops.
+    pub at: f64,
+    ///The value or slope approaching from below
+    pub left: f64,
+    ///The value or slope approaching from above
+    pub right: f64
+
+}
+
+impl Discontinuity
+{
+
+    ///The size of the jump in the value or slope
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    ///
+    /// let step = Parametrizer::<f64>::new("p t>0|t+2>1").unwrap();
+    ///
+    /// assert_eq!(2.0, step.discontinuities(1e-9).unwrap()[0].magnitude());
+    /// ```
+    pub fn magnitude(&self) -> f64
+    {
+
+        return (self.right - self.left).abs();
+
+    }
+
+}
+
+//Finds every breakpoint of every piecewise term where the parts on either side differ in value or
+//slope by more than the tolerance, relative to the larger of 1 and the size of the values
+pub(crate) fn discontinuities<T: Number>(term: Subterm<'_, T>, tolerance: f64) -> Result<Vec<Discontinuity>, EvalError>
+{
+
+    let mut found = Vec::new();
+
+    visit(term, tolerance, &mut Vec::new(), &mut found)?;
+
+    return Ok(found);
+
+}
+
+fn visit<T: Number>(term: Subterm<'_, T>, tolerance: f64, path: &mut Vec<usize>, found: &mut Vec<Discontinuity>) -> Result<(), EvalError>
+{
+
+    let view = term.to_view();
+
+    if let TermView::Piecewise(parts, cycle) = &view
+    {
+
+        check(parts, *cycle, tolerance, path, found).map_err(|mut e|
+        {
+
+            for index in path.iter().rev()
+            {
+
+                e = e.within(*index);
+
+            }
+
+            return e;
+
+        })?;
+
+    }
+
+    for (i, subterm) in view.subterms().into_iter().enumerate()
+    {
+
+        path.push(i);
+        visit(subterm, tolerance, path, found)?;
+        path.pop();
+
+    }
+
+    return Ok(());
+
+}
+
+//Compares the parts on either side of each after value, and of the seam where a looping term
+//returns from its loop value to 0
+fn check<T: Number>(parts: &[(Subterm<'_, T>, T)], cycle: Option<T>, tolerance: f64, path: &[usize], found: &mut Vec<Discontinuity>) -> Result<(), EvalError>
+{
+
+    let afters = || { return parts.iter().map(|part| { return part.1; }); };
+
+    let mut points = Vec::new();
+
+    for part in parts.iter().skip(1)
+    {
+
+        if !points.iter().any(|p: &(T, T)| { return p.0 == part.1; })
+        {
+
+            points.push((part.1, part.1));
+
+        }
+
+    }
+
+    if let Some(c) = cycle.filter(|c| { return *c > T::zero(); })
+    {
+
+        //Just after the loop value, t wraps to just after 0
+        points.push((c, T::zero()));
+
+    }
+
+    for (before, after) in points
+    {
+
+        let (left, right) = match (piecewiseterm::select_before(afters(), before), piecewiseterm::select(afters(), after))
+        {
+
+            (Some(left), Some(right)) => (left, right),
+            _ => continue
+
+        };
+
+        //Parts are compared using their own expressions at the breakpoint, so the same part on
+        //both sides is continuous unless it loops, and random values cannot be compared
+        if (left == right && cycle.is_none()) || is_random(parts[left].0) || is_random(parts[right].0)
+        {
+
+            continue;
+
+        }
+
+        let at = before.to_f64().ok_or(EvalError::new(EvalErrorKind::FunctionConversion))?;
+
+        let l = value(parts[left].0, before).map_err(|e| { return e.within(left); })?;
+        let r = value(parts[right].0, after).map_err(|e| { return e.within(right); })?;
+
+        if differ(l, r, tolerance)
+        {
+
+            found.push(Discontinuity { kind: Smoothness::C0, path: path.to_vec(), at, left: l, right: r });
+
+            continue;
+
+        }
+
+        let l = slope(parts[left].0, before)?;
+        let r = slope(parts[right].0, after)?;
+
+        //Slopes which cannot be approximated, e.g. at the end of a function's domain, are skipped
+        if l.is_finite() && r.is_finite() && differ(l, r, tolerance.max(SLOPE_TOLERANCE))
+        {
+
+            found.push(Discontinuity { kind: Smoothness::C1, path: path.to_vec(), at, left: l, right: r });
+
+        }
+
+    }
+
+    return Ok(());
+
+}
+
+//Values which are not finite, e.g. NaN, always differ
+fn differ(l: f64, r: f64, tolerance: f64) -> bool
+{
+
+    let difference = (l - r).abs();
+
+    return difference.is_nan() || difference > tolerance * l.abs().max(r.abs()).max(1.0);
+
+}
+
+fn value<T: Number>(term: Subterm<'_, T>, t: T) -> Result<f64, EvalError>
+{
+
+    return term.try_evaluate(t)?.to_f64().ok_or(EvalError::new(EvalErrorKind::FunctionConversion));
+
+}
+
+fn slope<T: Number>(term: Subterm<'_, T>, t: T) -> Result<f64, EvalError>
+{
+
+    let x = t.to_f64().ok_or(EvalError::new(EvalErrorKind::FunctionConversion))?;
+
+    return Ok(dual::slope::<T, _>(|x| { return T::from_f64(x).and_then(|x| { return term.try_evaluate(x).ok(); }).and_then(|y| { return y.to_f64(); }).unwrap_or(f64::NAN); }, x));
+
+}
+
+//Whether the term contains a random value, distribution, or choice
+fn is_random<T: Number>(term: Subterm<'_, T>) -> bool
+{
+
+    let view = term.to_view();
+
+    if let TermView::Random(_, _) | TermView::Distribution(_, _) | TermView::Choice(_) = view
+    {
+
+        return true;
+
+    }
+
+    return view.subterms().into_iter().any(is_random);
+
+}
+
+#[cfg(test)]
+mod continuity_tests
+{
+
+    use crate::Parametrizer;
+    use crate::ParametrizerFunction;
+    use crate::Policy;
+    use crate::ParseErrorKind;
+    use super::Smoothness;
+
+    #[test]
+    fn test_discontinuities ()
+    {
+
+        //Smooth at 1, with a kink at 2 and a jump of 0.5 at 3
+        let curve = Parametrizer::<f64>::new("p t*t>0|2*t - 1>1|5 - t>2|t*t - 4*t + 5.5>3").unwrap();
+        let found = curve.discontinuities(1e-9).unwrap();
+
+        assert_eq!(2, found.len());
+        assert_eq!((Smoothness::C1, 2.0), (found[0].kind, found[0].at));
+        assert!((found[0].left - 2.0).abs() < 1e-6 && (found[0].right + 1.0).abs() < 1e-6);
+        assert_eq!((Smoothness::C0, 3.0, 0.5), (found[1].kind, found[1].at, found[1].magnitude()));
+
+        //The seam of a loop returns from 2 to 0
+        let saw = Parametrizer::<f64>::new("p[2]t>0").unwrap();
+        let found = saw.discontinuities(1e-9).unwrap();
+
+        assert_eq!(1, found.len());
+        assert_eq!((2.0, 2.0, 0.0), (found[0].at, found[0].left, found[0].right));
+
+        let smooth = Parametrizer::<f64>::new_functions("p[6.283185307179586]sin(t)>0", vec![ParametrizerFunction::new("sin".to_string(), f64::sin)]).unwrap();
+
+        assert!(smooth.discontinuities(1e-9).unwrap().is_empty());
+
+        //Random parts cannot be compared
+        assert!(Parametrizer::<f64>::new("p rd(0<1)>0|5>1").unwrap().discontinuities(1e-9).unwrap().is_empty());
+
+    }
+
+    #[test]
+    fn test_policy ()
+    {
+
+        let continuous = Policy { require_smoothness: Some(Smoothness::C0), ..Policy::default() };
+        let smooth = Policy { require_smoothness: Some(Smoothness::C1), ..Policy::default() };
+
+        let kinked = "p t>0|2*t - 1>1";
+
+        assert!(Parametrizer::<f64>::with_policy(kinked, Vec::new(), &continuous).is_ok());
+        assert_eq!(ParseErrorKind::Discontinuous, Parametrizer::<f64>::with_policy(kinked, Vec::new(), &smooth).err().unwrap().kind());
+        assert_eq!(ParseErrorKind::Discontinuous, Parametrizer::<i32>::with_policy("p t>0|t+1>5", Vec::new(), &continuous).err().unwrap().kind());
+
+    }
+
+}
@@ -0,0 +1,589 @@
+use std::fmt;
+
+use crate::Number;
+use crate::overflow;
+use crate::standard;
+use crate::standard::Expression;
+use crate::term::Subterm;
+use crate::term::TermView;
+use crate::term::arenaterm::ArenaTerm;
+use crate::term::arenaterm::ArenaNode;
+use crate::term::sequenceterm::SequenceOperations;
+
+///The reason why a function could not be differentiated or integrated symbolically
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DerivativeErrorKind
+{
+
+    ///The function contains a random value, distribution, or choice, which has no derivative or
+    ///antiderivative
+    Random,
+    ///The function contains noise, whose derivative and antiderivative are not supported
+    Noise,
+    ///The function applies a function which is neither one of the standard functions nor was given
+    ///a derivative with ParametrizerFunction::with_derivative
+    UnknownFunction,
+    ///The function contains a user-defined term, whose structure is unknown
+    Opaque,
+    ///The result requires negation, which is impossible for unsigned types
+    Unsigned,
+    ///The term has no antiderivative which can be written as a function
+    Unsupported
+
+}
+
+impl fmt::Display for DerivativeErrorKind
+{
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+
+        let reason = match self
+        {
+
+            DerivativeErrorKind::Random => "Random values cannot be differentiated or integrated",
+            DerivativeErrorKind::Noise => "Noise cannot be differentiated or integrated",
+            DerivativeErrorKind::UnknownFunction => "The derivative of the function is unknown",
+            DerivativeErrorKind::Opaque => "User-defined terms cannot be differentiated or integrated",
+            DerivativeErrorKind::Unsigned => "The result requires negation, which is impossible for unsigned types",
+            DerivativeErrorKind::Unsupported => "The antiderivative cannot be written as a function"
+
+        };
+
+        return write!(f, "{}", reason);
+
+    }
+
+}
+
+///An error which describes why differentiation or symbolic integration failed. Contains the kind of
+///failure as well as the path to the term which could not be differentiated or integrated, given
+///in the same way as for EvalError.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivativeError
+{
+
+    kind: DerivativeErrorKind,
+    path: Vec<usize>
+
+}
+
+impl DerivativeError
+{
+
+    pub(crate) fn new(kind: DerivativeErrorKind) -> DerivativeError
+    {
+
+        return DerivativeError { kind, path: Vec::new() };
+
+    }
+
+    pub(crate) fn within(mut self, index: usize) -> DerivativeError
+    {
+
+        self.path.insert(0, index);
+
+        return self;
+
+    }
+
+    ///Returns the kind of failure
+    pub fn kind(&self) -> DerivativeErrorKind
+    {
+
+        return self.kind;
+
+    }
+
+    ///Returns the path from the top-level term to the term which could not be differentiated or
+    ///integrated
+    pub fn path(&self) -> &[usize]
+    {
+
+        return &self.path;
+
+    }
+
+}
+
+impl fmt::Display for DerivativeError
+{
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+
+        return write!(f, "Parametrizer failed to differentiate or integrate term at path: {:?}, with failure reason: {}", self.path, self.kind);
+
+    }
+
+}
+
+//A derivative, which is kept as 0 or 1 when possible to avoid building needless terms
+#[derive(Clone, Copy, PartialEq)]
+enum Derived
+{
+
+    Zero,
+    One,
+    Node(usize)
+
+}
+
+//Builds the derivative of a term into a new arena
+struct Differentiator<T: Number>
+{
+
+    arena: ArenaTerm<T>
+
+}
+
+//Differentiates the whole of the arena, returning a new arena with the same policies
+pub(crate) fn differentiate<T: Number>(source: &ArenaTerm<T>) -> Result<ArenaTerm<T>, DerivativeError>
+{
+
+    let mut differentiator = Differentiator { arena: source.derived() };
+
+    let derived = differentiator.derive(source.root())?;
+    let root = differentiator.node(derived);
+
+    //The root of an arena is its last node, which a derivative reusing an earlier node may not be
+    if root + 1 != differentiator.arena.node_count()
+    {
+
+        differentiator.arena.add(ArenaNode::Sequence(SequenceOperations::Addition, vec![root]));
+
+    }
+
+    return Ok(differentiator.arena);
+
+}
+
+impl<T: Number> Differentiator<T>
+{
+
+    fn add(&mut self, node: ArenaNode<T>) -> usize
+    {
+
+        return self.arena.add(node);
+
+    }
+
+    fn copy(&mut self, term: Subterm<'_, T>) -> Result<usize, DerivativeError>
+    {
+
+        return self.arena.copy(term).ok_or(DerivativeError::new(DerivativeErrorKind::Opaque));
+
+    }
+
+    //Returns the node for a derivative, adding a constant node for 0 or 1
+    fn node(&mut self, derived: Derived) -> usize
+    {
+
+        match derived
+        {
+
+            Derived::Zero => return self.add(ArenaNode::Constant(T::zero())),
+            Derived::One => return self.add(ArenaNode::Constant(T::one())),
+            Derived::Node(n) => return n
+
+        }
+
+    }
+
+    fn negative_one(&self) -> Result<T, DerivativeError>
+    {
+
+        if overflow::is_unsigned::<T>()
+        {
+
+            return Err(DerivativeError::new(DerivativeErrorKind::Unsigned));
+
+        }
+
+        return Ok(T::zero() - T::one());
+
+    }
+
+    //Adds up the terms, skipping any which are 0
+    fn sum(&mut self, terms: Vec<Derived>) -> Derived
+    {
+
+        let terms : Vec<Derived> = terms.into_iter().filter(|d| { return *d != Derived::Zero; }).collect();
+
+        if terms.len() <= 1
+        {
+
+            return terms.first().copied().unwrap_or(Derived::Zero);
+
+        }
+
+        let nodes = terms.into_iter().map(|d| { return self.node(d); }).collect();
+
+        return Derived::Node(self.add(ArenaNode::Sequence(SequenceOperations::Addition, nodes)));
+
+    }
+
+    //Multiplies the factors, skipping any which are 1
+    fn product(&mut self, factors: Vec<Derived>) -> Derived
+    {
+
+        if factors.contains(&Derived::Zero)
+        {
+
+            return Derived::Zero;
+
+        }
+
+        let nodes : Vec<usize> = factors.into_iter().filter_map(|d| { return if let Derived::Node(n) = d { Some(n) } else { None }; }).collect();
+
+        match nodes.len()
+        {
+
+            0 => return Derived::One,
+            1 => return Derived::Node(nodes[0]),
+            _ => return Derived::Node(self.add(ArenaNode::Sequence(SequenceOperations::Multiplication, nodes)))
+
+        }
+
+    }
+
+    fn function(&mut self, argument: usize, function: fn(f64) -> f64, identifier: &str) -> usize
+    {
+
+        return self.add(ArenaNode::Function(argument, function, Some(identifier.to_string())));
+
+    }
+
+    //The derivatives of the standard functions, which are assumed for functions with these
+    //identifiers when no derivative was given. Returns None for any other identifier.
+    fn standard(&mut self, identifier: &str, argument: usize) -> Result<Option<usize>, DerivativeError>
+    {
+
+        return match standard::find(identifier)
+        {
+
+            Some(standard) => Ok(Some(self.expression(standard.derivative, argument)?)),
+            None => Ok(None)
+
+        };
+
+    }
+
+    //Adds the nodes of an expression of the argument
+    fn expression(&mut self, expression: Expression, argument: usize) -> Result<usize, DerivativeError>
+    {
+
+        match expression
+        {
+
+            Expression::Scaled(scale, g) =>
+            {
+
+                let g = self.function(argument, standard::function(g), g);
+
+                return self.scale(g, scale);
+
+            },
+            Expression::Reciprocal(scale, g, power) =>
+            {
+
+                let one = self.add(ArenaNode::Constant(T::one()));
+                let base = match g
+                {
+
+                    Some(g) => self.function(argument, standard::function(g), g),
+                    None => argument
+
+                };
+
+                let power = if power == 1 { base } else { self.add(ArenaNode::Sequence(SequenceOperations::Multiplication, vec![base; power as usize])) };
+                let denominator = self.scale(power, scale)?;
+
+                return Ok(self.add(ArenaNode::Fraction(one, denominator)));
+
+            }
+
+        }
+
+    }
+
+    //Scales the node by one of the small integers used by expressions
+    fn scale(&mut self, node: usize, scale: f64) -> Result<usize, DerivativeError>
+    {
+
+        if scale == 1.0
+        {
+
+            return Ok(node);
+
+        }
+
+        //Only negative scales cannot be represented, by unsigned types
+        let scale = T::from_f64(scale).ok_or(DerivativeError::new(DerivativeErrorKind::Unsigned))?;
+
+        return Ok(self.add(ArenaNode::Scalar(node, scale)));
+
+    }
+
+    fn derive(&mut self, term: Subterm<'_, T>) -> Result<Derived, DerivativeError>
+    {
+
+        match term.to_view()
+        {
+
+            TermView::Constant(_) => return Ok(Derived::Zero),
+            TermView::Variable => return Ok(Derived::One),
+            TermView::Sequence(SequenceOperations::Addition, terms) =>
+            {
+
+                let mut derived = Vec::new();
+
+                for (i, subterm) in terms.into_iter().enumerate()
+                {
+
+                    derived.push(self.derive(subterm).map_err(|e| { return e.within(i); })?);
+
+                }
+
+                return Ok(self.sum(derived));
+
+            },
+            TermView::Sequence(SequenceOperations::Multiplication, terms) =>
+            {
+
+                //The product rule: the sum over every factor of its derivative times the others
+                let mut derived = Vec::new();
+
+                for (i, subterm) in terms.iter().enumerate()
+                {
+
+                    derived.push(self.derive(*subterm).map_err(|e| { return e.within(i); })?);
+
+                }
+
+                let mut products = Vec::new();
+
+                for (i, d) in derived.into_iter().enumerate()
+                {
+
+                    if d == Derived::Zero
+                    {
+
+                        continue;
+
+                    }
+
+                    let mut factors = Vec::new();
+
+                    for (j, subterm) in terms.iter().enumerate()
+                    {
+
+                        factors.push(if i == j { d } else { Derived::Node(self.copy(*subterm).map_err(|e| { return e.within(j); })?) });
+
+                    }
+
+                    products.push(self.product(factors));
+
+                }
+
+                return Ok(self.sum(products));
+
+            },
+            TermView::Scalar(subterm, scale) =>
+            {
+
+                match self.derive(subterm).map_err(|e| { return e.within(0); })?
+                {
+
+                    Derived::Zero => return Ok(Derived::Zero),
+                    Derived::One => return Ok(Derived::Node(self.add(ArenaNode::Constant(scale)))),
+                    Derived::Node(n) => return Ok(Derived::Node(self.add(ArenaNode::Scalar(n, scale))))
+
+                }
+
+            },
+            TermView::Fraction(numerator, denominator) =>
+            {
+
+                let dn = self.derive(numerator).map_err(|e| { return e.within(0); })?;
+                let dd = self.derive(denominator).map_err(|e| { return e.within(1); })?;
+
+                if dd == Derived::Zero
+                {
+
+                    if dn == Derived::Zero
+                    {
+
+                        return Ok(Derived::Zero);
+
+                    }
+
+                    let dn = self.node(dn);
+                    let d = self.copy(denominator).map_err(|e| { return e.within(1); })?;
+
+                    return Ok(Derived::Node(self.add(ArenaNode::Fraction(dn, d))));
+
+                }
+
+                //The quotient rule: (n'd - nd') / d^2
+                let scale = self.negative_one()?;
+
+                let d = self.copy(denominator).map_err(|e| { return e.within(1); })?;
+                let n = self.copy(numerator).map_err(|e| { return e.within(0); })?;
+
+                let left = self.product(vec![dn, Derived::Node(d)]);
+                let right = self.product(vec![Derived::Node(n), dd]);
+                let right = self.node(right);
+                let right = self.add(ArenaNode::Scalar(right, scale));
+
+                let top = self.sum(vec![left, Derived::Node(right)]);
+                let top = self.node(top);
+                let square = self.add(ArenaNode::Sequence(SequenceOperations::Multiplication, vec![d, d]));
+
+                return Ok(Derived::Node(self.add(ArenaNode::Fraction(top, square))));
+
+            },
+            TermView::Function(subterm, _, identifier) =>
+            {
+
+                //The chain rule: f'(g) * g'
+                let inner = self.derive(subterm).map_err(|e| { return e.within(0); })?;
+
+                if inner == Derived::Zero
+                {
+
+                    return Ok(Derived::Zero);
+
+                }
+
+                let argument = self.copy(subterm).map_err(|e| { return e.within(0); })?;
+
+                let outer = match identifier
+                {
+
+                    Some(i) => match self.arena.function_derivative(i)
+                    {
+
+                        Some(derivative) => Some(self.add(ArenaNode::Function(argument, derivative, None))),
+                        None => self.standard(i, argument)?
+
+                    },
+                    None => None
+
+                };
+
+                match outer
+                {
+
+                    Some(outer) => return Ok(self.product(vec![Derived::Node(outer), inner])),
+                    None => return Err(DerivativeError::new(DerivativeErrorKind::UnknownFunction))
+
+                }
+
+            },
+            TermView::Piecewise(parts, cycle) =>
+            {
+
+                //Each part is differentiated separately, ignoring any jumps between parts
+                let mut derived = Vec::new();
+
+                for (i, part) in parts.into_iter().enumerate()
+                {
+
+                    let d = self.derive(part.0).map_err(|e| { return e.within(i); })?;
+
+                    derived.push((self.node(d), part.1));
+
+                }
+
+                return Ok(Derived::Node(self.add(ArenaNode::Piecewise(derived, cycle))));
+
+            },
+            TermView::Random(_, _) | TermView::Distribution(_, _) | TermView::Choice(_) => return Err(DerivativeError::new(DerivativeErrorKind::Random)),
+            TermView::Noise(_, _) => return Err(DerivativeError::new(DerivativeErrorKind::Noise)),
+            TermView::Opaque => return Err(DerivativeError::new(DerivativeErrorKind::Opaque))
+
+        }
+
+    }
+
+}
+
+#[cfg(test)]
+mod derivative_tests
+{
+
+    use crate::Parametrizer;
+    use crate::ParametrizerFunction;
+    use super::DerivativeErrorKind;
+
+    fn functions() -> Vec<ParametrizerFunction>
+    {
+
+        return vec![
+
+            ParametrizerFunction::new("sin".to_string(), f64::sin),
+            ParametrizerFunction::new("cos".to_string(), f64::cos),
+            ParametrizerFunction::new("tan".to_string(), f64::tan),
+            ParametrizerFunction::new("exp".to_string(), f64::exp),
+            ParametrizerFunction::new("ln".to_string(), f64::ln),
+            ParametrizerFunction::new("sqrt".to_string(), f64::sqrt),
+            ParametrizerFunction::new("cbrt".to_string(), f64::cbrt)
+
+        ];
+
+    }
+
+    #[test]
+    fn test_matches_finite_differences ()
+    {
+
+        let cases = ["t", "5", "t*t*t + 2*t", "-3*t*t", "sin(t)/(t*t+2)", "4/t", "t/4", "sin(2*t)*cos(t)", "tan(t/3)", "exp(-t*t)", "ln(t*t+1)", "sqrt(t+2)", "sin(cos(t))/exp(t)", "p[5]t*t>0|3-t>2"];
+
+        for case in cases.iter()
+        {
+
+            let function = Parametrizer::<f64>::new_functions(case, functions()).unwrap();
+            let derivative = function.derivative().unwrap_or_else(|e| { panic!("Failed to differentiate {}: {}", case, e); });
+
+            for i in 0..20
+            {
+
+                //Avoid the boundaries between piecewise parts
+                let t = 0.3 + f64::from(i) * 0.23;
+                let h = 1e-6;
+
+                let expected = (function.evaluate(t + h) - function.evaluate(t - h)) / (2.0 * h);
+                let (value, slope) = function.evaluate_with_derivative(t);
+
+                assert!((derivative.evaluate(t) - expected).abs() < 1e-5, "Derivative of {} at {} was {} rather than {}", case, t, derivative.evaluate(t), expected);
+                assert!((slope - derivative.evaluate(t)).abs() < 1e-9, "Dual derivative of {} at {} was {} rather than {}", case, t, slope, derivative.evaluate(t));
+                assert_eq!(function.evaluate(t), value);
+
+            }
+
+        }
+
+    }
+
+    #[test]
+    fn test_failures ()
+    {
+
+        let unknown = Parametrizer::<f64>::new_functions("1 + cbrt(t)", functions()).unwrap();
+        let constant = Parametrizer::<f64>::new_functions("cbrt(2) * t", functions()).unwrap();
+        let unsigned = Parametrizer::<u32>::new_functions("t*t + 2/t", functions()).unwrap();
+        let noise = Parametrizer::<f64>::new("t*noise(t)").unwrap();
+
+        let error = unknown.derivative().err().unwrap();
+
+        assert_eq!(DerivativeErrorKind::UnknownFunction, error.kind());
+        assert_eq!(&[1], error.path());
+
+        assert_eq!(Ok(2.0_f64.cbrt()), constant.derivative().map(|d| { return d.evaluate(1.0); }).map_err(|e| { return e.kind(); }));
+        assert_eq!(DerivativeErrorKind::Unsigned, unsigned.derivative().err().unwrap().kind());
+        assert_eq!(&[1], noise.derivative().err().unwrap().path());
+
+    }
+
+}
@@ -0,0 +1,166 @@
+use std::fmt;
+
+use crate::Interval;
+
+///The kind of possible failure found by Parametrizer::analyze
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticKind
+{
+
+    ///The denominator of a fraction, or the loop value of a piecewise term, may be 0
+    DivisionByZero,
+    ///The minimum of a random term may not be less than its maximum
+    RandomRange,
+    ///A function may be applied outside of its domain, as declared with
+    ///ParametrizerFunction::with_domain or known for the standard functions ln and sqrt
+    Domain
+
+}
+
+impl fmt::Display for DiagnosticKind
+{
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+
+        let reason = match self
+        {
+
+            DiagnosticKind::DivisionByZero => "May divide by 0",
+            DiagnosticKind::RandomRange => "Minimum may not be smaller than maximum when generating a random value",
+            DiagnosticKind::Domain => "May apply a function outside of its domain"
+
+        };
+
+        return write!(f, "{}", reason);
+
+    }
+
+}
+
+///A possible failure found by Parametrizer::analyze. Contains the kind of failure, the path to the
+///term which may fail, given in the same way as for EvalError, and an interval containing the
+///values responsible:
+///- for DiagnosticKind::DivisionByZero, the values of the denominator or loop value
+///- for DiagnosticKind::RandomRange, the values of the maximum minus the minimum
+///- for DiagnosticKind::Domain, the values the function is applied to
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic
+{
+
+    kind: DiagnosticKind,
+    path: Vec<usize>,
+    values: Interval
+
+}
+
+impl Diagnostic
+{
+
+    pub(crate) fn new(kind: DiagnosticKind, path: Vec<usize>, values: Interval) -> Diagnostic
+    {
+
+        return Diagnostic { kind, path, values };
+
+    }
+
+    //Widens the values of the diagnostic, for a term which may fail for several inputs
+    pub(crate) fn include(&mut self, values: Interval)
+    {
+
+        self.values = self.values.union(values);
+
+    }
+
+    ///Returns the kind of possible failure
+    pub fn kind(&self) -> DiagnosticKind
+    {
+
+        return self.kind;
+
+    }
+
+    ///Returns the path from the top-level term to the term which may fail
+    pub fn path(&self) -> &[usize]
+    {
+
+        return &self.path;
+
+    }
+
+    ///Returns an interval containing the values responsible for the possible failure
+    pub fn values(&self) -> Interval
+    {
+
+        return self.values;
+
+    }
+
+}
+
+impl fmt::Display for Diagnostic
+{
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+
+        return write!(f, "Parametrizer found a possible failure of term at path: {:?}, with values between {} and {}, and reason: {}", self.path, self.values.min, self.values.max, self.kind);
+
+    }
+
+}
+
+#[cfg(test)]
+mod domain_tests
+{
+
+    use crate::Parametrizer;
+    use crate::ParametrizerFunction;
+    use super::DiagnosticKind;
+
+    #[test]
+    fn test_diagnostics ()
+    {
+
+        let functions = || { return vec![ParametrizerFunction::new("ln".to_string(), f64::ln), ParametrizerFunction::new("sqrt".to_string(), f64::sqrt)]; };
+
+        let log = Parametrizer::new_functions("ln(t - 1)", functions()).unwrap();
+        let diagnostics = log.analyze(0.0, 3.0).unwrap();
+
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(DiagnosticKind::Domain, diagnostics[0].kind());
+        assert_eq!(&[] as &[usize], diagnostics[0].path());
+        //Only the parts of the range where the function may fail are included
+        assert!(diagnostics[0].values().contains(-1.0) && !diagnostics[0].values().contains(1.0));
+        assert!(log.analyze(1.5, 3.0).unwrap().is_empty());
+
+        //t*t - t + 1 is at least 0.75, which is only found by splitting the range
+        let root = Parametrizer::new_functions("sqrt(t*t - t + 1)", functions()).unwrap();
+
+        assert!(root.analyze(-5.0, 5.0).unwrap().is_empty());
+
+        let pole = Parametrizer::new_functions("2 + sqrt(t)/(t - 3)", functions()).unwrap();
+        let diagnostics = pole.analyze(0.0, 5.0).unwrap();
+
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(DiagnosticKind::DivisionByZero, diagnostics[0].kind());
+        assert_eq!(&[1], diagnostics[0].path());
+
+    }
+
+    #[test]
+    fn test_integers ()
+    {
+
+        //t*t is never 2 for integers, which is found by splitting the range down to single values
+        let integer = Parametrizer::<i32>::new("10/(t*t - 2) + rd(t<10)").unwrap();
+        let diagnostics = integer.analyze(-5, 12).unwrap();
+
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(DiagnosticKind::RandomRange, diagnostics[0].kind());
+        assert_eq!(&[1], diagnostics[0].path());
+        assert_eq!((-2.0, 0.0), (diagnostics[0].values().min, diagnostics[0].values().max));
+
+    }
+
+}
@@ -0,0 +1,480 @@
+use std::ops::Add;
+use std::ops::Sub;
+use std::ops::Mul;
+use std::ops::Div;
+use std::ops::Rem;
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use num::Num;
+use num::Zero;
+use num::One;
+use num::Bounded;
+use num::ToPrimitive;
+use num::FromPrimitive;
+
+use crate::Number;
+use crate::OverflowPolicy;
+use crate::overflow;
+
+///A dual number, pairing a value with its derivative with respect to some variable. Arithmetic on
+///dual numbers applies the rules of differentiation, so evaluating a function with
+///Dual::variable(t) computes both its value and its derivative at t in a single pass. This is known
+///as forward-mode automatic differentiation.
+///
+///Dual numbers satisfy Number, so can be used with any term. Comparisons only consider the value,
+///so piecewise terms select the same parts as they would for the value alone. Functions applied by
+///FunctionTerm are computed through f64, and integer arithmetic is checked for overflow through
+///i128, both of which lose the derivative, so Parametrizer::evaluate_with_derivative should be used
+///for functions containing them or for integer types.
+///
+/// # Examples
+///
+/// ```
+/// use crate::parametrizer::Parametrizer;
+/// use crate::parametrizer::Dual;
+///
+/// let cubic = Parametrizer::<Dual<f64>>::new("t*t*t - 4/t").unwrap();
+///
+/// let computed = cubic.evaluate(Dual::variable(2.0));
+///
+/// assert_eq!(6.0, computed.value());
+/// assert_eq!(13.0, computed.derivative());
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Dual<T: Number>
+{
+
+    value: T,
+    derivative: T
+
+}
+
+impl<T: Number> Dual<T>
+{
+
+    ///Creates a dual number with the given value and derivative
+    pub fn new(value: T, derivative: T) -> Dual<T>
+    {
+
+        return Dual { value, derivative };
+
+    }
+
+    ///Creates a dual number for a constant, whose derivative is 0
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::Dual;
+    ///
+    /// assert_eq!(0, Dual::constant(5).derivative());
+    /// ```
+    pub fn constant(value: T) -> Dual<T>
+    {
+
+        return Dual::new(value, T::zero());
+
+    }
+
+    ///Creates a dual number for the variable being differentiated with respect to, whose derivative
+    ///is 1
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::Dual;
+    ///
+    /// let t = Dual::variable(3);
+    /// let square = t * t;
+    ///
+    /// assert_eq!(9, square.value());
+    /// assert_eq!(6, square.derivative());
+    /// ```
+    pub fn variable(value: T) -> Dual<T>
+    {
+
+        return Dual::new(value, T::one());
+
+    }
+
+    ///The value of the dual number
+    pub fn value(&self) -> T
+    {
+
+        return self.value;
+
+    }
+
+    ///The derivative of the dual number
+    pub fn derivative(&self) -> T
+    {
+
+        return self.derivative;
+
+    }
+
+    //Adds or multiplies like SequenceOperations::compound, returning None if any part overflows
+    pub(crate) fn add_with(self, other: Dual<T>, policy: OverflowPolicy) -> Option<Dual<T>>
+    {
+
+        return Some(Dual::new(policy.add(self.value, other.value)?, policy.add(self.derivative, other.derivative)?));
+
+    }
+
+    pub(crate) fn multiply_with(self, other: Dual<T>, policy: OverflowPolicy) -> Option<Dual<T>>
+    {
+
+        let left = policy.multiply(self.derivative, other.value)?;
+        let right = policy.multiply(self.value, other.derivative)?;
+
+        return Some(Dual::new(policy.multiply(self.value, other.value)?, policy.add(left, right)?));
+
+    }
+
+    pub(crate) fn scale_with(self, scale: T, policy: OverflowPolicy) -> Option<Dual<T>>
+    {
+
+        return Some(Dual::new(policy.multiply(scale, self.value)?, policy.multiply(scale, self.derivative)?));
+
+    }
+
+    //Divides by a denominator with a nonzero value. The derivative is (n' - q * d') / d, where q is
+    //the quotient, which avoids squaring the denominator.
+    pub(crate) fn divide_with(self, other: Dual<T>, policy: OverflowPolicy) -> Option<Dual<T>>
+    {
+
+        let quotient = policy.divide(self.value, other.value)?;
+        let change = policy.subtract(self.derivative, policy.multiply(quotient, other.derivative)?)?;
+
+        return Some(Dual::new(quotient, policy.divide(change, other.value)?));
+
+    }
+
+}
+
+//Approximates the derivative of a function which can only be computed through f64 with a central
+//difference, using a step of 1 for integer types
+pub(crate) fn slope<T: Number, F: Fn(f64) -> f64>(function: F, x: f64) -> f64
+{
+
+    let step = if overflow::is_integer::<T>() { 1.0 } else { f64::EPSILON.cbrt() * x.abs().max(1.0) };
+
+    return (function(x + step) - function(x - step)) / (2.0 * step);
+
+}
+
+impl<T: Number> PartialEq for Dual<T>
+{
+
+    fn eq(&self, other: &Dual<T>) -> bool
+    {
+
+        return self.value == other.value;
+
+    }
+
+}
+
+impl<T: Number> PartialOrd for Dual<T>
+{
+
+    fn partial_cmp(&self, other: &Dual<T>) -> Option<Ordering>
+    {
+
+        return self.value.partial_cmp(&other.value);
+
+    }
+
+}
+
+impl<T: Number> Add for Dual<T>
+{
+
+    type Output = Dual<T>;
+
+    fn add(self, other: Dual<T>) -> Dual<T>
+    {
+
+        return Dual::new(self.value + other.value, self.derivative + other.derivative);
+
+    }
+
+}
+
+impl<T: Number> Sub for Dual<T>
+{
+
+    type Output = Dual<T>;
+
+    fn sub(self, other: Dual<T>) -> Dual<T>
+    {
+
+        return Dual::new(self.value - other.value, self.derivative - other.derivative);
+
+    }
+
+}
+
+impl<T: Number> Mul for Dual<T>
+{
+
+    type Output = Dual<T>;
+
+    fn mul(self, other: Dual<T>) -> Dual<T>
+    {
+
+        return Dual::new(self.value * other.value, self.derivative * other.value + self.value * other.derivative);
+
+    }
+
+}
+
+impl<T: Number> Div for Dual<T>
+{
+
+    type Output = Dual<T>;
+
+    fn div(self, other: Dual<T>) -> Dual<T>
+    {
+
+        let quotient = self.value / other.value;
+
+        return Dual::new(quotient, (self.derivative - quotient * other.derivative) / other.value);
+
+    }
+
+}
+
+impl<T: Number> Rem for Dual<T>
+{
+
+    type Output = Dual<T>;
+
+    //The remainder is n - k * d for the truncated quotient k, which is locally constant
+    fn rem(self, other: Dual<T>) -> Dual<T>
+    {
+
+        let remainder = self.value % other.value;
+        let truncated = (self.value - remainder) / other.value;
+
+        return Dual::new(remainder, self.derivative - truncated * other.derivative);
+
+    }
+
+}
+
+impl<T: Number> Zero for Dual<T>
+{
+
+    fn zero() -> Dual<T>
+    {
+
+        return Dual::constant(T::zero());
+
+    }
+
+    fn is_zero(&self) -> bool
+    {
+
+        return self.value.is_zero();
+
+    }
+
+}
+
+impl<T: Number> One for Dual<T>
+{
+
+    fn one() -> Dual<T>
+    {
+
+        return Dual::constant(T::one());
+
+    }
+
+}
+
+impl<T: Number> Num for Dual<T>
+{
+
+    type FromStrRadixErr = T::FromStrRadixErr;
+
+    fn from_str_radix(text: &str, radix: u32) -> Result<Dual<T>, T::FromStrRadixErr>
+    {
+
+        return T::from_str_radix(text, radix).map(Dual::constant);
+
+    }
+
+}
+
+impl<T: Number> Bounded for Dual<T>
+{
+
+    fn min_value() -> Dual<T>
+    {
+
+        return Dual::constant(T::min_value());
+
+    }
+
+    fn max_value() -> Dual<T>
+    {
+
+        return Dual::constant(T::max_value());
+
+    }
+
+}
+
+impl<T: Number> ToPrimitive for Dual<T>
+{
+
+    fn to_i64(&self) -> Option<i64>
+    {
+
+        return self.value.to_i64();
+
+    }
+
+    fn to_u64(&self) -> Option<u64>
+    {
+
+        return self.value.to_u64();
+
+    }
+
+    fn to_i128(&self) -> Option<i128>
+    {
+
+        return self.value.to_i128();
+
+    }
+
+    fn to_u128(&self) -> Option<u128>
+    {
+
+        return self.value.to_u128();
+
+    }
+
+    fn to_f64(&self) -> Option<f64>
+    {
+
+        return self.value.to_f64();
+
+    }
+
+}
+
+impl<T: Number> FromPrimitive for Dual<T>
+{
+
+    fn from_i64(n: i64) -> Option<Dual<T>>
+    {
+
+        return T::from_i64(n).map(Dual::constant);
+
+    }
+
+    fn from_u64(n: u64) -> Option<Dual<T>>
+    {
+
+        return T::from_u64(n).map(Dual::constant);
+
+    }
+
+    fn from_i128(n: i128) -> Option<Dual<T>>
+    {
+
+        return T::from_i128(n).map(Dual::constant);
+
+    }
+
+    fn from_u128(n: u128) -> Option<Dual<T>>
+    {
+
+        return T::from_u128(n).map(Dual::constant);
+
+    }
+
+    fn from_f64(n: f64) -> Option<Dual<T>>
+    {
+
+        return T::from_f64(n).map(Dual::constant);
+
+    }
+
+}
+
+impl<T: Number> FromStr for Dual<T>
+{
+
+    type Err = T::Err;
+
+    fn from_str(text: &str) -> Result<Dual<T>, T::Err>
+    {
+
+        return text.parse::<T>().map(Dual::constant);
+
+    }
+
+}
+
+#[cfg(test)]
+mod dual_tests
+{
+
+    use crate::Parametrizer;
+    use crate::ParametrizerFunction;
+
+    #[test]
+    #[should_panic(expected = "Cannot divide by 0")]
+    fn test_division_by_zero ()
+    {
+
+        let fraction = Parametrizer::<f64>::new("1/(t - 2)").unwrap();
+
+        assert_eq!((-1.0, -1.0), fraction.evaluate_with_derivative(1.0));
+
+        fraction.evaluate_with_derivative(2.0);
+
+    }
+
+    #[test]
+    fn test_dual_approximations ()
+    {
+
+        let unknown = Parametrizer::<f64>::new_functions("1 + cbrt(t)", vec![ParametrizerFunction::new("cbrt".to_string(), f64::cbrt)]).unwrap();
+        let noise = Parametrizer::<f64>::with_seed("t*noise(t)", Vec::new(), 3).unwrap();
+        let random = Parametrizer::<f64>::new("t*rd(2<2.5)").unwrap();
+        let integer = Parametrizer::<i32>::new("p 3*t*t>0|t/2>10").unwrap();
+
+        for i in 0..20
+        {
+
+            let t = 0.3 + f64::from(i) * 0.23;
+            let h = 1e-6;
+
+            for function in [&unknown, &noise].iter()
+            {
+
+                let expected = (function.evaluate(t + h) - function.evaluate(t - h)) / (2.0 * h);
+
+                assert!((function.evaluate_with_derivative(t).1 - expected).abs() < 1e-5);
+
+            }
+
+            let (value, slope) = random.evaluate_with_derivative(t);
+
+            assert!((value / t - slope).abs() < 1e-12);
+
+        }
+
+        assert_eq!((27, 18), integer.evaluate_with_derivative(3));
+        assert_eq!((6, 0), integer.evaluate_with_derivative(12));
+
+    }
+
+}
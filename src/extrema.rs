@@ -0,0 +1,263 @@
+use crate::Number;
+use crate::EvalError;
+use crate::EvalErrorKind;
+use crate::overflow;
+use crate::roots;
+use crate::sampler;
+use crate::sampler::Sampler;
+use crate::term::arenaterm::ArenaTerm;
+
+//The number of samples taken in each segment between breakpoints
+const SAMPLES : usize = 256;
+//The number of steps of golden section search used to refine a sampled extremum
+const REFINEMENTS : usize = 80;
+
+///The global minimum and maximum of a function over an interval, found by Parametrizer::extrema
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Extrema<T: Number>
+{
+
+    ///The smallest value of the function
+    pub min: T,
+    ///The value of t at which the function is smallest
+    pub min_at: T,
+    ///The largest value of the function
+    pub max: T,
+    ///The value of t at which the function is largest
+    pub max_at: T
+
+}
+
+//The smallest and largest values found so far, with their locations
+struct Search<'a, T: Number>
+{
+
+    sampler: Sampler<'a, T>,
+    min: (f64, f64),
+    max: (f64, f64)
+
+}
+
+//Finds the extrema between a and b by sampling every segment between piecewise breakpoints,
+//refining the sampled local extrema, and checking the critical points where the derivative is 0
+pub(crate) fn extrema<T: Number>(term: &ArenaTerm<T>, derivative: Option<&ArenaTerm<T>>, a: T, b: T) -> Result<Extrema<T>, EvalError>
+{
+
+    let conversion = || { return EvalError::new(EvalErrorKind::FunctionConversion); };
+
+    let (start, end) = (a.to_f64().ok_or_else(conversion)?, b.to_f64().ok_or_else(conversion)?);
+    let (start, end) = (start.min(end), start.max(end));
+
+    let mut search = Search { sampler: Sampler::new(term), min: (f64::INFINITY, start), max: (f64::NEG_INFINITY, start) };
+
+    let segments = sampler::segments(term, start, end);
+    let integer = overflow::is_integer::<T>();
+
+    for (i, segment) in segments.windows(2).enumerate()
+    {
+
+        //The end of the segment is only sampled for the last one, as it belongs to the next part
+        let last = i + 2 == segments.len();
+        let step = if integer { ((segment[1] - segment[0]) / SAMPLES as f64).ceil().max(1.0) } else { (segment[1] - segment[0]) / SAMPLES as f64 };
+
+        let mut samples = Vec::new();
+        let mut k = 0.0;
+
+        while segment[0] + k * step < segment[1] || (last && segment[0] + k * step == segment[1])
+        {
+
+            let x = segment[0] + k * step;
+
+            samples.push((x, search.consider(x)?));
+            k += 1.0;
+
+        }
+
+        if last && samples.last().map(|s| { return s.0; }) != Some(segment[1])
+        {
+
+            samples.push((segment[1], search.consider(segment[1])?));
+
+        }
+
+        if !integer
+        {
+
+            search.refine(&samples);
+
+        }
+
+    }
+
+    //The derivative may fail to evaluate where the function does not, e.g. for sqrt at 0, in which
+    //case only the samples are used
+    if let Some(derivative) = derivative
+    {
+
+        for x in roots::find_all_roots(derivative, None, (a, b), SAMPLES).unwrap_or_default()
+        {
+
+            if let Some(x) = x.to_f64()
+            {
+
+                search.consider(x)?;
+
+            }
+
+        }
+
+    }
+
+    let value = |v: f64| { return T::from_f64(v).ok_or_else(conversion); };
+
+    if search.min.0 > search.max.0
+    {
+
+        //Every value was NaN
+        return Err(EvalError::new(EvalErrorKind::NonFinite));
+
+    }
+
+    return Ok(Extrema { min: value(search.min.0)?, min_at: value(search.min.1)?, max: value(search.max.0)?, max_at: value(search.max.1)? });
+
+}
+
+impl<T: Number> Search<'_, T>
+{
+
+    fn consider(&mut self, x: f64) -> Result<f64, EvalError>
+    {
+
+        let y = self.sampler.evaluate(x)?;
+
+        if y < self.min.0
+        {
+
+            self.min = (y, x);
+
+        }
+
+        if y > self.max.0
+        {
+
+            self.max = (y, x);
+
+        }
+
+        return Ok(y);
+
+    }
+
+    //Refines every sampled local extremum with golden section search between its neighbours.
+    //Failures to evaluate during refinement, e.g. at a pole, end the refinement of that extremum.
+    fn refine(&mut self, samples: &[(f64, f64)])
+    {
+
+        for window in samples.windows(3)
+        {
+
+            let (l, m, r) = (window[0], window[1], window[2]);
+
+            if m.1 >= l.1 && m.1 >= r.1
+            {
+
+                let _ = self.golden(l.0, r.0, -1.0);
+
+            }
+
+            if m.1 <= l.1 && m.1 <= r.1
+            {
+
+                let _ = self.golden(l.0, r.0, 1.0);
+
+            }
+
+        }
+
+    }
+
+    //Minimizes sign * f between l and r, considering every point evaluated
+    fn golden(&mut self, mut l: f64, mut r: f64, sign: f64) -> Result<(), EvalError>
+    {
+
+        let ratio = (5.0_f64.sqrt() - 1.0) / 2.0;
+
+        let mut c = r - ratio * (r - l);
+        let mut d = l + ratio * (r - l);
+        let mut fc = sign * self.consider(c)?;
+        let mut fd = sign * self.consider(d)?;
+
+        for _ in 0..REFINEMENTS
+        {
+
+            if fc < fd
+            {
+
+                r = d;
+                d = c;
+                fd = fc;
+                c = r - ratio * (r - l);
+                fc = sign * self.consider(c)?;
+
+            }
+            else
+            {
+
+                l = c;
+                c = d;
+                fc = fd;
+                d = l + ratio * (r - l);
+                fd = sign * self.consider(d)?;
+
+            }
+
+        }
+
+        return Ok(());
+
+    }
+
+}
+
+#[cfg(test)]
+mod extrema_tests
+{
+
+    use crate::Parametrizer;
+    use crate::ParametrizerFunction;
+    use crate::EvalErrorKind;
+
+    #[test]
+    fn test_refinement ()
+    {
+
+        //cbrt has no symbolic derivative, so the peak between samples is only found by refinement
+        let functions = vec![ParametrizerFunction::new("cbrt".to_string(), f64::cbrt)];
+        let peak = Parametrizer::<f64>::new_functions("1 - cbrt(t*t - 0.2468*t + 0.01522756)", functions).unwrap();
+
+        let extrema = peak.extrema(-1.0, 1.0).unwrap();
+
+        assert!((extrema.max - 1.0).abs() < 1e-4);
+        assert!((extrema.max_at - 0.1234).abs() < 1e-6);
+
+        //The bounds may be given in either order
+        assert_eq!(extrema, peak.extrema(1.0, -1.0).unwrap());
+
+    }
+
+    #[test]
+    fn test_failures ()
+    {
+
+        let pole = Parametrizer::<i32>::new("10/t").unwrap();
+
+        assert_eq!(EvalErrorKind::DivisionByZero, pole.extrema(-5, 5).unwrap_err().kind());
+
+        //Division truncates, so the minimum is first reached at 4
+        let extrema = pole.extrema(1, 5).unwrap();
+
+        assert_eq!((2, 4, 10, 1), (extrema.min, extrema.min_at, extrema.max, extrema.max_at));
+
+    }
+
+}
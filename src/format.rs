@@ -0,0 +1,562 @@
+use std::fmt;
+
+use crate::Number;
+use crate::overflow;
+use crate::term::Term;
+use crate::term::Subterm;
+use crate::term::TermView;
+use crate::term::sequenceterm::SequenceOperations;
+
+//Written for terms whose structure is unknown, which cannot be parsed
+const OPAQUE : &str = "<opaque>";
+//Written in place of the identifier of a function which does not have one
+const UNNAMED : &str = "<function>";
+
+//The lacunarity and gain of fractal noise which may be left out of "fbm("
+pub(crate) const DEFAULT_LACUNARITY : f64 = 2.0;
+pub(crate) const DEFAULT_GAIN : f64 = 0.5;
+
+//The shape of a written term, which decides whether it needs parentheses as part of another
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Shape
+{
+
+    Sum,
+    Product,
+    Fraction,
+    Negation,
+    Constant,
+    //Variables and calls, which never need parentheses
+    Atom,
+    //Terms which can only be parsed at the top level or not at all, so are always wrapped
+    Unparseable
+
+}
+
+fn shape<T: Number>(term: Subterm<'_, T>) -> Shape
+{
+
+    match term.to_view()
+    {
+
+        TermView::Sequence(SequenceOperations::Addition, _) => return Shape::Sum,
+        TermView::Sequence(SequenceOperations::Multiplication, _) => return Shape::Product,
+        TermView::Scalar(_, scale) => return if is_negation(scale) { Shape::Negation } else { Shape::Product },
+        TermView::Fraction(_, _) => return Shape::Fraction,
+        TermView::Constant(_) => return Shape::Constant,
+        TermView::Piecewise(_, _) | TermView::Opaque => return Shape::Unparseable,
+        _ => return Shape::Atom
+
+    }
+
+}
+
+pub(crate) fn is_negation<T: Number>(scale: T) -> bool
+{
+
+    return !overflow::is_unsigned::<T>() && scale == T::zero() - T::one();
+
+}
+
+//Writes a number without requiring Display, which the Number trait does not. Integers are written
+//exactly, and other numbers as the shortest of their f32 and f64 representations which parses back
+//to the same number.
+pub(crate) fn number<T: Number>(c: T) -> String
+{
+
+    if overflow::is_integer::<T>()
+    {
+
+        if let Some(i) = c.to_i128()
+        {
+
+            return i.to_string();
+
+        }
+
+        if let Some(u) = c.to_u128()
+        {
+
+            return u.to_string();
+
+        }
+
+    }
+
+    return match c.to_f64()
+    {
+
+        Some(x) if (x as f32).to_string().parse::<T>().ok() == Some(c) => (x as f32).to_string(),
+        Some(x) => x.to_string(),
+        None => OPAQUE.to_string()
+
+    };
+
+}
+
+//Displays a subterm using Term::format
+struct Formatted<'a, T: Number>(Subterm<'a, T>);
+
+impl<T: Number> fmt::Display for Formatted<'_, T>
+{
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+
+        return self.0.format(f);
+
+    }
+
+}
+
+//Writes the subterm, wrapped in parentheses if it has one of the given shapes or, when the position
+//does not allow a leading minus sign, if it starts with one. A minus sign is only allowed at the
+//start of a term or of an operand which is itself at the start, as the parser reads "-" as "+-".
+fn operand<T: Number>(term: Subterm<'_, T>, wrapped: &[Shape], leading_minus: bool) -> String
+{
+
+    let written = Formatted(term).to_string();
+
+    if wrapped.contains(&shape(term)) || shape(term) == Shape::Unparseable || (!leading_minus && written.starts_with('-'))
+    {
+
+        return format!("({})", written);
+
+    }
+
+    return written;
+
+}
+
+//Writes the arguments of a call, separated by commas
+fn arguments<T: Number>(f: &mut fmt::Formatter, identifier: &str, terms: &[Subterm<'_, T>]) -> fmt::Result
+{
+
+    let written : Vec<String> = terms.iter().map(|term| { return operand(*term, &[], true); }).collect();
+
+    return write!(f, "{}({})", identifier, written.join(", "));
+
+}
+
+//Writes the term described by the view in the syntax accepted by the parser, using as few
+//parentheses as are needed for the parser to rebuild a tree which evaluates the same operations in
+//the same order. A sum or product leading a sum or product is written without parentheses, as the
+//parser adds its terms in the same order, and a negated constant is written as a negative constant.
+pub(crate) fn write_view<T: Number>(view: TermView<'_, T>, f: &mut fmt::Formatter) -> fmt::Result
+{
+
+    match view
+    {
+
+        TermView::Constant(c) => return write!(f, "{}", number(c)),
+        TermView::Variable => return write!(f, "t"),
+        TermView::Sequence(operation, terms) =>
+        {
+
+            if terms.is_empty()
+            {
+
+                return if operation == SequenceOperations::Addition { write!(f, "0") } else { write!(f, "1") };
+
+            }
+
+            for (i, term) in terms.iter().enumerate()
+            {
+
+                if operation == SequenceOperations::Addition
+                {
+
+                    let written = operand(*term, if i == 0 { &[] } else { &[Shape::Sum] }, true);
+
+                    //Subtraction is written as the sum of a term starting with a minus sign
+                    match (i, written.strip_prefix('-'))
+                    {
+
+                        (0, _) => write!(f, "{}", written)?,
+                        (_, Some(rest)) => write!(f, " - {}", rest)?,
+                        (_, None) => write!(f, " + {}", written)?
+
+                    }
+
+                }
+                else
+                {
+
+                    let written = operand(*term, if i == 0 { &[Shape::Sum] } else { &[Shape::Sum, Shape::Product] }, i == 0);
+
+                    write!(f, "{}{}", if i == 0 { "" } else { "*" }, written)?;
+
+                }
+
+            }
+
+            return Ok(());
+
+        },
+        TermView::Scalar(term, scale) =>
+        {
+
+            //Only negation can be parsed, so other scales are written as products
+            if is_negation(scale)
+            {
+
+                return write!(f, "-{}", operand(term, &[Shape::Sum, Shape::Product, Shape::Fraction, Shape::Negation], false));
+
+            }
+
+            return write!(f, "{}*{}", number(scale), operand(term, &[Shape::Sum, Shape::Product], false));
+
+        },
+        TermView::Fraction(numerator, denominator) =>
+        {
+
+            let shapes = [Shape::Sum, Shape::Product, Shape::Fraction];
+
+            return write!(f, "{}/{}", operand(numerator, &shapes, true), operand(denominator, &shapes, false));
+
+        },
+        TermView::Function(term, _, identifier) => return arguments(f, identifier.unwrap_or(UNNAMED), &[term]),
+        TermView::Random(min, max) => return write!(f, "rd({}<{})", operand(min, &[], true), operand(max, &[], true)),
+        TermView::Distribution(distribution, terms) => return arguments(f, distribution.identifier(), &terms),
+        TermView::Choice(choices) =>
+        {
+
+            let written : Vec<String> = choices.iter().map(|choice| { return format!("{}:{}", operand(choice.0, &[], true), operand(choice.1, &[], true)); }).collect();
+
+            return write!(f, "choice({})", written.join(", "));
+
+        },
+        TermView::Noise(term, noise) =>
+        {
+
+            if noise.octaves() == 1
+            {
+
+                return arguments(f, noise.kind().identifier(), &[term]);
+
+            }
+
+            write!(f, "fbm({}, {}", operand(term, &[], true), noise.octaves())?;
+
+            if noise.lacunarity() != DEFAULT_LACUNARITY || noise.gain() != DEFAULT_GAIN
+            {
+
+                write!(f, ", {}, {}", noise.lacunarity(), noise.gain())?;
+
+            }
+
+            return write!(f, ")");
+
+        },
+        TermView::Piecewise(parts, cycle) =>
+        {
+
+            write!(f, "p")?;
+
+            if let Some(c) = cycle
+            {
+
+                write!(f, "[{}]", number(c))?;
+
+            }
+
+            for (i, part) in parts.iter().enumerate()
+            {
+
+                write!(f, "{}{}>{}", if i == 0 { " " } else { "|" }, operand(part.0, &[], true), number(part.1))?;
+
+            }
+
+            return Ok(());
+
+        },
+        TermView::Opaque => return write!(f, "{}", OPAQUE)
+
+    }
+
+}
+
+#[cfg(test)]
+mod format_tests
+{
+
+    use proptest::prelude::*;
+
+    use crate::Parametrizer;
+    use crate::ParametrizerFunction;
+    use crate::term::Subterm;
+    use crate::term::TermView;
+    use crate::term::sequenceterm::SequenceOperations;
+
+    //Writes fully parenthesized expressions, so that the formatter has to remove parentheses
+    fn expression() -> impl Strategy<Value = String>
+    {
+
+        let leaf = prop_oneof![
+
+            Just("t".to_string()),
+            (0u32..20).prop_map(|c| { return c.to_string(); }),
+            (0u32..40).prop_map(|c| { return format!("{}", c as f64 / 8.0); })
+
+        ];
+
+        return leaf.prop_recursive(5, 48, 3, |inner|
+        {
+
+            return prop_oneof![
+
+                prop::collection::vec(inner.clone(), 2..4).prop_map(|terms| { return format!("({})", terms.join(")+(")); }),
+                prop::collection::vec(inner.clone(), 2..4).prop_map(|terms| { return format!("({})", terms.join(")*(")); }),
+                (inner.clone(), inner.clone()).prop_map(|(a, b)| { return format!("({})/({})", a, b); }),
+                (inner.clone(), inner.clone()).prop_map(|(a, b)| { return format!("({})-({})", a, b); }),
+                inner.clone().prop_map(|a| { return format!("-({})", a); }),
+                inner.clone().prop_map(|a| { return format!("sin({})", a); }),
+                (inner.clone(), inner.clone()).prop_map(|(a, b)| { return format!("rd({}<{})", a, b); }),
+                (inner.clone(), inner).prop_map(|(a, b)| { return format!("choice(1:{}, 2:{})", a, b); })
+
+            ];
+
+        });
+
+    }
+
+    fn piecewise() -> impl Strategy<Value = String>
+    {
+
+        let part = (expression(), -10i32..10);
+
+        return (prop::option::of(1u32..20), prop::collection::vec(part, 1..4)).prop_map(|(cycle, parts)|
+        {
+
+            let parts : Vec<String> = parts.into_iter().map(|(term, after)| { return format!("{}>{}", term, after); }).collect();
+
+            return match cycle
+            {
+
+                Some(c) => format!("p[{}]{}", c, parts.join("|")),
+                None => format!("p{}", parts.join("|"))
+
+            };
+
+        });
+
+    }
+
+    fn functions() -> Vec<ParametrizerFunction>
+    {
+
+        return vec![ParametrizerFunction::new("sin".to_string(), f64::sin)];
+
+    }
+
+    //Describes the operations of a term in the order they are evaluated, so that terms which are
+    //written the same way have the same description. Sums and products leading a sum or product are
+    //merged into it, and negated constants are replaced by their values.
+    fn tree(term: Subterm<'_, f64>) -> String
+    {
+
+        match term.to_view()
+        {
+
+            TermView::Constant(c) => return c.to_string(),
+            TermView::Variable => return "t".to_string(),
+            TermView::Sequence(operation, _) => return format!("({} {})", if operation == SequenceOperations::Addition { "+" } else { "*" }, leading(term, operation).join(" ")),
+            TermView::Scalar(subterm, scale) =>
+            {
+
+                let described = tree(subterm);
+
+                return match described.parse::<f64>()
+                {
+
+                    Ok(c) => (c * scale).to_string(),
+                    Err(_e) => format!("({} {})", scale, described)
+
+                };
+
+            },
+            TermView::Fraction(numerator, denominator) => return format!("(/ {} {})", tree(numerator), tree(denominator)),
+            TermView::Function(subterm, _, identifier) => return format!("({} {})", identifier.unwrap_or("?"), tree(subterm)),
+            TermView::Random(min, max) => return format!("(rd {} {})", tree(min), tree(max)),
+            TermView::Distribution(distribution, terms) => return format!("({} {})", distribution.identifier(), terms.into_iter().map(tree).collect::<Vec<String>>().join(" ")),
+            TermView::Choice(choices) => return format!("(choice {})", choices.into_iter().map(|(weight, choice)| { return format!("{}:{}", tree(weight), tree(choice)); }).collect::<Vec<String>>().join(" ")),
+            TermView::Noise(subterm, noise) => return format!("(noise {} {})", noise.octaves(), tree(subterm)),
+            TermView::Piecewise(parts, cycle) => return format!("(p {:?} {})", cycle, parts.into_iter().map(|(part, after)| { return format!("{}>{}", tree(part), after); }).collect::<Vec<String>>().join(" ")),
+            TermView::Opaque => return "?".to_string()
+
+        }
+
+    }
+
+    //Describes the terms of a sequence, including those of the sequences leading it
+    fn leading(term: Subterm<'_, f64>, operation: SequenceOperations) -> Vec<String>
+    {
+
+        match term.to_view()
+        {
+
+            TermView::Sequence(inner, terms) if inner == operation =>
+            {
+
+                let mut described = Vec::new();
+
+                for (i, term) in terms.into_iter().enumerate()
+                {
+
+                    if i == 0 { described.extend(leading(term, operation)); } else { described.push(tree(term)); }
+
+                }
+
+                return described;
+
+            },
+            _ => return vec![tree(term)]
+
+        }
+
+    }
+
+    //Parses, writes, and parses again, checking that the second string is written the same way, that
+    //both functions evaluate the same operations and agree wherever they are not random, and that
+    //removing any pair of parentheses outside of calls fails to parse or changes the operations
+    fn check(param: &str) -> Result<(), TestCaseError>
+    {
+
+        let first = Parametrizer::<f64>::new_functions(param, functions()).unwrap();
+        let written = first.to_string();
+
+        let second = match Parametrizer::<f64>::new_functions(&written, functions())
+        {
+
+            Ok(second) => second,
+            Err(e) => return Err(TestCaseError::fail(format!("{} was written as {}, which failed to parse: {}", param, written, e)))
+
+        };
+
+        prop_assert_eq!(&written, &second.to_string());
+        prop_assert_eq!(tree(first.term.root()), tree(second.term.root()));
+
+        for (i, _) in written.match_indices('(').filter(|(i, _)| { return *i == 0 || !written.as_bytes()[i - 1].is_ascii_alphanumeric(); })
+        {
+
+            let mut balance = 0;
+            let closing = written[i..].char_indices().position(|(_, c)|
+            {
+
+                balance += match c { '(' => 1, ')' => -1, _ => 0 };
+
+                return balance == 0;
+
+            }).unwrap() + i;
+
+            let removed = format!("{}{}{}", &written[..i], &written[i + 1..closing], &written[closing + 1..]);
+
+            if let Ok(parsed) = Parametrizer::<f64>::new_functions(&removed, functions())
+            {
+
+                prop_assert_ne!(tree(first.term.root()), tree(parsed.term.root()), "{} was written as {}, whose parentheses at {} are not needed", param, written, i);
+
+            }
+
+        }
+
+        if !written.contains("rd(") && !written.contains("choice(")
+        {
+
+            for t in [-2.5, -1.0, 0.0, 0.75, 3.0, 12.5]
+            {
+
+                let (a, b) = (first.try_evaluate(t), second.try_evaluate(t));
+                //Merged sums and products change the paths to failing terms, but not the failures
+                let same = match (&a, &b)
+                {
+
+                    (Ok(a), Ok(b)) => a == b || (a.is_nan() && b.is_nan()),
+                    (Err(a), Err(b)) => a.kind() == b.kind(),
+                    _ => false
+
+                };
+
+                prop_assert!(same, "{} and {} differ at {}", param, written, t);
+
+            }
+
+        }
+
+        return Ok(());
+
+    }
+
+    #[test]
+    fn test_terms ()
+    {
+
+        use crate::term::Term;
+        use crate::term::scalarterm::ScalarTerm;
+        use crate::term::variableterm::VariableTerm;
+        use crate::term::functionterm::FunctionTerm;
+
+        let written = |param: &str| { return Parametrizer::<i32>::new(param).unwrap().to_string(); };
+
+        assert_eq!("(t + 1)*(t + 2)", written("(t+1)*(t+2)"));
+        //Negation binds tighter than multiplication, so "-t*3" is the product of -t and 3
+        assert_eq!("t - 2 - t*3 - (-3)", written("t - 2 + (-t)*3 - (-3)"));
+        assert_eq!("10/(-t) - (t/2)/3", written("10/(-t) - (t/2)/3"));
+        assert_eq!("rd(rd(0<t)<2*t) + normal(t, 1)*poisson(2)", written("rd(rd(0<t)<2*t) + normal(t,1)*poisson(2)"));
+        assert_eq!("choice(1:t, 3:-t) + perlin(t) + fbm(t, 3) + fbm(t, 2, 3, 0.25)", written("choice(1:t,3:-t) + noise(t) + fbm(t,3) + fbm(t,2,3,0.25)"));
+        assert_eq!("p[10] t>0|-t>-5", written("p[10]t>0|-t>-5"));
+
+        //Parentheses are only kept where removing them would change the operations or their order
+        assert_eq!("t + 1 - 2", written("(t + 1) - 2"));
+        assert_eq!("2*t*t - (t + 1 + t)", written("((2*t)*t) - ((t + 1) + t)"));
+        assert_eq!("t*(2*t) - (1 - (t - 1))", written("t*(2*t) - (1 - (t - 1))"));
+        assert_eq!("-1/t - (2*t) - (-3)", written("-(1)/t - (2*t) - (-(3))"));
+
+        //Only negation can be parsed, so other scales are written as products
+        assert_eq!("3*t", Parametrizer::from_term(Box::new(ScalarTerm::new(Box::new(VariableTerm::new()), 3))).to_string());
+        assert_eq!("<function>(t)", Parametrizer::<f64>::from_term(Box::new(FunctionTerm::new(Box::new(VariableTerm::new()), f64::exp))).to_string());
+
+        struct Custom;
+
+        impl Term<f64> for Custom
+        {
+
+            fn evaluate(&self, t: f64) -> f64
+            {
+
+                return t;
+
+            }
+
+        }
+
+        assert_eq!("-(<opaque>)", Parametrizer::from_term(Box::new(ScalarTerm::new(Box::new(Custom), -1.0))).to_string());
+
+        //Numbers are written as briefly as they can be parsed back
+        assert_eq!("0.1*t + 0.30000000000000004", Parametrizer::<f64>::new("0.1*t + 0.30000000000000004").unwrap().to_string());
+        assert_eq!("0.1*t", Parametrizer::<f32>::new("0.1*t").unwrap().to_string());
+
+    }
+
+    proptest!
+    {
+
+        #[test]
+        fn test_round_trip (param in expression())
+        {
+
+            check(&param)?;
+
+        }
+
+        #[test]
+        fn test_piecewise_round_trip (param in piecewise())
+        {
+
+            check(&param)?;
+
+        }
+
+    }
+
+}
@@ -0,0 +1,347 @@
+use crate::Number;
+use crate::EvalError;
+use crate::EvalErrorKind;
+use crate::overflow;
+use crate::sampler;
+use crate::sampler::Sampler;
+use crate::term::arenaterm::ArenaTerm;
+
+//The 15 point Kronrod nodes on [-1, 1], from the centre outwards. Every other node from the second
+//is also a node of the embedded 7 point Gauss rule.
+const KRONROD_NODES : [f64; 8] = [0.0, 0.207_784_955_007_898_5, 0.405_845_151_377_397_2, 0.586_087_235_467_691_1, 0.741_531_185_599_394_4, 0.864_864_423_359_769_1, 0.949_107_912_342_758_5, 0.991_455_371_120_812_6];
+const KRONROD_WEIGHTS : [f64; 8] = [0.209_482_141_084_727_8, 0.204_432_940_075_298_9, 0.190_350_578_064_785_4, 0.169_004_726_639_267_9, 0.140_653_259_715_525_9, 0.104_790_010_322_250_2, 0.063_092_092_629_979_0, 0.022_935_322_010_529_2];
+//The weights of the 7 point Gauss rule, for the centre and then the odd Kronrod nodes
+const GAUSS_WEIGHTS : [f64; 4] = [0.417_959_183_673_469_4, 0.381_830_050_505_118_9, 0.279_705_391_489_276_7, 0.129_484_966_168_869_7];
+
+//The most unit segments an integer function is split into, beyond which it is integrated like any
+//other function
+const MAX_INTEGER_SEGMENTS : f64 = 65536.0;
+
+///The method used by Parametrizer::integrate. The adaptive methods evaluate the function up to
+///2^max_depth times per segment for functions which never meet the tolerance, such as random ones.
+///The default is AdaptiveSimpson with a tolerance of 1e-10 and a max_depth of 20.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IntegrationMethod
+{
+
+    ///Adaptive Simpson's rule, which halves each interval until its estimated error is within its
+    ///share of the tolerance or it has been halved max_depth times
+    AdaptiveSimpson
+    {
+
+        ///The total error to aim for
+        tolerance: f64,
+        ///The most times an interval is halved
+        max_depth: u32
+
+    },
+    ///Adaptive 15 point Gauss-Kronrod quadrature, which estimates the error of each interval by
+    ///comparing with the embedded 7 point Gauss rule, and halves each interval until its estimated
+    ///error is within its share of the tolerance or it has been halved max_depth times. Usually the
+    ///most accurate method for smooth functions, and never evaluates the ends of an interval.
+    GaussKronrod
+    {
+
+        ///The total error to aim for
+        tolerance: f64,
+        ///The most times an interval is halved
+        max_depth: u32
+
+    },
+    ///The trapezoid rule with the given total number of equal steps, split between the segments in
+    ///proportion to their length. The error is estimated by comparing with half as many steps.
+    Trapezoid
+    {
+
+        ///The total number of steps
+        steps: usize
+
+    }
+
+}
+
+impl Default for IntegrationMethod
+{
+
+    fn default() -> IntegrationMethod
+    {
+
+        return IntegrationMethod::AdaptiveSimpson { tolerance: 1e-10, max_depth: 20 };
+
+    }
+
+}
+
+///The result of numerical integration, with an estimate of its absolute error
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Integral
+{
+
+    ///The estimated value of the integral
+    pub value: f64,
+    ///The estimated absolute error of the value
+    pub error: f64
+
+}
+
+//Integrates the function between the bounds, in the direction from a to b. The interval is split at
+//the breakpoints of piecewise terms, and at every integer for integer types, whose functions are
+//constant between integers, so that each segment is continuous.
+pub(crate) fn integrate<T: Number>(term: &ArenaTerm<T>, a: T, b: T, method: IntegrationMethod) -> Result<Integral, EvalError>
+{
+
+    let start = a.to_f64().ok_or(EvalError::new(EvalErrorKind::FunctionConversion))?;
+    let end = b.to_f64().ok_or(EvalError::new(EvalErrorKind::FunctionConversion))?;
+
+    if end < start
+    {
+
+        let reversed = integrate(term, b, a, method)?;
+
+        return Ok(Integral { value: -reversed.value, error: reversed.error });
+
+    }
+
+    let mut points = sampler::segments(term, start, end);
+
+    if overflow::is_integer::<T>() && end - start <= MAX_INTEGER_SEGMENTS
+    {
+
+        points.extend((start.floor() as i64 + 1..=end.ceil() as i64 - 1).map(|i| { return i as f64; }));
+        points.sort_by(|a, b| { return a.total_cmp(b); });
+        points.dedup();
+
+    }
+
+    let sampler = Sampler::new(term);
+    let mut total = Integral { value: 0.0, error: 0.0 };
+
+    for segment in points.windows(2)
+    {
+
+        let (a, b) = (segment[0], segment[1]);
+
+        if b <= a
+        {
+
+            continue;
+
+        }
+
+        //Each segment gets its share of the tolerance or steps
+        let share = (b - a) / (end - start);
+
+        let integral = match method
+        {
+
+            IntegrationMethod::AdaptiveSimpson { tolerance, max_depth } => sampler.simpson(a, b, tolerance * share, max_depth)?,
+            IntegrationMethod::GaussKronrod { tolerance, max_depth } => sampler.kronrod(a, b, tolerance * share, max_depth)?,
+            IntegrationMethod::Trapezoid { steps } => sampler.trapezoid(a, b, ((steps as f64 * share).round() as usize).max(1))?
+
+        };
+
+        total.value += integral.value;
+        total.error += integral.error;
+
+    }
+
+    return Ok(total);
+
+}
+
+impl<T: Number> Sampler<'_, T>
+{
+
+    fn simpson(&self, a: f64, b: f64, tolerance: f64, max_depth: u32) -> Result<Integral, EvalError>
+    {
+
+        let fa = self.evaluate_inside(a, b)?;
+        let fb = self.evaluate_inside(b, a)?;
+        let m = (a + b) / 2.0;
+        let fm = self.evaluate(m)?;
+
+        return self.simpson_step(a, b, (fa, fm, fb), (b - a) / 6.0 * (fa + 4.0 * fm + fb), tolerance, max_depth);
+
+    }
+
+    fn simpson_step(&self, a: f64, b: f64, values: (f64, f64, f64), whole: f64, tolerance: f64, depth: u32) -> Result<Integral, EvalError>
+    {
+
+        let (fa, fm, fb) = values;
+        let m = (a + b) / 2.0;
+
+        let fl = self.evaluate((a + m) / 2.0)?;
+        let fr = self.evaluate((m + b) / 2.0)?;
+
+        let left = (m - a) / 6.0 * (fa + 4.0 * fl + fm);
+        let right = (b - m) / 6.0 * (fm + 4.0 * fr + fb);
+        let delta = left + right - whole;
+
+        //Richardson extrapolation, as the error of Simpson's rule falls by a factor of 16 when
+        //the interval is halved
+        if depth == 0 || delta.abs() <= 15.0 * tolerance
+        {
+
+            return Ok(Integral { value: left + right + delta / 15.0, error: delta.abs() / 15.0 });
+
+        }
+
+        let left = self.simpson_step(a, m, (fa, fl, fm), left, tolerance / 2.0, depth - 1)?;
+        let right = self.simpson_step(m, b, (fm, fr, fb), right, tolerance / 2.0, depth - 1)?;
+
+        return Ok(Integral { value: left.value + right.value, error: left.error + right.error });
+
+    }
+
+    fn kronrod(&self, a: f64, b: f64, tolerance: f64, depth: u32) -> Result<Integral, EvalError>
+    {
+
+        let centre = (a + b) / 2.0;
+        let half = (b - a) / 2.0;
+
+        let mid = self.evaluate(centre)?;
+        let mut kronrod = KRONROD_WEIGHTS[0] * mid;
+        let mut gauss = GAUSS_WEIGHTS[0] * mid;
+
+        for i in 1..KRONROD_NODES.len()
+        {
+
+            let pair = self.evaluate(centre - half * KRONROD_NODES[i])? + self.evaluate(centre + half * KRONROD_NODES[i])?;
+
+            kronrod += KRONROD_WEIGHTS[i] * pair;
+
+            if i % 2 == 0
+            {
+
+                gauss += GAUSS_WEIGHTS[i / 2] * pair;
+
+            }
+
+        }
+
+        let value = kronrod * half;
+        let error = ((kronrod - gauss) * half).abs();
+
+        if depth == 0 || error <= tolerance
+        {
+
+            return Ok(Integral { value, error });
+
+        }
+
+        let left = self.kronrod(a, centre, tolerance / 2.0, depth - 1)?;
+        let right = self.kronrod(centre, b, tolerance / 2.0, depth - 1)?;
+
+        return Ok(Integral { value: left.value + right.value, error: left.error + right.error });
+
+    }
+
+    fn trapezoid(&self, a: f64, b: f64, steps: usize) -> Result<Integral, EvalError>
+    {
+
+        let fine = self.trapezoid_sum(a, b, steps)?;
+
+        //Richardson extrapolation, as the error of the trapezoid rule falls by a factor of 4 when
+        //the step is halved
+        let error = if steps >= 2
+        {
+
+            (fine - self.trapezoid_sum(a, b, steps / 2)?).abs() / 3.0
+
+        }
+        else
+        {
+
+            (fine - self.trapezoid_sum(a, b, 2)?).abs() * 4.0 / 3.0
+
+        };
+
+        return Ok(Integral { value: fine, error });
+
+    }
+
+    fn trapezoid_sum(&self, a: f64, b: f64, steps: usize) -> Result<f64, EvalError>
+    {
+
+        let h = (b - a) / steps as f64;
+        let mut sum = (self.evaluate_inside(a, b)? + self.evaluate_inside(b, a)?) / 2.0;
+
+        for i in 1..steps
+        {
+
+            sum += self.evaluate(a + h * i as f64)?;
+
+        }
+
+        return Ok(sum * h);
+
+    }
+
+}
+
+#[cfg(test)]
+mod integrate_tests
+{
+
+    use crate::Parametrizer;
+    use super::IntegrationMethod;
+
+    const GAUSS_KRONROD : IntegrationMethod = IntegrationMethod::GaussKronrod { tolerance: 1e-12, max_depth: 16 };
+
+    #[test]
+    fn test_breakpoints ()
+    {
+
+        //t, t^2 and 3 integrate to 1/2, 7/3 and 3 over their parts, which only the splitting at 1
+        //and 2 integrates exactly
+        let parts = Parametrizer::<f64>::new("p t>0|t*t>1|3>2").unwrap();
+        //Straight parts are integrated exactly by a single trapezoid, and every segment gets at least
+        //one of the 2 steps
+        let jumps = Parametrizer::<f64>::new("p 2*t>0|5>1.5|-t>1.75").unwrap();
+
+        assert!((parts.integrate(0.0, 3.0, GAUSS_KRONROD).unwrap().value - (0.5 + 7.0 / 3.0 + 3.0)).abs() < 1e-12);
+        assert!((parts.integrate(3.0, 0.5, GAUSS_KRONROD).unwrap().value + (0.375 + 7.0 / 3.0 + 3.0)).abs() < 1e-12);
+
+        let area = jumps.integrate(0.0, 2.0, IntegrationMethod::Trapezoid { steps: 2 }).unwrap();
+
+        assert!((area.value - (2.25 + 1.25 - 0.46875)).abs() < 1e-9);
+        assert!(area.error < 1e-9);
+
+    }
+
+    #[test]
+    fn test_integer_segments ()
+    {
+
+        //Integer functions are constant between integers, so t integrates to the sum of the integers
+        //below the upper bound, or above the lower bound for negative t, which is truncated towards 0
+        let identity = Parametrizer::<i64>::new("t").unwrap();
+
+        assert_eq!(65536.0 * 65535.0 / 2.0, identity.integrate(0, 65536, IntegrationMethod::default()).unwrap().value);
+        assert_eq!(-3.0, identity.integrate(-3, 0, IntegrationMethod::default()).unwrap().value);
+
+        //Beyond MAX_INTEGER_SEGMENTS, the steps are integrated like any other function, which only
+        //approximates the sum
+        let wide = identity.integrate(0, 100_000, IntegrationMethod::Trapezoid { steps: 1000 }).unwrap().value;
+        let sum = 100_000.0 * 99_999.0 / 2.0;
+
+        assert_ne!(sum, wide);
+        assert!((wide - sum).abs() / sum < 1e-3);
+
+    }
+
+    #[test]
+    fn test_looping_breakpoints ()
+    {
+
+        //Each loop of 2 integrates to 1/2 for t over [0, 1) and 5 over [1, 2), and the half loop at
+        //the end to 1/2 again
+        let looping = Parametrizer::<f64>::new("p[2] t>0|5>1").unwrap();
+        let area = looping.integrate(0.0, 7.0, IntegrationMethod::Trapezoid { steps: 7 }).unwrap();
+
+        assert!((area.value - (3.0 * 5.5 + 0.5)).abs() < 1e-9);
+        assert!((looping.integrate(0.0, 7.0, GAUSS_KRONROD).unwrap().value - (3.0 * 5.5 + 0.5)).abs() < 1e-12);
+
+    }
+
+}
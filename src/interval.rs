@@ -0,0 +1,986 @@
+use std::f64::consts::PI;
+
+use crate::Number;
+use crate::EvalError;
+use crate::EvalErrorKind;
+use crate::OverflowPolicy;
+use crate::NonFinitePolicy;
+use crate::overflow;
+use crate::term::Subterm;
+use crate::term::TermView;
+use crate::term::arenaterm::ArenaTerm;
+use crate::term::sequenceterm::SequenceOperations;
+use crate::term::distributionterm::Distribution;
+use crate::standard;
+use crate::standard::Bounds;
+use crate::domain::Diagnostic;
+use crate::domain::DiagnosticKind;
+
+//The largest magnitude below which every integer is exactly representable as an f64
+const EXACT_INTEGERS : f64 = 9_007_199_254_740_992.0;
+//The most times the input of a term is halved, and the most enclosures computed, when checking
+//whether a possible failure is only due to the width of the intervals
+const MAX_SPLITS : u32 = 24;
+const MAX_PIECES : usize = 1024;
+
+//A computed bound, and whether it is the exact result of the arithmetic which produced it
+type Bound = (f64, bool);
+
+///A closed interval of values, which may be unbounded on either side. Returned by
+///Parametrizer::evaluate_interval as an enclosure of every value a function can take.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval
+{
+
+    ///The lower bound, which may be negative infinity
+    pub min: f64,
+    ///The upper bound, which may be infinity
+    pub max: f64
+
+}
+
+impl Interval
+{
+
+    ///Creates the interval between two values, in either order
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::Interval;
+    ///
+    /// assert_eq!(Interval { min: -1.0, max: 2.0 }, Interval::new(2.0, -1.0));
+    /// ```
+    pub fn new(a: f64, b: f64) -> Interval
+    {
+
+        return Interval { min: a.min(b), max: a.max(b) };
+
+    }
+
+    ///Whether the value is within the interval, including its bounds
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::Interval;
+    ///
+    /// let interval = Interval::new(0.0, f64::INFINITY);
+    ///
+    /// assert!(interval.contains(0.0) && interval.contains(1e300));
+    /// assert!(!interval.contains(-1e-300));
+    /// ```
+    pub fn contains(&self, value: f64) -> bool
+    {
+
+        return self.min <= value && value <= self.max;
+
+    }
+
+    ///The smallest interval containing both intervals
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::Interval;
+    ///
+    /// assert_eq!(Interval::new(-1.0, 5.0), Interval::new(-1.0, 0.0).union(Interval::new(3.0, 5.0)));
+    /// ```
+    pub fn union(self, other: Interval) -> Interval
+    {
+
+        return Interval { min: self.min.min(other.min), max: self.max.max(other.max) };
+
+    }
+
+    ///Whether both bounds are finite
+    pub fn is_bounded(&self) -> bool
+    {
+
+        return self.min.is_finite() && self.max.is_finite();
+
+    }
+
+    pub(crate) fn entire() -> Interval
+    {
+
+        return Interval { min: f64::NEG_INFINITY, max: f64::INFINITY };
+
+    }
+
+}
+
+///How a function given to Parametrizer changes as its input increases, declared with
+///ParametrizerFunction::with_monotonicity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Monotonicity
+{
+
+    ///The function never decreases, such as f64::cbrt
+    Increasing,
+    ///The function never increases, such as 1/x for positive x
+    Decreasing
+
+}
+
+//Computes enclosures of the nodes of an arena, rounding every bound outwards so that the values
+//computed by the generic type are always within them
+struct Enclosure<'a, T: Number>
+{
+
+    arena: &'a ArenaTerm<T>,
+    //How far a computed value may be from the exact result, relative to its size and in total
+    relative: f64,
+    absolute: f64,
+    //The range of the type, for integer types
+    range: Option<Interval>,
+    overflow: OverflowPolicy,
+    //Whether any bound was undefined, e.g. from subtracting infinite bounds, in which case values
+    //may be NaN
+    undefined: bool,
+    //The path to the term being enclosed, and the possible failures found, if they are collected
+    //rather than failing
+    path: Vec<usize>,
+    diagnostics: Option<Vec<Diagnostic>>
+
+}
+
+//Finds an interval containing every value the term takes for t between a and b. Fails with the path
+//to the first fraction whose denominator may be 0.
+pub(crate) fn enclose<T: Number>(term: &ArenaTerm<T>, a: T, b: T) -> Result<Interval, EvalError>
+{
+
+    let mut enclosure = Enclosure::new(term, false);
+
+    let input = enclosure.input(a, b)?;
+    let mut result = enclosure.enclose(term.root(), input)?;
+
+    if let NonFinitePolicy::Fallback(fallback) = term.non_finite_policy()
+    {
+
+        if enclosure.undefined || !result.is_bounded()
+        {
+
+            result = result.union(enclosure.point(fallback));
+
+        }
+
+    }
+
+    return Ok(result);
+
+}
+
+//Finds every term which may fail for t between a and b. Each possible failure is checked again on
+//smaller and smaller parts of the input before it is reported, so that failures which only appear
+//because the same input is used several times, as in t*t - t, are usually not reported.
+pub(crate) fn diagnose<T: Number>(term: &ArenaTerm<T>, a: T, b: T) -> Result<Vec<Diagnostic>, EvalError>
+{
+
+    let mut enclosure = Enclosure::new(term, true);
+
+    let input = enclosure.input(a, b)?;
+
+    enclosure.enclose(term.root(), input)?;
+
+    return Ok(enclosure.diagnostics.unwrap_or_default());
+
+}
+
+impl<'a, T: Number> Enclosure<'a, T>
+{
+
+    fn new(arena: &'a ArenaTerm<T>, collect: bool) -> Enclosure<'a, T>
+    {
+
+        let integer = overflow::is_integer::<T>();
+
+        let range = if integer
+        {
+
+            T::min_value().to_f64().zip(T::max_value().to_f64()).map(|(min, max)| { return Interval { min, max }; })
+
+        }
+        else
+        {
+
+            None
+
+        };
+
+        let (relative, absolute) = if integer { (0.0, 0.0) } else { precision::<T>() };
+
+        return Enclosure { arena, relative, absolute, range, overflow: arena.overflow_policy(), undefined: false, path: Vec::new(), diagnostics: if collect { Some(Vec::new()) } else { None } };
+
+    }
+
+    //Rounds the bounds of a computed interval outwards. Integer types are rounded inwards to
+    //integers afterwards, or truncated like the type's division and conversion from f64.
+    fn finish(&mut self, min: f64, max: f64, truncate: bool) -> Interval
+    {
+
+        return self.finish_bounds((min, false), (max, false), truncate);
+
+    }
+
+    //Like finish, but bounds which are the exact result of arithmetic and can be represented by the
+    //floating point type are kept, as the type computes them exactly
+    fn finish_bounds(&mut self, min: Bound, max: Bound, truncate: bool) -> Interval
+    {
+
+        let min = if min.0.is_nan() { self.undefined = true; (f64::NEG_INFINITY, false) } else { min };
+        let max = if max.0.is_nan() { self.undefined = true; (f64::INFINITY, false) } else { max };
+
+        let range = match self.range
+        {
+
+            Some(range) => range,
+            None =>
+            {
+
+                let min = if min.1 && representable::<T>(min.0) { min.0 } else { (min.0 - min.0.abs() * self.relative - self.absolute).next_down() };
+                let max = if max.1 && representable::<T>(max.0) { max.0 } else { (max.0 + max.0.abs() * self.relative + self.absolute).next_up() };
+
+                return Interval { min, max };
+
+            }
+
+        };
+
+        let (min, max) = (min.0, max.0);
+
+        //Integers are computed exactly by f64 within this range
+        let min = if min.abs() > EXACT_INTEGERS { min.next_down() } else { min };
+        let max = if max.abs() > EXACT_INTEGERS { max.next_up() } else { max };
+
+        let (min, max) = if truncate { (min.trunc(), max.trunc()) } else { (min.ceil(), max.floor()) };
+
+        if min < range.min || max > range.max
+        {
+
+            //Overflowing values are errors under the checked policy, so are not part of the result
+            if self.overflow == OverflowPolicy::Wrapping
+            {
+
+                return range;
+
+            }
+
+            return Interval { min: min.clamp(range.min, range.max), max: max.clamp(range.min, range.max) };
+
+        }
+
+        return Interval { min, max };
+
+    }
+
+    fn input(&mut self, a: T, b: T) -> Result<Interval, EvalError>
+    {
+
+        let conversion = || { return EvalError::new(EvalErrorKind::FunctionConversion); };
+
+        let input = Interval::new(a.to_f64().ok_or_else(conversion)?, b.to_f64().ok_or_else(conversion)?);
+
+        return Ok(self.exact(input.min, input.max));
+
+    }
+
+    //Encloses values of the type converted to f64, which is only inexact for large integers
+    fn exact(&mut self, min: f64, max: f64) -> Interval
+    {
+
+        if self.range.is_none()
+        {
+
+            return Interval { min, max };
+
+        }
+
+        return self.finish(min, max, false);
+
+    }
+
+    fn point(&mut self, value: T) -> Interval
+    {
+
+        return match value.to_f64()
+        {
+
+            Some(v) => self.exact(v, v),
+            None => Interval::entire()
+
+        };
+
+    }
+
+    fn add(&mut self, l: Interval, r: Interval) -> Interval
+    {
+
+        let (min, max) = (l.min + r.min, l.max + r.max);
+
+        return self.finish_bounds((min, exact_sum(l.min, r.min, min)), (max, exact_sum(l.max, r.max, max)), false);
+
+    }
+
+    fn multiply(&mut self, l: Interval, r: Interval) -> Interval
+    {
+
+        //The product of 0 and an unbounded value is taken to be 0, as every value in the interval
+        //is finite
+        let product = |a: f64, b: f64|
+        {
+
+            if a == 0.0 || b == 0.0
+            {
+
+                return (0.0, true);
+
+            }
+
+            let p = a * b;
+
+            return (p, a.mul_add(b, -p) == 0.0);
+
+        };
+
+        let products = [product(l.min, r.min), product(l.min, r.max), product(l.max, r.min), product(l.max, r.max)];
+
+        return self.finish_bounds(extreme(&products, true), extreme(&products, false), false);
+
+    }
+
+    //Divides by a denominator which does not contain 0
+    fn divide(&mut self, n: Interval, d: Interval) -> Interval
+    {
+
+        let quotient = |a: f64, b: f64|
+        {
+
+            let q = a / b;
+
+            return (q, q.mul_add(b, -a) == 0.0);
+
+        };
+
+        let quotients = [quotient(n.min, d.min), quotient(n.min, d.max), quotient(n.max, d.min), quotient(n.max, d.max)];
+
+        return self.finish_bounds(extreme(&quotients, true), extreme(&quotients, false), true);
+
+    }
+
+    //Records a possible failure of the current term, returning whether failures are being collected,
+    //in which case enclosing continues
+    fn report(&mut self, kind: DiagnosticKind, values: Interval) -> bool
+    {
+
+        let path = &self.path;
+
+        let diagnostics = match &mut self.diagnostics
+        {
+
+            Some(diagnostics) => diagnostics,
+            None => return false
+
+        };
+
+        //A term inside a looping piecewise term may be reached by several inputs
+        match diagnostics.iter_mut().find(|d| { return d.kind() == kind && d.path() == path.as_slice(); })
+        {
+
+            Some(existing) => existing.include(values),
+            None => diagnostics.push(Diagnostic::new(kind, path.clone(), values))
+
+        }
+
+        return true;
+
+    }
+
+    fn child(&mut self, index: usize, term: Subterm<'_, T>, t: Interval) -> Result<Interval, EvalError>
+    {
+
+        self.path.push(index);
+
+        let value = self.enclose(term, t).map_err(|e| { return e.within(index); });
+
+        self.path.pop();
+
+        return value;
+
+    }
+
+    //Splits the input into smaller and smaller parts until the enclosures of the terms on each part
+    //are not rejected, or the parts cannot be split further, returning the enclosures for every part.
+    //Failures on a part are treated as unbounded.
+    fn split<F: Fn(&[Interval]) -> bool>(&mut self, terms: &[Subterm<'_, T>], t: Interval, rejected: F) -> Vec<Vec<Interval>>
+    {
+
+        //Failures of the terms themselves are reported separately
+        let diagnostics = self.diagnostics.take();
+
+        let mut pending = vec![(t, 0)];
+        let mut pieces = Vec::new();
+        let mut evaluated = 0;
+
+        while let Some((input, splits)) = pending.pop()
+        {
+
+            let values : Vec<Interval> = terms.iter().map(|term| { return self.enclose(*term, input).unwrap_or(Interval::entire()); }).collect();
+
+            evaluated += 1;
+
+            //Integer inputs are split between integers
+            let middle = if self.range.is_some() { ((input.min + input.max) / 2.0).floor() } else { input.min / 2.0 + input.max / 2.0 };
+
+            if rejected(&values) && splits < MAX_SPLITS && evaluated + pending.len() < MAX_PIECES && input.min < input.max && middle.is_finite()
+            {
+
+                let next = if self.range.is_some() { middle + 1.0 } else { middle };
+
+                pending.push((Interval { min: next, max: input.max }, splits + 1));
+                pending.push((Interval { min: input.min, max: middle }, splits + 1));
+
+                continue;
+
+            }
+
+            pieces.push(values);
+
+        }
+
+        self.diagnostics = diagnostics;
+
+        return pieces;
+
+    }
+
+    //Checks a possible failure by splitting the input, reporting it if it remains possible on any
+    //part. Returns whether it was reported, and the union of the enclosures of each term on every
+    //part.
+    fn check<F: Fn(&[Interval]) -> bool>(&mut self, kind: DiagnosticKind, terms: &[Subterm<'_, T>], t: Interval, rejected: F, responsible: fn(&[Interval]) -> Interval) -> (bool, Vec<Vec<Interval>>)
+    {
+
+        let pieces = self.split(terms, t, &rejected);
+
+        let failing : Vec<Interval> = pieces.iter().filter(|p| { return rejected(p); }).map(|p| { return responsible(p); }).collect();
+
+        if let Some(values) = failing.into_iter().reduce(Interval::union)
+        {
+
+            self.report(kind, values);
+
+            return (true, pieces);
+
+        }
+
+        return (false, pieces);
+
+    }
+
+    fn enclose(&mut self, term: Subterm<'_, T>, t: Interval) -> Result<Interval, EvalError>
+    {
+
+        match term.to_view()
+        {
+
+            TermView::Constant(c) => return Ok(self.point(c)),
+            TermView::Variable => return Ok(t),
+            TermView::Sequence(operation, terms) =>
+            {
+
+                let mut computed = Interval { min: operation.unit::<f64>(), max: operation.unit::<f64>() };
+
+                for (i, subterm) in terms.into_iter().enumerate()
+                {
+
+                    let value = self.child(i, subterm, t)?;
+
+                    computed = match operation
+                    {
+
+                        SequenceOperations::Addition => self.add(computed, value),
+                        SequenceOperations::Multiplication => self.multiply(computed, value)
+
+                    };
+
+                }
+
+                return Ok(computed);
+
+            },
+            TermView::Scalar(subterm, scale) =>
+            {
+
+                let value = self.child(0, subterm, t)?;
+                let scale = self.point(scale);
+
+                return Ok(self.multiply(scale, value));
+
+            },
+            TermView::Fraction(numerator, denominator) =>
+            {
+
+                let n = self.child(0, numerator, t)?;
+                let d = self.child(1, denominator, t)?;
+
+                if d.contains(0.0)
+                {
+
+                    if self.diagnostics.is_none()
+                    {
+
+                        return Err(EvalError::new(EvalErrorKind::DivisionByZero));
+
+                    }
+
+                    let (reported, pieces) = self.check(DiagnosticKind::DivisionByZero, &[numerator, denominator], t, |v| { return v[1].contains(0.0); }, |v| { return v[1]; });
+
+                    if reported
+                    {
+
+                        return Ok(Interval::entire());
+
+                    }
+
+                    let quotients : Vec<Interval> = pieces.iter().map(|p| { return self.divide(p[0], p[1]); }).collect();
+
+                    return Ok(quotients.into_iter().reduce(Interval::union).unwrap_or(Interval::entire()));
+
+                }
+
+                return Ok(self.divide(n, d));
+
+            },
+            TermView::Function(subterm, function, identifier) =>
+            {
+
+                let value = self.child(0, subterm, t)?;
+
+                let domain = identifier.and_then(|id| { return self.arena.function_domain(id).or_else(|| { return standard::find(id).and_then(|standard| { return standard.domain; }); }); });
+
+                if let Some(domain) = domain
+                {
+
+                    let outside = move |v: &[Interval]| { return v[0].min < domain.min || v[0].max > domain.max; };
+
+                    if self.diagnostics.is_some() && outside(&[value])
+                    {
+
+                        self.check(DiagnosticKind::Domain, &[subterm], t, outside, |v| { return v[0]; });
+
+                    }
+
+                }
+
+                return Ok(self.apply(function, identifier, value));
+
+            },
+            TermView::Random(min_term, max_term) =>
+            {
+
+                let min = self.child(0, min_term, t)?;
+                let max = self.child(1, max_term, t)?;
+
+                if self.diagnostics.is_some() && max.min <= min.max
+                {
+
+                    self.check(DiagnosticKind::RandomRange, &[min_term, max_term], t, |v| { return v[1].min <= v[0].max; }, |v| { return Interval::new(v[1].min - v[0].max, v[1].max - v[0].min); });
+
+                }
+
+                return Ok(self.finish(min.min, max.max, true));
+
+            },
+            TermView::Distribution(distribution, parameters) =>
+            {
+
+                let mut values = Vec::new();
+
+                for (i, parameter) in parameters.into_iter().enumerate()
+                {
+
+                    values.push(self.child(i, parameter, t)?);
+
+                }
+
+                let (min, max) = match distribution
+                {
+
+                    //A normal distribution is unbounded unless its standard deviation is 0
+                    Distribution::Normal if values[1].max <= 0.0 => (values[0].min, values[0].max),
+                    Distribution::Normal => (f64::NEG_INFINITY, f64::INFINITY),
+                    Distribution::LogNormal | Distribution::Exponential | Distribution::Poisson => (0.0, f64::INFINITY),
+                    Distribution::Triangular => (values[0].min, values[1].max),
+                    Distribution::Bernoulli => (0.0, 1.0)
+
+                };
+
+                return Ok(self.finish(min, max, true));
+
+            },
+            TermView::Choice(choices) =>
+            {
+
+                let mut computed : Option<Interval> = None;
+
+                for (i, choice) in choices.into_iter().enumerate()
+                {
+
+                    self.child(2 * i, choice.0, t)?;
+
+                    let value = self.child(2 * i + 1, choice.1, t)?;
+
+                    computed = Some(computed.map_or(value, |c| { return c.union(value); }));
+
+                }
+
+                return Ok(computed.unwrap_or(Interval::entire()));
+
+            },
+            TermView::Noise(subterm, _) =>
+            {
+
+                self.child(0, subterm, t)?;
+
+                //Noise is scaled to stay within -1 and 1
+                return Ok(self.finish(-1.0, 1.0, true));
+
+            },
+            TermView::Piecewise(parts, cycle) =>
+            {
+
+                let mut computed : Option<Interval> = None;
+
+                for input in self.wrap(t, cycle)?
+                {
+
+                    //A part applies from the largest after value up to and including its own, until
+                    //the next part's after value
+                    let mut start = f64::NEG_INFINITY;
+
+                    for (i, part) in parts.iter().enumerate()
+                    {
+
+                        if i > 0
+                        {
+
+                            start = start.max(self.point(part.1).min);
+
+                        }
+
+                        let end = parts.get(i + 1).map_or(f64::INFINITY, |next| { return self.point(next.1).max; });
+
+                        if input.max < start || input.min >= end
+                        {
+
+                            continue;
+
+                        }
+
+                        let value = self.child(i, part.0, Interval { min: input.min.max(start), max: input.max.min(end) })?;
+
+                        computed = Some(computed.map_or(value, |c| { return c.union(value); }));
+
+                    }
+
+                }
+
+                //A piecewise term without parts is 0
+                return Ok(computed.unwrap_or(Interval { min: 0.0, max: 0.0 }));
+
+            },
+            TermView::Opaque =>
+            {
+
+                self.undefined = true;
+
+                return Ok(Interval::entire());
+
+            }
+
+        }
+
+    }
+
+    //Encloses the values of t within a piecewise term, which are wrapped into the loop when they
+    //exceed its loop value
+    fn wrap(&mut self, t: Interval, cycle: Option<T>) -> Result<Vec<Interval>, EvalError>
+    {
+
+        let c = match cycle
+        {
+
+            Some(c) => self.point(c),
+            None => return Ok(vec![t])
+
+        };
+
+        if t.max <= c.min
+        {
+
+            return Ok(vec![t]);
+
+        }
+
+        if c.contains(0.0)
+        {
+
+            if self.report(DiagnosticKind::DivisionByZero, c)
+            {
+
+                return Ok(vec![t]);
+
+            }
+
+            return Err(EvalError::new(EvalErrorKind::DivisionByZero));
+
+        }
+
+        let mut inputs = Vec::new();
+
+        if t.min <= c.max
+        {
+
+            inputs.push(Interval { min: t.min, max: t.max.min(c.max) });
+
+        }
+
+        //The remainder has the sign of t, and is smaller than the loop value
+        let bound = c.min.abs().max(c.max.abs());
+        let start = t.min.max(c.min);
+
+        if c.min > 0.0 && c.min == c.max
+        {
+
+            let loops = (start / c.min).floor();
+
+            if t.max < (loops + 1.0) * c.min
+            {
+
+                inputs.push(self.finish(start - loops * c.min, t.max - loops * c.min, false));
+
+                return Ok(inputs);
+
+            }
+
+        }
+
+        inputs.push(Interval { min: if start < 0.0 { -bound } else { 0.0 }, max: if t.max > 0.0 { bound } else { 0.0 } });
+
+        return Ok(inputs);
+
+    }
+
+    //Encloses the output of a function, which is only bounded if it is monotonic or periodic
+    fn apply(&mut self, function: fn(f64) -> f64, identifier: Option<&str>, x: Interval) -> Interval
+    {
+
+        let declared = identifier.and_then(|id| { return self.arena.function_monotonicity(id); }).map(Bounds::Monotonic);
+        let bounds = declared.or_else(|| { return identifier.and_then(standard::find).and_then(|standard| { return standard.bounds; }); });
+
+        let (min, max) = match bounds
+        {
+
+            Some(Bounds::Monotonic(Monotonicity::Increasing)) => (function(x.min), function(x.max)),
+            Some(Bounds::Monotonic(Monotonicity::Decreasing)) => (function(x.max), function(x.min)),
+            Some(Bounds::Periodic(peak)) => periodic(function, x, peak),
+            Some(Bounds::Poles(first, spacing)) =>
+            {
+
+                let pole = ((x.min - first) / spacing).ceil() * spacing + first;
+
+                if !x.is_bounded() || pole <= x.max { (f64::NEG_INFINITY, f64::INFINITY) } else { (function(x.min), function(x.max)) }
+
+            },
+            _ =>
+            {
+
+                self.undefined = true;
+
+                (f64::NEG_INFINITY, f64::INFINITY)
+
+            }
+
+        };
+
+        return self.finish(min, max, true);
+
+    }
+
+}
+
+//Whether s is exactly the sum of a and b, using the error of the sum found by Knuth's TwoSum
+fn exact_sum(a: f64, b: f64, s: f64) -> bool
+{
+
+    let v = s - a;
+
+    return (a - (s - v)) + (b - v) == 0.0;
+
+}
+
+//The smallest or largest of the computed bounds, which is only exact if every bound equal to it is
+fn extreme(bounds: &[Bound], smallest: bool) -> Bound
+{
+
+    let value = if smallest
+    {
+
+        bounds.iter().map(|b| { return b.0; }).fold(f64::INFINITY, f64::min)
+
+    }
+    else
+    {
+
+        bounds.iter().map(|b| { return b.0; }).fold(f64::NEG_INFINITY, f64::max)
+
+    };
+
+    return (value, bounds.iter().all(|b| { return b.0 != value || b.1; }));
+
+}
+
+//Whether the value can be represented exactly by the type
+fn representable<T: Number>(value: f64) -> bool
+{
+
+    return T::from_f64(value).and_then(|v| { return v.to_f64(); }) == Some(value);
+
+}
+
+//Encloses sin or cos, given the offset of the first maximum after 0
+fn periodic(function: fn(f64) -> f64, x: Interval, peak: f64) -> (f64, f64)
+{
+
+    if !x.is_bounded() || x.max - x.min >= 2.0 * PI
+    {
+
+        return (-1.0, 1.0);
+
+    }
+
+    let (a, b) = (function(x.min), function(x.max));
+
+    //Whether a multiple of 2 pi offset by the given amount is within the interval
+    let reaches = |offset: f64| { return ((x.min - offset) / (2.0 * PI)).ceil() * 2.0 * PI + offset <= x.max; };
+
+    let min = if reaches(peak + PI) { -1.0 } else { a.min(b) };
+    let max = if reaches(peak) { 1.0 } else { a.max(b) };
+
+    return (min, max);
+
+}
+
+//The relative precision of a floating point type, and its smallest positive value, which bound the
+//difference between an exact result and its rounded value
+fn precision<T: Number>() -> (f64, f64)
+{
+
+    let mut relative = 1.0_f64;
+
+    while relative / 2.0 >= f64::EPSILON && T::from_f64(1.0 + relative / 2.0).is_some_and(|v| { return v > T::one(); })
+    {
+
+        relative /= 2.0;
+
+    }
+
+    let mut absolute = 1.0_f64;
+
+    while absolute / 2.0 > 0.0 && T::from_f64(absolute / 2.0).is_some_and(|v| { return v > T::zero(); })
+    {
+
+        absolute /= 2.0;
+
+    }
+
+    return (relative, absolute);
+
+}
+
+#[cfg(test)]
+mod interval_tests
+{
+
+    use crate::Parametrizer;
+    use crate::ParametrizerFunction;
+    use crate::EvalErrorKind;
+    use crate::OverflowPolicy;
+
+    //Checks that every sampled value between a and b is within the enclosure
+    fn check(function: &Parametrizer<f64>, a: f64, b: f64)
+    {
+
+        let interval = function.evaluate_interval(a, b).unwrap();
+
+        for i in 0..=1000
+        {
+
+            let t = a + (b - a) * i as f64 / 1000.0;
+
+            if let Ok(value) = function.try_evaluate(t)
+            {
+
+                assert!(interval.contains(value), "{} at {} is outside {:?}", value, t, interval);
+
+            }
+
+        }
+
+    }
+
+    #[test]
+    fn test_enclosures ()
+    {
+
+        let functions = || { return vec![ParametrizerFunction::new("sin".to_string(), f64::sin), ParametrizerFunction::new("cos".to_string(), f64::cos), ParametrizerFunction::new("tan".to_string(), f64::tan), ParametrizerFunction::new("exp".to_string(), f64::exp)]; };
+
+        let cases = ["t*t*t - 2*t", "sin(3*t) + cos(t*t)", "exp(sin(t))/(2 + cos(t))", "tan(t/4)", "p t>0|5-t*t>1|sin(t)>2", "p[2]t*t>0|1-t>1", "rd(t<t+1)*2", "noise(t)+1"];
+
+        for case in cases
+        {
+
+            let function = Parametrizer::new_functions(case, functions()).unwrap();
+
+            check(&function, -1.5, 3.5);
+            check(&function, 0.25, 0.5);
+
+        }
+
+        //The ends of a monotonic function are exact
+        let exp = Parametrizer::new_functions("exp(t)", functions()).unwrap().evaluate_interval(0.0, 1.0).unwrap();
+
+        assert!(exp.min <= 1.0 && exp.min > 0.999_999 && exp.max >= 1.0_f64.exp() && exp.max < 2.718_29);
+
+        //Unknown functions are unbounded
+        let unknown = Parametrizer::new_functions("cbrt(t)", vec![ParametrizerFunction::new("cbrt".to_string(), f64::cbrt)]).unwrap();
+
+        assert!(!unknown.evaluate_interval(0.0, 1.0).unwrap().is_bounded());
+
+    }
+
+    #[test]
+    fn test_integers ()
+    {
+
+        let truncated = Parametrizer::<i32>::new("(t+1)/2").unwrap().evaluate_interval(0, 6).unwrap();
+
+        assert_eq!((0.0, 3.0), (truncated.min, truncated.max));
+
+        let mut overflowing = Parametrizer::<i8>::new("t*t").unwrap();
+
+        assert_eq!((0.0, 127.0), { let i = overflowing.evaluate_interval(0, 20).unwrap(); (i.min, i.max) });
+
+        overflowing.set_overflow_policy(OverflowPolicy::Wrapping);
+
+        assert_eq!((-128.0, 127.0), { let i = overflowing.evaluate_interval(0, 20).unwrap(); (i.min, i.max) });
+
+        let pole = Parametrizer::<i32>::new("3 + 10/(t-2)").unwrap();
+
+        assert_eq!(EvalErrorKind::DivisionByZero, pole.evaluate_interval(0, 5).unwrap_err().kind());
+        assert_eq!(&[1], pole.evaluate_interval(0, 5).unwrap_err().path());
+        assert!(pole.evaluate_interval(3, 5).is_ok());
+
+    }
+
+}
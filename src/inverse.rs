@@ -0,0 +1,401 @@
+use std::fmt;
+
+use crate::Number;
+use crate::EvalError;
+use crate::EvalErrorKind;
+use crate::RootError;
+use crate::overflow;
+use crate::interval;
+use crate::roots;
+use crate::sampler;
+use crate::sampler::Sampler;
+use crate::term::arenaterm::ArenaTerm;
+
+//The number of samples taken in each segment between breakpoints when checking monotonicity
+const SAMPLES : usize = 256;
+//How many times the ranges between samples are halved when looking for a decrease of an increasing
+//function, or an increase of a decreasing one, using the derivative
+const MAX_DEPTH : usize = 8;
+
+///The reason why a function could not be inverted by Parametrizer::inverse
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InverseError
+{
+
+    ///The function is not strictly increasing or strictly decreasing over the domain
+    NotMonotonic,
+    ///The function failed to evaluate while checking monotonicity
+    Evaluation(EvalError)
+
+}
+
+impl From<EvalError> for InverseError
+{
+
+    fn from(error: EvalError) -> InverseError
+    {
+
+        return InverseError::Evaluation(error);
+
+    }
+
+}
+
+impl fmt::Display for InverseError
+{
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+
+        match self
+        {
+
+            InverseError::NotMonotonic => return write!(f, "Parametrizer could not invert the function, as it is not monotonic over the domain"),
+            InverseError::Evaluation(error) => return write!(f, "Parametrizer failed to invert the function due to an evaluation error: {}", error)
+
+        }
+
+    }
+
+}
+
+///The inverse of a function which is monotonic over a domain, created by Parametrizer::inverse.
+///Finds the value of t within the domain at which the function equals a given value, by root
+///finding within a bracket. The bracket is the whole domain unless a lookup table is added with
+///Inverse::with_table, which narrows it to the two neighbouring entries of the table.
+pub struct Inverse<'a, T: Number>
+{
+
+    term: &'a ArenaTerm<T>,
+    derivative: Option<ArenaTerm<T>>,
+    domain: (T, T),
+    range: (T, T),
+    increasing: bool,
+    //Pairs of t and the value of the function, in order of t
+    table: Vec<(f64, f64)>
+
+}
+
+//Checks that the function is monotonic between a and b, sampling each segment between breakpoints
+//and, if the derivative is known, checking its sign between the samples with interval arithmetic
+pub(crate) fn inverse<T: Number>(term: &ArenaTerm<T>, derivative: Option<ArenaTerm<T>>, a: T, b: T) -> Result<Inverse<'_, T>, InverseError>
+{
+
+    let conversion = || { return EvalError::new(EvalErrorKind::FunctionConversion); };
+
+    let (start, end) = (a.to_f64().ok_or_else(conversion)?, b.to_f64().ok_or_else(conversion)?);
+    let (start, end) = (start.min(end), start.max(end));
+
+    let sampler = Sampler::new(term);
+    let segments = sampler::segments(term, start, end);
+    let integer = overflow::is_integer::<T>();
+
+    //The samples in order of t, including the values approached at the end of each part, and
+    //whether each starts a new part
+    let mut samples = Vec::new();
+
+    for (i, segment) in segments.windows(2).enumerate()
+    {
+
+        let last = i + 2 == segments.len();
+        let step = if integer { ((segment[1] - segment[0]) / SAMPLES as f64).ceil().max(1.0) } else { (segment[1] - segment[0]) / SAMPLES as f64 };
+
+        let mut k = 0.0;
+
+        while segment[0] + k * step < segment[1]
+        {
+
+            let x = segment[0] + k * step;
+
+            samples.push((x, sampler.evaluate(x)?, i > 0 && k == 0.0));
+            k += 1.0;
+
+        }
+
+        let end = if last { sampler.evaluate(segment[1])? } else { sampler.evaluate_inside(segment[1], segment[0])? };
+
+        samples.push((segment[1], end, false));
+
+    }
+
+    let increasing = samples[samples.len() - 1].1 >= samples[0].1;
+
+    for pair in samples.windows(2)
+    {
+
+        let ((a, first, _), (b, second, starts)) = (pair[0], pair[1]);
+
+        //The value approached at the end of a part may equal the value at the start of the next
+        let rises = if increasing { second > first } else { second < first };
+
+        if !(rises || (starts && second == first))
+        {
+
+            return Err(InverseError::NotMonotonic);
+
+        }
+
+        if let Some(derivative) = &derivative
+        {
+
+            if !starts && reverses(derivative, a, b, increasing, MAX_DEPTH)?
+            {
+
+                return Err(InverseError::NotMonotonic);
+
+            }
+
+        }
+
+    }
+
+    let range = (T::from_f64(sampler.evaluate(start)?).ok_or_else(conversion)?, T::from_f64(sampler.evaluate(end)?).ok_or_else(conversion)?);
+
+    return Ok(Inverse { term, derivative, domain: (T::from_f64(start).ok_or_else(conversion)?, T::from_f64(end).ok_or_else(conversion)?), range, increasing, table: Vec::new() });
+
+}
+
+//Whether the derivative certainly has the wrong sign somewhere between a and b, halving the range
+//until the interval enclosing the derivative has the right sign or the depth runs out
+fn reverses<T: Number>(derivative: &ArenaTerm<T>, a: f64, b: f64, increasing: bool, depth: usize) -> Result<bool, EvalError>
+{
+
+    let (ta, tb) = match (T::from_f64(a), T::from_f64(b))
+    {
+
+        (Some(ta), Some(tb)) => (ta, tb),
+        _ => return Ok(false)
+
+    };
+
+    //The derivative may be undefined where the function is not, e.g. for sqrt(t) at 0, in which
+    //case the samples decide
+    let slopes = match interval::enclose(derivative, ta, tb)
+    {
+
+        Ok(slopes) => slopes,
+        Err(_) => return Ok(false)
+
+    };
+
+    if (increasing && slopes.max < 0.0) || (!increasing && slopes.min > 0.0)
+    {
+
+        return Ok(true);
+
+    }
+
+    if (increasing && slopes.min >= 0.0) || (!increasing && slopes.max <= 0.0) || depth == 0
+    {
+
+        return Ok(false);
+
+    }
+
+    let middle = a + (b - a) / 2.0;
+
+    if middle <= a || middle >= b
+    {
+
+        return Ok(false);
+
+    }
+
+    return Ok(reverses(derivative, a, middle, increasing, depth - 1)? || reverses(derivative, middle, b, increasing, depth - 1)?);
+
+}
+
+impl<'a, T: Number> Inverse<'a, T>
+{
+
+    ///Precomputes the function at size + 1 evenly spaced values of t across the domain, so that
+    ///each evaluation of the inverse only searches between two neighbouring entries. Larger tables
+    ///make evaluation faster at the cost of memory. Integer types are tabulated at integers.
+    ///Returns an error if the function fails to evaluate at an entry.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    ///
+    /// let growth = Parametrizer::<f64>::new("t*t*t + t").unwrap();
+    /// let inverse = growth.inverse((0.0, 10.0)).unwrap().with_table(64).unwrap();
+    ///
+    /// assert!((inverse.evaluate(130.0).unwrap() - 5.0).abs() < 1e-9);
+    /// ```
+    pub fn with_table(mut self, size: usize) -> Result<Inverse<'a, T>, EvalError>
+    {
+
+        let conversion = || { return EvalError::new(EvalErrorKind::FunctionConversion); };
+
+        let (start, end) = (self.domain.0.to_f64().ok_or_else(conversion)?, self.domain.1.to_f64().ok_or_else(conversion)?);
+        let size = size.max(1);
+        let sampler = Sampler::new(self.term);
+
+        let mut table = Vec::with_capacity(size + 1);
+
+        for k in 0..=size
+        {
+
+            let mut x = start + (end - start) * k as f64 / size as f64;
+
+            if overflow::is_integer::<T>()
+            {
+
+                x = x.round();
+
+            }
+
+            if table.last().map(|entry: &(f64, f64)| { return entry.0; }) != Some(x)
+            {
+
+                table.push((x, sampler.evaluate(x)?));
+
+            }
+
+        }
+
+        self.table = table;
+
+        return Ok(self);
+
+    }
+
+    ///Finds the value of t within the domain at which the function equals the value. Uses the same
+    ///root finding as Parametrizer::find_root, so for integer types the function must equal the
+    ///value exactly at an integer. Returns RootError::NoRoot if the value is outside of the range
+    ///of the function over the domain, or is skipped by a jump at a breakpoint.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    /// use crate::parametrizer::RootError;
+    ///
+    /// //Resumes an ease in-out curve at its current value
+    /// let ease = Parametrizer::<f64>::new("3*t*t - 2*t*t*t").unwrap();
+    /// let inverse = ease.inverse((0.0, 1.0)).unwrap();
+    ///
+    /// let t = inverse.evaluate(0.8).unwrap();
+    ///
+    /// assert!((ease.evaluate(t) - 0.8).abs() < 1e-12);
+    /// assert_eq!(Err(RootError::NoRoot), inverse.evaluate(1.5));
+    /// ```
+    pub fn evaluate(&self, value: T) -> Result<T, RootError>
+    {
+
+        let (low, high) = if self.increasing { self.range } else { (self.range.1, self.range.0) };
+
+        if value < low || value > high
+        {
+
+            return Err(RootError::NoRoot);
+
+        }
+
+        let mut bracket = self.domain;
+
+        if self.table.len() > 1
+        {
+
+            let target = value.to_f64().ok_or(EvalError::new(EvalErrorKind::FunctionConversion))?;
+
+            //The first entry past the value, so that the value is between it and the one before
+            let index = self.table.partition_point(|entry| { return if self.increasing { entry.1 < target } else { entry.1 > target }; });
+            let index = index.clamp(1, self.table.len() - 1);
+
+            if let (Some(a), Some(b)) = (T::from_f64(self.table[index - 1].0), T::from_f64(self.table[index].0))
+            {
+
+                bracket = (a, b);
+
+            }
+
+        }
+
+        return roots::find_root(self.term, self.derivative.as_ref(), value, bracket);
+
+    }
+
+    ///Returns whether the function increases over the domain, rather than decreasing
+    pub fn increasing(&self) -> bool
+    {
+
+        return self.increasing;
+
+    }
+
+    ///Returns the domain, i.e. the smallest and largest values of t which may be returned
+    pub fn domain(&self) -> (T, T)
+    {
+
+        return self.domain;
+
+    }
+
+    ///Returns the values of the function at the start and end of the domain, between which every
+    ///value returned by the function over the domain lies
+    pub fn range(&self) -> (T, T)
+    {
+
+        return self.range;
+
+    }
+
+}
+
+#[cfg(test)]
+mod inverse_tests
+{
+
+    use crate::Parametrizer;
+    use crate::RootError;
+    use super::InverseError;
+
+    #[test]
+    fn test_monotonicity ()
+    {
+
+        let wave = Parametrizer::<f64>::new("t*t - 2*t").unwrap();
+
+        assert_eq!(Some(InverseError::NotMonotonic), wave.inverse((0.0, 3.0)).err());
+        assert!(wave.inverse((1.0, 3.0)).unwrap().increasing());
+        assert!(!wave.inverse((-2.0, 1.0)).unwrap().increasing());
+
+        //A dip narrower than the samples is found using the derivative
+        let dip = Parametrizer::<f64>::new("t - 0.01/(1 + 1000000*(t - 0.5)*(t - 0.5))").unwrap();
+
+        assert_eq!(Some(InverseError::NotMonotonic), dip.inverse((0.0, 1.0)).err());
+
+        //Flat parts cannot be inverted, but jumps in the right direction can
+        assert_eq!(Some(InverseError::NotMonotonic), Parametrizer::<f64>::new("p t>0|1>1|t>2").unwrap().inverse((0.0, 3.0)).err());
+
+        let steps = Parametrizer::<f64>::new("p 3 - t>0|1 - t>1").unwrap();
+        let inverse = steps.inverse((0.0, 3.0)).unwrap().with_table(8).unwrap();
+
+        assert_eq!((3.0, -2.0), inverse.range());
+        assert_eq!(Ok(0.5), inverse.evaluate(2.5));
+        assert_eq!(Ok(2.5), inverse.evaluate(-1.5));
+        assert_eq!(Err(RootError::NoRoot), inverse.evaluate(1.0));
+
+    }
+
+    #[test]
+    fn test_integers ()
+    {
+
+        let integer = Parametrizer::<i32>::new("1000 - t*t").unwrap();
+        let inverse = integer.inverse((0, 30)).unwrap();
+        let table = integer.inverse((0, 30)).unwrap().with_table(7).unwrap();
+
+        for t in 0..=30
+        {
+
+            assert_eq!(Ok(t), inverse.evaluate(1000 - t*t));
+            assert_eq!(Ok(t), table.evaluate(1000 - t*t));
+
+        }
+
+        assert_eq!(Err(RootError::NoRoot), table.evaluate(998));
+
+    }
+
+}
@@ -0,0 +1,761 @@
+//! Compilation of parametrized functions to native machine code using Cranelift. Only available
+//! with the `jit` feature, and only for `Parametrizer<f32>` and `Parametrizer<f64>`.
+//!
+//! Constants, variables, sequences, scalars, fractions, user functions and piecewise terms are
+//! lowered directly to machine code. Terms which cannot be lowered, such as random terms and
+//! user-defined terms, are evaluated by calling back into the interpreter, so every parametrized
+//! function can be compiled.
+//!
+//! # Examples
+//! ```
+//! use crate::parametrizer::Parametrizer;
+//!
+//! let parametrizer = Parametrizer::new("1+5*t+25*t*t").unwrap();
+//! let compiled = parametrizer.compile().unwrap();
+//!
+//! assert_eq!(parametrizer.evaluate(3.0), compiled.evaluate(3.0));
+//! assert_eq!(111.0, compiled.evaluate(2.0));
+//! ```
+
+use std::any::Any;
+use std::fmt;
+use std::mem;
+use std::panic;
+
+use cranelift_codegen::ir::condcodes::FloatCC;
+use cranelift_codegen::ir::types;
+use cranelift_codegen::ir::AbiParam;
+use cranelift_codegen::ir::InstBuilder;
+use cranelift_codegen::ir::SigRef;
+use cranelift_codegen::ir::Signature;
+use cranelift_codegen::ir::Type;
+use cranelift_codegen::ir::Value;
+use cranelift_codegen::settings;
+use cranelift_codegen::settings::Configurable;
+use cranelift_frontend::FunctionBuilder;
+use cranelift_frontend::FunctionBuilderContext;
+use cranelift_jit::JITBuilder;
+use cranelift_jit::JITModule;
+use cranelift_module::Linkage;
+use cranelift_module::Module;
+
+use crate::term::sequenceterm::SequenceOperations;
+use crate::term::Subterm;
+use crate::term::Term;
+use crate::term::TermView;
+use crate::Number;
+use crate::NonFinitePolicy;
+use crate::Parametrizer;
+
+mod private
+{
+
+    pub trait Sealed {}
+
+    impl Sealed for f32 {}
+    impl Sealed for f64 {}
+
+}
+
+///A floating point type which parametrized functions can be compiled for. Implemented for f32 and
+///f64.
+pub trait JitFloat: Number + private::Sealed
+{
+
+    ///The Cranelift type corresponding to Self
+    fn ir_type() -> Type;
+
+    ///Emits a constant of type Self
+    fn constant(builder: &mut FunctionBuilder, value: Self) -> Value;
+
+    ///Converts a value of type Self to f64, which is the type taken by ParametrizerFunctions
+    fn promote(builder: &mut FunctionBuilder, value: Value) -> Value;
+
+    ///Converts an f64 value back to type Self
+    fn demote(builder: &mut FunctionBuilder, value: Value) -> Value;
+
+}
+
+impl JitFloat for f32
+{
+
+    fn ir_type() -> Type
+    {
+
+        return types::F32;
+
+    }
+
+    fn constant(builder: &mut FunctionBuilder, value: f32) -> Value
+    {
+
+        return builder.ins().f32const(value);
+
+    }
+
+    fn promote(builder: &mut FunctionBuilder, value: Value) -> Value
+    {
+
+        return builder.ins().fpromote(types::F64, value);
+
+    }
+
+    fn demote(builder: &mut FunctionBuilder, value: Value) -> Value
+    {
+
+        return builder.ins().fdemote(types::F32, value);
+
+    }
+
+}
+
+impl JitFloat for f64
+{
+
+    fn ir_type() -> Type
+    {
+
+        return types::F64;
+
+    }
+
+    fn constant(builder: &mut FunctionBuilder, value: f64) -> Value
+    {
+
+        return builder.ins().f64const(value);
+
+    }
+
+    fn promote(_builder: &mut FunctionBuilder, value: Value) -> Value
+    {
+
+        return value;
+
+    }
+
+    fn demote(_builder: &mut FunctionBuilder, value: Value) -> Value
+    {
+
+        return value;
+
+    }
+
+}
+
+///An error which describes why compilation failed
+#[derive(Debug)]
+pub struct JitError
+{
+
+    reason: String
+
+}
+
+impl fmt::Display for JitError
+{
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+
+        return write!(f, "Parametrizer failed to compile function, with failure reason: {}", self.reason);
+
+    }
+
+}
+
+impl JitError
+{
+
+    fn new<E: fmt::Display>(error: E) -> JitError
+    {
+
+        return JitError { reason: error.to_string() };
+
+    }
+
+}
+
+//Records the first failure encountered while running compiled code. Panics cannot unwind through
+//compiled frames, so they are caught at the boundary, stored here, and resumed once the compiled
+//function has returned.
+enum Failure
+{
+
+    DivisionByZero,
+    Panic(Box<dyn Any + Send>)
+
+}
+
+struct Status
+{
+
+    failure: Option<Failure>
+
+}
+
+impl Status
+{
+
+    fn fail(&mut self, failure: Failure)
+    {
+
+        if self.failure.is_none()
+        {
+
+            self.failure = Some(failure);
+
+        }
+
+    }
+
+}
+
+type Fallback<'a, T> = Subterm<'a, T>;
+
+extern "C" fn evaluate_fallback<T: JitFloat>(term: *const Fallback<'_, T>, t: T, status: *mut Status) -> T
+{
+
+    //Safety: term points into the boxed fallbacks owned by the CompiledParametrizer running this
+    //code, and status points to the Status on the stack of CompiledParametrizer::evaluate
+    let term = unsafe { *term };
+    let status = unsafe { &mut *status };
+
+    match panic::catch_unwind(panic::AssertUnwindSafe(|| { return term.evaluate(t); }))
+    {
+
+        Ok(value) => return value,
+        Err(payload) =>
+        {
+
+            status.fail(Failure::Panic(payload));
+
+            return T::zero();
+
+        }
+
+    }
+
+}
+
+extern "C" fn apply_function(function: *const (), x: f64, status: *mut Status) -> f64
+{
+
+    //Safety: function is the address of a fn(f64) -> f64 taken from a FunctionTerm
+    let function = unsafe { mem::transmute::<*const (), fn(f64) -> f64>(function) };
+    let status = unsafe { &mut *status };
+
+    match panic::catch_unwind(|| { return function(x); })
+    {
+
+        Ok(value) => return value,
+        Err(payload) =>
+        {
+
+            status.fail(Failure::Panic(payload));
+
+            return 0.0;
+
+        }
+
+    }
+
+}
+
+extern "C" fn divide_by_zero(status: *mut Status)
+{
+
+    let status = unsafe { &mut *status };
+
+    status.fail(Failure::DivisionByZero);
+
+}
+
+extern "C" fn remainder<T: JitFloat>(t: T, c: T) -> T
+{
+
+    return t % c;
+
+}
+
+///A parametrized function compiled to native machine code. Borrows the Parametrizer it was compiled
+///from, as terms which could not be compiled are evaluated through it.
+pub struct CompiledParametrizer<'a, T: JitFloat>
+{
+
+    //Kept alive for as long as the compiled code may run, and freed on drop
+    module: Option<JITModule>,
+    function: extern "C" fn(T, *mut Status) -> T,
+    //Boxed so that the addresses embedded in the compiled code stay fixed
+    #[allow(clippy::vec_box)]
+    _fallbacks: Vec<Box<Fallback<'a, T>>>
+
+}
+
+impl<T: JitFloat> CompiledParametrizer<'_, T>
+{
+
+    ///Computes the compiled function at a specific point. Produces exactly the same values as
+    ///Parametrizer::evaluate.
+    ///
+    /// # Panics
+    /// Panics if a denominator evaluates to 0, or if a term evaluated by the interpreter panics.
+    ///
+    /// ```should_panic
+    /// use crate::parametrizer::Parametrizer;
+    ///
+    /// let parametrizer = Parametrizer::new("1/(t+-2)").unwrap();
+    /// let compiled = parametrizer.compile().unwrap();
+    ///
+    /// compiled.evaluate(2.0_f64);
+    /// ```
+    pub fn evaluate(&self, t: T) -> T
+    {
+
+        let mut status = Status { failure: None };
+
+        let value = (self.function)(t, &mut status);
+
+        return match status.failure
+        {
+
+            None => value,
+            Some(Failure::DivisionByZero) => panic!("Cannot divide by 0 in compiled FractionTerm. Make sure the function you set as your denominator is never zero on your inputs."),
+            Some(Failure::Panic(payload)) => panic::resume_unwind(payload)
+
+        };
+
+    }
+
+}
+
+impl<T: JitFloat> Drop for CompiledParametrizer<'_, T>
+{
+
+    fn drop(&mut self)
+    {
+
+        if let Some(module) = self.module.take()
+        {
+
+            //Safety: the function pointer into this memory is dropped along with self
+            unsafe { module.free_memory(); }
+
+        }
+
+    }
+
+}
+
+//Walks a term tree and emits the instructions computing it
+struct Lowering<'a, T: JitFloat>
+{
+
+    pointer: Type,
+    #[allow(clippy::vec_box)]
+    fallbacks: Vec<Box<Fallback<'a, T>>>,
+    fallback_signature: Option<SigRef>,
+    function_signature: Option<SigRef>,
+    division_signature: Option<SigRef>,
+    remainder_signature: Option<SigRef>
+
+}
+
+impl<'a, T: JitFloat> Lowering<'a, T>
+{
+
+    fn signature(&self, builder: &mut FunctionBuilder, params: &[Type], returns: &[Type]) -> SigRef
+    {
+
+        let mut signature = Signature::new(builder.func.signature.call_conv);
+
+        signature.params.extend(params.iter().map(|p| { return AbiParam::new(*p); }));
+        signature.returns.extend(returns.iter().map(|r| { return AbiParam::new(*r); }));
+
+        return builder.import_signature(signature);
+
+    }
+
+    fn call(&self, builder: &mut FunctionBuilder, signature: SigRef, address: usize, args: &[Value]) -> Option<Value>
+    {
+
+        let callee = builder.ins().iconst(self.pointer, address as i64);
+        let call = builder.ins().call_indirect(signature, callee, args);
+
+        return builder.inst_results(call).first().copied();
+
+    }
+
+    fn fallback(&mut self, builder: &mut FunctionBuilder, term: Fallback<'a, T>, t: Value, status: Value) -> Value
+    {
+
+        let signature = match self.fallback_signature
+        {
+
+            Some(s) => s,
+            None => *self.fallback_signature.insert(self.signature(builder, &[self.pointer, T::ir_type(), self.pointer], &[T::ir_type()]))
+
+        };
+
+        let slot = Box::new(term);
+        let slot_address = builder.ins().iconst(self.pointer, &*slot as *const Fallback<'a, T> as i64);
+        self.fallbacks.push(slot);
+
+        return self.call(builder, signature, evaluate_fallback::<T> as *const () as usize, &[slot_address, t, status]).expect("Fallback signature returns a value.");
+
+    }
+
+    fn lower(&mut self, builder: &mut FunctionBuilder, term: Fallback<'a, T>, t: Value, status: Value) -> Value
+    {
+
+        match term.to_view()
+        {
+
+            TermView::Constant(c) => return T::constant(builder, c),
+            TermView::Variable => return t,
+            TermView::Sequence(operation, terms) =>
+            {
+
+                let mut computed = match operation
+                {
+
+                    SequenceOperations::Addition => T::constant(builder, T::zero()),
+                    SequenceOperations::Multiplication => T::constant(builder, T::one())
+
+                };
+
+                for subterm in terms
+                {
+
+                    let value = self.lower(builder, subterm, t, status);
+
+                    computed = match operation
+                    {
+
+                        SequenceOperations::Addition => builder.ins().fadd(computed, value),
+                        SequenceOperations::Multiplication => builder.ins().fmul(computed, value)
+
+                    };
+
+                }
+
+                return computed;
+
+            },
+            TermView::Scalar(subterm, scale) =>
+            {
+
+                let scale = T::constant(builder, scale);
+                let value = self.lower(builder, subterm, t, status);
+
+                return builder.ins().fmul(scale, value);
+
+            },
+            TermView::Fraction(numerator, denominator) =>
+            {
+
+                let signature = match self.division_signature
+                {
+
+                    Some(s) => s,
+                    None => *self.division_signature.insert(self.signature(builder, &[self.pointer], &[]))
+
+                };
+
+                let zero_block = builder.create_block();
+                let divide_block = builder.create_block();
+                let merge_block = builder.create_block();
+                builder.append_block_param(merge_block, T::ir_type());
+
+                //Evaluated in the same order as FractionTerm::evaluate
+                let d = self.lower(builder, denominator, t, status);
+                let zero = T::constant(builder, T::zero());
+                let is_zero = builder.ins().fcmp(FloatCC::Equal, d, zero);
+                builder.ins().brif(is_zero, zero_block, &[], divide_block, &[]);
+
+                builder.switch_to_block(zero_block);
+                self.call(builder, signature, divide_by_zero as *const () as usize, &[status]);
+                builder.ins().jump(merge_block, &[zero]);
+
+                builder.switch_to_block(divide_block);
+                let n = self.lower(builder, numerator, t, status);
+                let quotient = builder.ins().fdiv(n, d);
+                builder.ins().jump(merge_block, &[quotient]);
+
+                builder.switch_to_block(merge_block);
+
+                return builder.block_params(merge_block)[0];
+
+            },
+            TermView::Function(subterm, function, _) =>
+            {
+
+                let signature = match self.function_signature
+                {
+
+                    Some(s) => s,
+                    None => *self.function_signature.insert(self.signature(builder, &[self.pointer, types::F64, self.pointer], &[types::F64]))
+
+                };
+
+                let value = self.lower(builder, subterm, t, status);
+                let x = T::promote(builder, value);
+                let function = builder.ins().iconst(self.pointer, function as *const () as i64);
+
+                let y = self.call(builder, signature, apply_function as *const () as usize, &[function, x, status]).expect("Function signature returns a value.");
+
+                return T::demote(builder, y);
+
+            },
+            TermView::Piecewise(parts, cycle) =>
+            {
+
+                if parts.is_empty()
+                {
+
+                    return T::constant(builder, T::zero());
+
+                }
+
+                let mut t = t;
+
+                if let Some(c) = cycle
+                {
+
+                    let signature = match self.remainder_signature
+                    {
+
+                        Some(s) => s,
+                        None => *self.remainder_signature.insert(self.signature(builder, &[T::ir_type(), T::ir_type()], &[T::ir_type()]))
+
+                    };
+
+                    let loop_block = builder.create_block();
+                    let continue_block = builder.create_block();
+                    builder.append_block_param(continue_block, T::ir_type());
+
+                    let c = T::constant(builder, c);
+                    let past_loop = builder.ins().fcmp(FloatCC::GreaterThan, t, c);
+                    builder.ins().brif(past_loop, loop_block, &[], continue_block, &[t]);
+
+                    builder.switch_to_block(loop_block);
+                    let remainder = self.call(builder, signature, remainder::<T> as *const () as usize, &[t, c]).expect("Remainder signature returns a value.");
+                    builder.ins().jump(continue_block, &[remainder]);
+
+                    builder.switch_to_block(continue_block);
+                    t = builder.block_params(continue_block)[0];
+
+                }
+
+                let merge_block = builder.create_block();
+                builder.append_block_param(merge_block, T::ir_type());
+
+                //Mirrors PiecewiseTerm::evaluate: the previous part applies as soon as t is before
+                //the next part's after value
+                for i in 1..parts.len()
+                {
+
+                    let previous_block = builder.create_block();
+                    let next_block = builder.create_block();
+
+                    let after = T::constant(builder, parts[i].1);
+                    let reached = builder.ins().fcmp(FloatCC::GreaterThanOrEqual, t, after);
+                    builder.ins().brif(reached, next_block, &[], previous_block, &[]);
+
+                    builder.switch_to_block(previous_block);
+                    let value = self.lower(builder, parts[i - 1].0, t, status);
+                    builder.ins().jump(merge_block, &[value]);
+
+                    builder.switch_to_block(next_block);
+
+                }
+
+                let value = self.lower(builder, parts[parts.len() - 1].0, t, status);
+                builder.ins().jump(merge_block, &[value]);
+
+                builder.switch_to_block(merge_block);
+
+                return builder.block_params(merge_block)[0];
+
+            },
+            TermView::Random(_, _) | TermView::Distribution(_, _) | TermView::Choice(_) | TermView::Noise(_, _) | TermView::Opaque => return self.fallback(builder, term, t, status)
+
+        }
+
+    }
+
+}
+
+impl<T: JitFloat> Parametrizer<T>
+{
+
+    ///Compiles the parametric function to native machine code for the host CPU. Random terms and
+    ///user-defined terms are evaluated by the interpreter from inside the compiled code. Compiled
+    ///code always follows IEEE arithmetic, so compilation fails if a NonFinitePolicy other than
+    ///NonFinitePolicy::Propagate has been set.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    /// use crate::parametrizer::ParametrizerFunction;
+    ///
+    /// fn square(t: f64) -> f64
+    /// {
+    ///
+    ///     return t * t;
+    ///
+    /// }
+    ///
+    /// let trig = Parametrizer::new("2*sin(t+5)").unwrap();
+    /// let user = Parametrizer::new_functions("square(t)/2", vec![ ParametrizerFunction::new("square".to_string(), square) ]).unwrap();
+    /// let piecewise = Parametrizer::new("p[10]t>0|t*t-16>5").unwrap();
+    ///
+    /// let compiled_trig = trig.compile().unwrap();
+    /// let compiled_user = user.compile().unwrap();
+    /// let compiled_piecewise = piecewise.compile().unwrap();
+    ///
+    /// assert_eq!(2.0 * 6.0_f64.sin(), compiled_trig.evaluate(1.0));
+    /// assert_eq!(4.5_f32, compiled_user.evaluate(3.0));
+    /// assert_eq!(4.0, compiled_piecewise.evaluate(4.0));
+    /// assert_eq!(48.0, compiled_piecewise.evaluate(18.0));
+    /// ```
+    ///
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    ///
+    /// let random = Parametrizer::new("rd(t<2*t)+1").unwrap();
+    /// let compiled = random.compile().unwrap();
+    ///
+    /// let value = compiled.evaluate(4.0);
+    ///
+    /// assert!(value >= 5.0 && value < 9.0);
+    /// ```
+    pub fn compile(&self) -> Result<CompiledParametrizer<'_, T>, JitError>
+    {
+
+        if self.term.non_finite_policy() != NonFinitePolicy::Propagate
+        {
+
+            return Err(JitError::new("Compiled functions only support NonFinitePolicy::Propagate."));
+
+        }
+
+        let mut flags = settings::builder();
+        flags.set("opt_level", "speed").map_err(JitError::new)?;
+
+        let isa = cranelift_native::builder().map_err(JitError::new)?.finish(settings::Flags::new(flags)).map_err(JitError::new)?;
+
+        let mut module = JITModule::new(JITBuilder::with_isa(isa, cranelift_module::default_libcall_names()));
+
+        let pointer = module.target_config().pointer_type();
+
+        let mut context = module.make_context();
+        context.func.signature.params.push(AbiParam::new(T::ir_type()));
+        context.func.signature.params.push(AbiParam::new(pointer));
+        context.func.signature.returns.push(AbiParam::new(T::ir_type()));
+
+        let mut lowering = Lowering::<T> { pointer, fallbacks: Vec::new(), fallback_signature: None, function_signature: None, division_signature: None, remainder_signature: None };
+
+        let mut builder_context = FunctionBuilderContext::new();
+        let mut builder = FunctionBuilder::new(&mut context.func, &mut builder_context);
+
+        let entry = builder.create_block();
+        builder.append_block_params_for_function_params(entry);
+        builder.switch_to_block(entry);
+
+        let t = builder.block_params(entry)[0];
+        let status = builder.block_params(entry)[1];
+
+        let value = lowering.lower(&mut builder, self.term.root(), t, status);
+        builder.ins().return_(&[value]);
+
+        builder.seal_all_blocks();
+        builder.finalize();
+
+        let id = module.declare_function("parametrizer", Linkage::Local, &context.func.signature).map_err(JitError::new)?;
+        module.define_function(id, &mut context).map_err(JitError::new)?;
+        module.clear_context(&mut context);
+        module.finalize_definitions().map_err(JitError::new)?;
+
+        let code = module.get_finalized_function(id);
+
+        //Safety: the function was declared above with exactly this signature
+        let function = unsafe { mem::transmute::<*const u8, extern "C" fn(T, *mut Status) -> T>(code) };
+
+        return Ok(CompiledParametrizer { module: Some(module), function, _fallbacks: lowering.fallbacks });
+
+    }
+
+}
+
+#[cfg(test)]
+mod jit_tests
+{
+
+    use super::*;
+
+    #[test]
+    fn test_matches_interpreter ()
+    {
+
+        let params = ["1+5*t+25*t*t", "13+((2*t)+5)", "6/(t+1)", "-t", "13-t*t", "sin(t*t+t-1)", "cos(t)/3", "p2>0|4>2|8>6", "p2*t>0|sin(t)>2|9-t>6", "p[3.5]4>0|8>2", "p[10]t>0|t*t-16>5", "rc(4<8)*t"];
+        let inputs = [-7.5, -2.0, 0.0, 0.16, 1.0, 2.0, 2.5, 3.4, 6.0, 8.9, 18.0, 23.0, 30.1, 106.0];
+
+        for param in params.iter()
+        {
+
+            let parametrizer = Parametrizer::<f64>::new(param).expect("Parsing failed.");
+            let compiled = parametrizer.compile().expect("Compilation failed.");
+
+            let parametrizer_32 = Parametrizer::<f32>::new(param).expect("Parsing failed.");
+            let compiled_32 = parametrizer_32.compile().expect("Compilation failed.");
+
+            for t in inputs.iter()
+            {
+
+                assert_eq!(parametrizer.evaluate(*t).to_bits(), compiled.evaluate(*t).to_bits(), "Compiled {} differs at {}", param, t);
+                assert_eq!(parametrizer_32.evaluate(*t as f32).to_bits(), compiled_32.evaluate(*t as f32).to_bits(), "Compiled {} differs at {}", param, t);
+
+            }
+
+        }
+
+    }
+
+    #[test]
+    fn test_rejects_non_finite_policy ()
+    {
+
+        let mut parametrizer = Parametrizer::<f64>::new("1/t").expect("Parsing failed.");
+
+        parametrizer.set_non_finite_policy(NonFinitePolicy::Fallback(0.0));
+
+        assert!(parametrizer.compile().is_err());
+
+        parametrizer.set_non_finite_policy(NonFinitePolicy::Propagate);
+
+        assert!(parametrizer.compile().is_ok());
+
+    }
+
+    #[test]
+    fn test_fallback_panics ()
+    {
+
+        let random = Parametrizer::<f64>::new("rd(t<1)").expect("Parsing failed.");
+        let compiled = random.compile().expect("Compilation failed.");
+
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| { return compiled.evaluate(2.0); }));
+
+        match result
+        {
+
+            Ok(_) => panic!("Expected random bounds to panic."),
+            Err(e) => assert_eq!(Some(&"Minimum is not smaller than maximum when attempting to generate a random value in parametrized RandomTerm."), e.downcast_ref::<&str>())
+
+        }
+
+        assert!(compiled.evaluate(0.5) < 1.0);
+
+    }
+
+}
@@ -86,6 +86,63 @@
 //! ```
 //! use crate::parametrizer::Parametrizer;
 //!
+//! //Random distributions, whose parameters may depend on t
+//! let normal = Parametrizer::<f64>::new("normal(t, 0.5)").unwrap();
+//! let triangular = Parametrizer::<f64>::new("triangular(0, t, t/2)").unwrap();
+//! let poisson = Parametrizer::<i32>::new("poisson(4)").unwrap();
+//! let coin = Parametrizer::<f64>::new("bernoulli(0.5)").unwrap();
+//!
+//! //Weighted choices between terms, with weights and terms separated by colons
+//! let choice = Parametrizer::<f64>::new("choice(1: t, 3: 2*t, 0: 100)").unwrap();
+//!
+//! assert!(normal.evaluate(10.0).is_finite());
+//! assert!(triangular.evaluate(4.0) <= 4.0);
+//! assert!(poisson.evaluate(0) >= 0);
+//!
+//! let (flip, chosen) = (coin.evaluate(0.0), choice.evaluate(5.0));
+//!
+//! assert!(flip == 0.0 || flip == 1.0);
+//! assert!(chosen == 5.0 || chosen == 10.0);
+//! ```
+//!
+//! The other distributions are "lognormal(mean, sd)" and "exponential(rate)".
+//!
+//! ```
+//! use crate::parametrizer::Parametrizer;
+//!
+//! //The bounds of computed random values may be any terms which do not depend on t, including the
+//! //named constants pi and e. Integer types include both bounds.
+//! let angle = Parametrizer::<f64>::new("rc(-pi < pi)").unwrap();
+//! let die = Parametrizer::<i32>::new("rc(1 < 2*3)").unwrap();
+//!
+//! assert!(angle.evaluate(0.0).abs() <= std::f64::consts::PI);
+//! assert!(1 <= die.evaluate(0) && die.evaluate(0) <= 6);
+//! assert_eq!(2.0 * std::f64::consts::E, Parametrizer::new("2*e").unwrap().evaluate(0.0));
+//! ```
+//!
+//! ```
+//! use crate::parametrizer::Parametrizer;
+//!
+//! //Smooth noise of t, which is seeded when the string is parsed
+//! let noise = Parametrizer::<f64>::new("noise(t*0.5)").unwrap();
+//! let simplex = Parametrizer::<f64>::new("simplex(t)").unwrap();
+//! let value = Parametrizer::<f64>::new("valuenoise(t)").unwrap();
+//!
+//! //Fractal Brownian motion with 4 octaves, optionally followed by the lacunarity and gain
+//! let fbm = Parametrizer::<f64>::new("fbm(t, 4)").unwrap();
+//! let rough = Parametrizer::<f64>::new("fbm(t, 6, 2.5, 0.6)").unwrap();
+//!
+//! assert_eq!(noise.evaluate(1.3), noise.evaluate(1.3));
+//! assert!((noise.evaluate(1.3) - noise.evaluate(1.31)).abs() < 0.1);
+//! assert_eq!(0.0, simplex.evaluate(2.0));
+//! assert!(value.evaluate(0.5).abs() <= 1.0);
+//! assert!(fbm.evaluate(0.5).abs() <= 1.0);
+//! assert!(rough.evaluate(0.5).abs() <= 1.0);
+//! ```
+//!
+//! ```
+//! use crate::parametrizer::Parametrizer;
+//!
 //! //Piecewise functions
 //! let p1 = Parametrizer::new("p2>0|4>2|8>6").unwrap();
 //! let p2 = Parametrizer::new("p2*t>0|4>2").unwrap();
@@ -102,23 +159,123 @@
 //! assert_eq!(23, p3.evaluate(106));
 //! ```
 //!
+//! Parentheses are only removed from a term when they enclose all of it, so "(t+1)*(t+2)" is parsed
+//! as a product, and the bounds of "rd(" may contain nested random values. The times at which
+//! piecewise parts start and the lengths of loops may be written with a minus sign, as they are
+//! written by Parametrizer's Display implementation when negative.
+//!
+//! ```
+//! use crate::parametrizer::Parametrizer;
+//!
+//! let product = Parametrizer::new("(t+1)*(t+2)").unwrap();
+//! let nested = Parametrizer::<f64>::new("rd(rd(0<1)<2)").unwrap();
+//! let shifted = Parametrizer::new("p t>-5|2*t>-1").unwrap();
+//!
+//! assert_eq!(12, product.evaluate(2));
+//! assert!((0.0..2.0).contains(&nested.evaluate(0.0)));
+//! assert_eq!(-3, shifted.evaluate(-3));
+//! assert_eq!(4, shifted.evaluate(2));
+//! ```
+//!
 //! The underlying terms are public to allow for the manual composition of terms in code to avoid
 //! the string parsing overhead. See the `term` module documentation for more information. See the
 //! `Parametrizer` struct's implementation documentation to see more usage examples.
+//!
+//! Strings known at compile time can instead be parsed by the `parametrize!` macro from the
+//! companion `parametrizer_macros` crate, which expands to a native closure.
+//!
+//! # Optional Features
+//!
+//! * `rayon`: Adds `Parametrizer::par_evaluate_slice` and `Parametrizer::par_sample` for evaluating
+//!   large batches of inputs across multiple threads.
+//! * `jit`: Adds `Parametrizer::compile`, which compiles `Parametrizer<f32>` and `Parametrizer<f64>`
+//!   to native machine code using Cranelift. See the `jit` module documentation.
 
 extern crate num;
 
 use num::Num;
 use num::ToPrimitive;
 use num::FromPrimitive;
+use num::Bounded;
 use std::cmp::PartialOrd;
 use std::str::FromStr;
 use std::fmt;
+use rand::RngCore;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
 
 pub mod term;
+mod overflow;
+mod nonfinite;
+mod policy;
+mod derivative;
+mod antiderivative;
+mod dual;
+mod standard;
+mod integrate;
+mod sampler;
+mod roots;
+mod extrema;
+mod interval;
+mod domain;
+mod continuity;
+mod inverse;
+mod format;
+mod render;
+
+pub use overflow::OverflowPolicy;
+pub use nonfinite::NonFinitePolicy;
+pub use policy::Policy;
+pub use derivative::DerivativeError;
+pub use derivative::DerivativeErrorKind;
+pub use dual::Dual;
+pub use integrate::IntegrationMethod;
+pub use integrate::Integral;
+pub use roots::RootError;
+pub use extrema::Extrema;
+pub use interval::Interval;
+pub use interval::Monotonicity;
+pub use domain::Diagnostic;
+pub use domain::DiagnosticKind;
+pub use continuity::Smoothness;
+pub use continuity::Discontinuity;
+pub use inverse::Inverse;
+pub use inverse::InverseError;
+
+#[cfg(feature = "rayon")]
+mod parallel;
+
+#[cfg(feature = "jit")]
+pub mod jit;
+
+pub trait Number: Num + ToPrimitive + FromPrimitive + Bounded + PartialOrd + FromStr + Copy + Send + Sync + 'static {}
+impl<T: Num + ToPrimitive + FromPrimitive + Bounded + PartialOrd + FromStr + Copy + Send + Sync + 'static> Number for T {}
+
+///The kind of failure which caused parametrization to fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind
+{
+
+    ///The string is not a valid parametric function
+    Syntax,
+    ///The string is longer than ParseLimits::max_length
+    InputTooLong,
+    ///The string is nested deeper than ParseLimits::max_depth
+    NestingTooDeep,
+    ///The string contains more terms than ParseLimits::max_nodes
+    TooManyNodes,
+    ///The string contains more function calls than ParseLimits::max_function_calls
+    TooManyFunctionCalls,
+    ///A piecewise function has more parts than ParseLimits::max_piecewise_parts
+    TooManyPiecewiseParts,
+    ///The estimated cost of evaluating the function exceeds Policy::max_cost
+    CostExceeded,
+    ///The string uses a random value or function which is not allowed by the Policy
+    ForbiddenFeature,
+    ///A piecewise function is not as smooth as required by Policy::require_smoothness
+    Discontinuous
 
-pub trait Number: Num + ToPrimitive + FromPrimitive + PartialOrd + FromStr + Copy + Send + Sync + 'static {}
-impl<T: Num + ToPrimitive + FromPrimitive + PartialOrd + FromStr + Copy + Send + Sync + 'static> Number for T {}
+}
 
 ///An error which describes why parametrization failed. Contains the param string which failed as
 ///well as the reason for failure.
@@ -127,7 +284,72 @@ pub struct ParametrizerError
 {
 
     param: String,
-    reason: &'static str
+    reason: &'static str,
+    kind: ParseErrorKind
+
+}
+
+impl ParametrizerError
+{
+
+    ///Returns the kind of failure, e.g. to distinguish exceeded ParseLimits from invalid syntax
+    pub fn kind(&self) -> ParseErrorKind
+    {
+
+        return self.kind;
+
+    }
+
+}
+
+///Limits on the size and complexity of strings accepted by the parser, for parsing strings from
+///untrusted sources. Parsing fails with the corresponding ParseErrorKind when a limit is exceeded.
+///The default limits are unbounded, and individual limits can be set using struct update syntax.
+///
+/// # Examples
+///
+/// ```
+/// use crate::parametrizer::Parametrizer;
+/// use crate::parametrizer::ParseLimits;
+/// use crate::parametrizer::ParseErrorKind;
+///
+/// let limits = ParseLimits { max_length: 64, max_depth: 16, ..ParseLimits::default() };
+///
+/// let nested = format!("{}t{}", "(".repeat(20), ")".repeat(20));
+/// let long = "1+".repeat(40) + "t";
+///
+/// assert!(Parametrizer::<f64>::with_limits("(1+t)*t", Vec::new(), limits).is_ok());
+/// assert_eq!(ParseErrorKind::NestingTooDeep, Parametrizer::<f64>::with_limits(&nested, Vec::new(), limits).err().unwrap().kind());
+/// assert_eq!(ParseErrorKind::InputTooLong, Parametrizer::<f64>::with_limits(&long, Vec::new(), limits).err().unwrap().kind());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseLimits
+{
+
+    ///The maximum length of the string in bytes, before any formatting
+    pub max_length: usize,
+    ///The maximum depth of recursion while parsing, which increases by one for each level of
+    ///parentheses, operators, negations, functions, and random terms
+    pub max_depth: usize,
+    ///The maximum number of terms in the parsed function
+    pub max_nodes: usize,
+    ///The maximum number of function calls, counting each use of a function separately. Random
+    ///terms, distributions, choices, and noise are counted as calls as well.
+    pub max_function_calls: usize,
+    ///The maximum number of parts of a piecewise function
+    pub max_piecewise_parts: usize
+
+}
+
+impl Default for ParseLimits
+{
+
+    fn default() -> ParseLimits
+    {
+
+        return ParseLimits { max_length: usize::MAX, max_depth: usize::MAX, max_nodes: usize::MAX, max_function_calls: usize::MAX, max_piecewise_parts: usize::MAX };
+
+    }
 
 }
 
@@ -143,12 +365,156 @@ impl fmt::Display for ParametrizerError
 
 }
 
+///The reason why evaluating a parametrized function failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvalErrorKind
+{
+
+    ///The denominator of a fraction evaluated to 0
+    DivisionByZero,
+    ///The minimum of a random term was not less than its maximum, or the range between them was
+    ///not finite
+    InvalidRandomRange,
+    ///The bounds of a random term or the generated value could not be converted between the generic
+    ///type and f64
+    RandomConversion,
+    ///The input or output of a function could not be converted between the generic type and f64
+    FunctionConversion,
+    ///An integer operation overflowed under OverflowPolicy::Checked
+    Overflow,
+    ///A term produced NaN or an infinite value under NonFinitePolicy::Error
+    NonFinite,
+    ///The parameters of a random distribution were invalid for it, such as a negative standard
+    ///deviation, or the weights of a random choice were negative or summed to 0
+    InvalidDistribution
+
+}
+
+impl fmt::Display for EvalErrorKind
+{
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+
+        let reason = match self
+        {
+
+            EvalErrorKind::DivisionByZero => "Cannot divide by 0",
+            EvalErrorKind::InvalidRandomRange => "Minimum is not smaller than maximum when generating a random value",
+            EvalErrorKind::RandomConversion => "Unable to convert between generic type and f64 when generating a random value",
+            EvalErrorKind::FunctionConversion => "Unable to convert between generic type and f64 when applying a function",
+            EvalErrorKind::Overflow => "Integer overflow",
+            EvalErrorKind::NonFinite => "Produced a non-finite value",
+            EvalErrorKind::InvalidDistribution => "Invalid parameters for a random distribution or weights for a random choice"
+
+        };
+
+        return write!(f, "{}", reason);
+
+    }
+
+}
+
+///An error which describes why evaluation failed. Contains the kind of failure as well as the path
+///to the term which failed, given as the index of each subterm in the order it appears in its
+///parent's TermView, starting from the top-level term.
+///
+/// # Examples
+///
+/// ```
+/// use crate::parametrizer::Parametrizer;
+/// use crate::parametrizer::EvalErrorKind;
+///
+/// let fraction = Parametrizer::new("1+4/(t-2)").unwrap();
+///
+/// let error = fraction.try_evaluate(2).unwrap_err();
+///
+/// assert_eq!(EvalErrorKind::DivisionByZero, error.kind());
+/// assert_eq!(&[1], error.path());
+/// assert_eq!(Ok(5), fraction.try_evaluate(3));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvalError
+{
+
+    kind: EvalErrorKind,
+    path: Vec<usize>
+
+}
+
+impl EvalError
+{
+
+    ///Creates an error for a failure in the term currently being evaluated
+    pub fn new(kind: EvalErrorKind) -> EvalError
+    {
+
+        return EvalError { kind, path: Vec::new() };
+
+    }
+
+    ///Records that the failing term is the subterm at the given index of its parent. Used when
+    ///passing an error from a subterm up to the term containing it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::EvalError;
+    /// use crate::parametrizer::EvalErrorKind;
+    ///
+    /// let error = EvalError::new(EvalErrorKind::DivisionByZero).within(2).within(0);
+    ///
+    /// assert_eq!(&[0, 2], error.path());
+    /// ```
+    pub fn within(mut self, index: usize) -> EvalError
+    {
+
+        self.path.insert(0, index);
+
+        return self;
+
+    }
+
+    ///Returns the kind of failure
+    pub fn kind(&self) -> EvalErrorKind
+    {
+
+        return self.kind;
+
+    }
+
+    ///Returns the path from the top-level term to the term which failed
+    pub fn path(&self) -> &[usize]
+    {
+
+        return &self.path;
+
+    }
+
+}
+
+impl fmt::Display for EvalError
+{
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+
+        return write!(f, "Parametrizer failed to evaluate term at path: {:?}, with failure reason: {}", self.path, self.kind);
+
+    }
+
+}
+
 ///A pair containing a function on 64-bit float numbers and a shorthand associated with it.
 pub struct ParametrizerFunction
 {
 
     shorthand: String,
-    function: fn(f64) -> f64
+    function: fn(f64) -> f64,
+    derivative: Option<fn(f64) -> f64>,
+    monotonicity: Option<Monotonicity>,
+    domain: Option<Interval>,
+    latex: Option<String>
 
 }
 
@@ -173,7 +539,7 @@ impl ParametrizerFunction
         let shorthand = identifier.to_lowercase();
         let shorthand = format!("{}(", shorthand);
 
-        return ParametrizerFunction { shorthand, function };
+        return ParametrizerFunction { shorthand, function, derivative: None, monotonicity: None, domain: None, latex: None };
 
     }
 
@@ -186,6 +552,24 @@ impl ParametrizerFunction
 
     }
 
+    ///Returns the lowercase identifier of the function, i.e. the shorthand without the trailing "("
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::ParametrizerFunction;
+    ///
+    /// let pair = ParametrizerFunction::new("Log".to_string(), f64::ln);
+    ///
+    /// assert_eq!("log", pair.identifier());
+    /// ```
+    pub fn identifier(&self) -> &str
+    {
+
+        return &self.shorthand[..self.shorthand.len() - 1];
+
+    }
+
     ///Returns the stored function
     pub fn function(&self) -> fn(f64) -> f64
     {
@@ -194,16 +578,164 @@ impl ParametrizerFunction
 
     }
 
+    ///Gives the derivative of the function, which is used by Parametrizer::derivative. Functions
+    ///without a given derivative can only be differentiated if they are one of the standard
+    ///functions known to Parametrizer::derivative.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    /// use crate::parametrizer::ParametrizerFunction;
+    ///
+    /// fn cube(x: f64) -> f64
+    /// {
+    ///
+    ///     return x * x * x;
+    ///
+    /// }
+    ///
+    /// fn cube_derivative(x: f64) -> f64
+    /// {
+    ///
+    ///     return 3.0 * x * x;
+    ///
+    /// }
+    ///
+    /// let function = ParametrizerFunction::new("cube".to_string(), cube).with_derivative(cube_derivative);
+    /// let cubed = Parametrizer::new_functions("cube(2*t)", vec![function]).unwrap();
+    ///
+    /// assert_eq!(Some(24.0), cubed.derivative().ok().map(|d| { return d.evaluate(1.0); }));
+    /// ```
+    pub fn with_derivative(mut self, derivative: fn(f64) -> f64) -> ParametrizerFunction
+    {
+
+        self.derivative = Some(derivative);
+
+        return self;
+
+    }
+
+    ///Returns the derivative of the function, if one was given
+    pub fn derivative(&self) -> Option<fn(f64) -> f64>
+    {
+
+        return self.derivative;
+
+    }
+
+    ///Declares that the function never decreases or never increases, which is used by
+    ///Parametrizer::evaluate_interval to bound its output by its values at the ends of the input
+    ///interval. The output of other functions is unbounded unless they are one of the standard
+    ///functions known to Parametrizer::evaluate_interval.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    /// use crate::parametrizer::ParametrizerFunction;
+    /// use crate::parametrizer::Monotonicity;
+    ///
+    /// let cbrt = ParametrizerFunction::new("cbrt".to_string(), f64::cbrt).with_monotonicity(Monotonicity::Increasing);
+    /// let root = Parametrizer::new_functions("cbrt(t)", vec![cbrt]).unwrap();
+    ///
+    /// let interval = root.evaluate_interval(-8.0, 27.0).unwrap();
+    ///
+    /// assert!(interval.contains(-2.0) && interval.contains(3.0));
+    /// assert!(!interval.contains(-2.001) && !interval.contains(3.001));
+    /// ```
+    pub fn with_monotonicity(mut self, monotonicity: Monotonicity) -> ParametrizerFunction
+    {
+
+        self.monotonicity = Some(monotonicity);
+
+        return self;
+
+    }
+
+    ///Returns the monotonicity of the function, if it was declared
+    pub fn monotonicity(&self) -> Option<Monotonicity>
+    {
+
+        return self.monotonicity;
+
+    }
+
+    ///Declares the interval of inputs for which the function is defined, which is used by
+    ///Parametrizer::analyze to find where the function may be applied outside of it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    /// use crate::parametrizer::ParametrizerFunction;
+    /// use crate::parametrizer::Interval;
+    /// use crate::parametrizer::DiagnosticKind;
+    ///
+    /// let acos = ParametrizerFunction::new("acos".to_string(), f64::acos).with_domain(Interval::new(-1.0, 1.0));
+    /// let angle = Parametrizer::new_functions("acos(t/2)", vec![acos]).unwrap();
+    ///
+    /// assert!(angle.analyze(-2.0, 2.0).unwrap().is_empty());
+    /// assert_eq!(DiagnosticKind::Domain, angle.analyze(0.0, 3.0).unwrap()[0].kind());
+    /// ```
+    pub fn with_domain(mut self, domain: Interval) -> ParametrizerFunction
+    {
+
+        self.domain = Some(domain);
+
+        return self;
+
+    }
+
+    ///Returns the domain of the function, if it was declared
+    pub fn domain(&self) -> Option<Interval>
+    {
+
+        return self.domain;
+
+    }
+
+    ///Sets the LaTeX used for the name of the function by Parametrizer::to_latex, such as
+    ///"\operatorname{sinc}" or "\Gamma". Functions without one are written using their identifier.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    /// use crate::parametrizer::ParametrizerFunction;
+    ///
+    /// let sinc = ParametrizerFunction::new("sinc".to_string(), |x| { return if x == 0.0 { 1.0 } else { x.sin() / x }; });
+    /// let function = Parametrizer::<f64>::new_functions("sinc(t+1)", vec![sinc.with_latex("\\operatorname{sinc}".to_string())]).unwrap();
+    ///
+    /// assert_eq!("\\operatorname{sinc}\\left(t + 1\\right)", function.to_latex());
+    /// ```
+    pub fn with_latex(mut self, latex: String) -> ParametrizerFunction
+    {
+
+        self.latex = Some(latex);
+
+        return self;
+
+    }
+
+    ///Returns the LaTeX used for the name of the function, if it was set
+    pub fn latex(&self) -> Option<&str>
+    {
+
+        return self.latex.as_deref();
+
+    }
+
 }
 
-///Main struct for parametrizing strings. Contains a pointer to the top-level term, which will
-///contain pointers to lower leves for recursive evaluations
+///Main struct for parametrizing strings. Contains an arena holding every term of the parsed
+///function, which are evaluated recursively starting from the top-level term
 pub struct Parametrizer<T: Number>
 {
 
-    //The top-level term for the parametrized function. Must be placed on the heap as the
-    //recursion could be of theoretically unbounded depth
-    term: Box<dyn term::Term<T> + Send + Sync>
+    //All of the terms for the parametrized function, stored contiguously and referring to their
+    //children by index
+    term: term::arenaterm::ArenaTerm<T>
 
 }
 
@@ -336,7 +868,7 @@ impl<T: Number> Parametrizer<T>
     pub fn new_functions(param: &str, functions: Vec<ParametrizerFunction>) -> Result<Parametrizer<T>, ParametrizerError>
     {
 
-        let term = term::create_parametrization::<T>(param, &functions[..])?;
+        let term = term::create_arena::<T>(param, &functions[..], &Policy::default(), None)?;
 
         return Ok(Parametrizer::<T> { term });
 
@@ -369,18 +901,887 @@ impl<T: Number> Parametrizer<T>
     pub fn quick_new(param: &str, functions: Vec<ParametrizerFunction>) -> Result<Parametrizer<T>, ParametrizerError>
     {
 
-        let term = term::quick_parametrization::<T>(param, &functions[..])?;
+        let term = term::quick_arena::<T>(param, &functions[..], &Policy::default(), None)?;
 
         return Ok(Parametrizer::<T> { term });
 
     }
 
-    ///Used to compute the parametric function at a specific point. As the parsing is done once at
-    ///creation time, the only overhead is due to pointers and recursion.
-    pub fn evaluate(&self, t: T) -> T
+    ///Constructor for strings from untrusted sources, which formats and parses the string like
+    ///Parametrizer::new_functions but fails if the string exceeds any of the given limits. As with
+    ///Parametrizer::new_functions, sine and cosine must be included in the list of functions to be
+    ///supported.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    /// use crate::parametrizer::ParametrizerFunction;
+    /// use crate::parametrizer::ParseLimits;
+    /// use crate::parametrizer::ParseErrorKind;
+    ///
+    /// let limits = ParseLimits { max_nodes: 8, max_function_calls: 2, max_piecewise_parts: 2, ..ParseLimits::default() };
+    /// let functions = || { return vec![ ParametrizerFunction::new("sin".to_string(), f64::sin) ]; };
+    ///
+    /// let allowed = Parametrizer::with_limits("sin(sin(t))+1", functions(), limits).unwrap();
+    ///
+    /// assert_eq!(1.0_f64.sin().sin() + 1.0, allowed.evaluate(1.0));
+    ///
+    /// let calls = Parametrizer::<f64>::with_limits("sin(t)+sin(t)+sin(t)", functions(), limits);
+    /// let nodes = Parametrizer::<f64>::with_limits("1+t+t*t+t*t*t", functions(), limits);
+    /// let parts = Parametrizer::<f64>::with_limits("p1>0|2>1|3>2", functions(), limits);
+    ///
+    /// assert_eq!(ParseErrorKind::TooManyFunctionCalls, calls.err().unwrap().kind());
+    /// assert_eq!(ParseErrorKind::TooManyNodes, nodes.err().unwrap().kind());
+    /// assert_eq!(ParseErrorKind::TooManyPiecewiseParts, parts.err().unwrap().kind());
+    /// ```
+    pub fn with_limits(param: &str, functions: Vec<ParametrizerFunction>, limits: ParseLimits) -> Result<Parametrizer<T>, ParametrizerError>
+    {
+
+        let term = term::create_arena::<T>(param, &functions[..], &Policy { limits, ..Policy::default() }, None)?;
+
+        return Ok(Parametrizer::<T> { term });
+
+    }
+
+    ///Constructor for strings in sandboxed contexts, which formats and parses the string like
+    ///Parametrizer::with_limits but also fails if the string uses features forbidden by the policy,
+    ///or if its estimated cost exceeds the policy's maximum cost.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    /// use crate::parametrizer::Policy;
+    /// use crate::parametrizer::ParseErrorKind;
+    ///
+    /// let policy = Policy { max_cost: 20, ..Policy::default() };
+    ///
+    /// let cheap = Parametrizer::<f64>::with_policy("3*t*t+2*t+1", Vec::new(), &policy).unwrap();
+    /// let expensive = Parametrizer::<f64>::with_policy("t*t*t*t*t*t*t*t*t*t*t*t*t*t*t*t*t*t*t*t", Vec::new(), &policy);
+    ///
+    /// assert_eq!(9, cheap.cost());
+    /// assert_eq!(ParseErrorKind::CostExceeded, expensive.err().unwrap().kind());
+    /// ```
+    pub fn with_policy(param: &str, functions: Vec<ParametrizerFunction>, policy: &Policy) -> Result<Parametrizer<T>, ParametrizerError>
+    {
+
+        let term = term::create_arena::<T>(param, &functions[..], policy, None)?;
+
+        return Ok(Parametrizer::<T> { term });
+
+    }
+
+    ///Constructor for reproducible random values, which formats and parses the string like
+    ///Parametrizer::new_functions but draws computed ("rc(") random values and noise seeds at parse
+    ///time, and dynamic random values at each evaluation, from a ChaCha generator seeded with the
+    ///given seed. Parametrizers built from the same string and seed produce the same values when
+    ///evaluated in the same order.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    ///
+    /// let first = Parametrizer::<f64>::with_seed("rc(0<10)+rd(0<t)", Vec::new(), 42).unwrap();
+    /// let second = Parametrizer::<f64>::with_seed("rc(0<10)+rd(0<t)", Vec::new(), 42).unwrap();
+    ///
+    /// for t in 1..10
+    /// {
+    ///
+    ///     assert_eq!(first.evaluate(t as f64), second.evaluate(t as f64));
+    ///
+    /// }
+    /// ```
+    pub fn with_seed(param: &str, functions: Vec<ParametrizerFunction>, seed: u64) -> Result<Parametrizer<T>, ParametrizerError>
+    {
+
+        return Parametrizer::with_rng(param, functions, ChaCha8Rng::seed_from_u64(seed));
+
+    }
+
+    ///Constructor like Parametrizer::with_seed which uses the given random number generator for
+    ///both computed and dynamic random values, e.g. a SeedableRng of the caller's choice. The
+    ///generator is shared by all evaluations, so when evaluating from several threads at once the
+    ///values drawn by each depend on the order in which they reach it. The parallel evaluations of
+    ///the rayon feature instead give each input its own stream drawn from the generator.
+    ///
+    /// # Examples
+    /// ```
+    /// use rand::SeedableRng;
+    /// use rand_chacha::ChaCha20Rng;
+    /// use crate::parametrizer::Parametrizer;
+    ///
+    /// let first = Parametrizer::<f64>::with_rng("rd(t<2*t)", Vec::new(), ChaCha20Rng::seed_from_u64(3)).unwrap();
+    /// let second = Parametrizer::<f64>::with_rng("rd(t<2*t)", Vec::new(), ChaCha20Rng::seed_from_u64(3)).unwrap();
+    ///
+    /// let values: Vec<f64> = (1..5).map(|t| { return first.evaluate(t as f64); }).collect();
+    /// let replayed: Vec<f64> = (1..5).map(|t| { return second.evaluate(t as f64); }).collect();
+    ///
+    /// assert_eq!(values, replayed);
+    /// ```
+    pub fn with_rng<R: RngCore + Send + 'static>(param: &str, functions: Vec<ParametrizerFunction>, rng: R) -> Result<Parametrizer<T>, ParametrizerError>
+    {
+
+        let term = term::create_arena::<T>(param, &functions[..], &Policy::default(), Some(Box::new(rng)))?;
+
+        return Ok(Parametrizer::<T> { term });
+
+    }
+
+    ///Constructor which wraps an already composed term, such as a user-defined implementation of
+    ///the Term trait, so that it can be used anywhere a Parametrizer is expected. The term is kept
+    ///as a single opaque node, so it is evaluated through its own Term implementation.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    /// use crate::parametrizer::term::Term;
+    /// use crate::parametrizer::term::scalarterm::ScalarTerm;
+    /// use crate::parametrizer::term::variableterm::VariableTerm;
+    ///
+    /// struct Square;
+    ///
+    /// impl Term<i32> for Square
+    /// {
+    ///
+    ///     fn evaluate(&self, t: i32) -> i32
+    ///     {
+    ///
+    ///         return t * t;
+    ///
+    ///     }
+    ///
+    /// }
+    ///
+    /// let composed = Parametrizer::from_term(Box::new(ScalarTerm::new(Box::new(VariableTerm::new()), 3)));
+    /// let custom = Parametrizer::from_term(Box::new(Square));
+    ///
+    /// assert_eq!(12, composed.evaluate(4));
+    /// assert_eq!(16, custom.evaluate(4));
+    /// ```
+    pub fn from_term(term: Box<dyn term::Term<T> + Send + Sync>) -> Parametrizer<T>
+    {
+
+        return Parametrizer::<T> { term: term::arenaterm::ArenaTerm::from(term) };
+
+    }
+
+    ///Used to compute the parametric function at a specific point. As the parsing is done once at
+    ///creation time, the only overhead is due to recursion through the arena.
+    pub fn evaluate(&self, t: T) -> T
+    {
+
+        return term::Term::evaluate(&self.term, t);
+
+    }
+
+    ///Computes the parametric function at a specific point like Parametrizer::evaluate, but returns
+    ///an error describing the failing term instead of panicking, e.g. on a division by zero or an
+    ///invalid random range. User-defined terms which do not implement Term::try_evaluate may still
+    ///panic.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    /// use crate::parametrizer::EvalErrorKind;
+    ///
+    /// let random = Parametrizer::new("rd(t<4)").unwrap();
+    /// let piecewise = Parametrizer::new("p1>0|sin(1/t)>2").unwrap();
+    ///
+    /// assert!(random.try_evaluate(2.0).is_ok());
+    /// assert_eq!(EvalErrorKind::InvalidRandomRange, random.try_evaluate(5.0).unwrap_err().kind());
+    /// assert_eq!(Ok(0.5_f64.sin()), piecewise.try_evaluate(2.0));
+    /// assert!(piecewise.try_evaluate(0.0).is_ok());
+    /// ```
+    ///
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    /// use crate::parametrizer::EvalErrorKind;
+    ///
+    /// let piecewise = Parametrizer::new("p1>0|t*(3/(t-2))>1").unwrap();
+    ///
+    /// let error = piecewise.try_evaluate(2).unwrap_err();
+    ///
+    /// assert_eq!(EvalErrorKind::DivisionByZero, error.kind());
+    /// assert_eq!(&[1, 1], error.path());
+    /// ```
+    pub fn try_evaluate(&self, t: T) -> Result<T, EvalError>
+    {
+
+        return term::Term::try_evaluate(&self.term, t);
+
+    }
+
+    ///Estimates the cost of evaluating the function once, as limited by Policy::max_cost. Every
+    ///term costs 1 plus the cost of its subterms, except that function calls and random values cost
+    ///10 plus the cost of their subterms, and noise costs 10 per octave plus the cost of its
+    ///subterm. Piecewise functions cost 1 plus the number of parts plus the cost of their most
+    ///expensive part, as only one part is evaluated per call.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    ///
+    /// assert_eq!(1, Parametrizer::<f64>::new("t").unwrap().cost());
+    /// assert_eq!(5, Parametrizer::<f64>::new("2*t+1").unwrap().cost());
+    /// assert_eq!(13, Parametrizer::<f64>::new("sin(t/2)").unwrap().cost());
+    /// assert_eq!(9, Parametrizer::<f64>::new("p1>0|2*t+1>1|t>2").unwrap().cost());
+    /// ```
+    pub fn cost(&self) -> u64
+    {
+
+        return policy::estimate_cost(self.term.root());
+
+    }
+
+    ///Differentiates the function symbolically, returning a new Parametrizer computing its derivative
+    ///with respect to t, which keeps this one's overflow and non-finite policies. Sums, products,
+    ///fractions, and scaled terms follow the usual rules, and each part of a piecewise function is
+    ///differentiated separately, ignoring any jumps between parts. Functions are differentiated by
+    ///the chain rule, using the derivative given by ParametrizerFunction::with_derivative, or else
+    ///the known derivative for functions named "sin", "cos", "tan", "exp", "ln", and "sqrt", which
+    ///are assumed to be the standard functions. Fails for random values, noise, user-defined terms,
+    ///and other functions.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    ///
+    /// let position = Parametrizer::new("3*t*t - 2*t + 1").unwrap();
+    /// let velocity = position.derivative().unwrap();
+    /// let acceleration = velocity.derivative().unwrap();
+    ///
+    /// assert_eq!(10, velocity.evaluate(2));
+    /// assert_eq!(6, acceleration.evaluate(2));
+    /// assert_eq!(0, acceleration.derivative().unwrap().evaluate(2));
+    ///
+    /// let wave = Parametrizer::new("sin(t*t)/t").unwrap().derivative().unwrap();
+    ///
+    /// assert!((wave.evaluate(2.0) - (2.0 * 4.0_f64.cos() - 4.0_f64.sin() / 4.0)).abs() < 1e-12);
+    ///
+    /// let piecewise = Parametrizer::new("p t*t>0|5*t>2").unwrap().derivative().unwrap();
+    ///
+    /// assert_eq!(2.0, piecewise.evaluate(1.0));
+    /// assert_eq!(5.0, piecewise.evaluate(3.0));
+    /// ```
+    ///
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    /// use crate::parametrizer::DerivativeErrorKind;
+    ///
+    /// let random = Parametrizer::<f64>::new("t + t*rd(0<1)").unwrap();
+    ///
+    /// let error = random.derivative().err().unwrap();
+    ///
+    /// assert_eq!(DerivativeErrorKind::Random, error.kind());
+    /// assert_eq!(&[1, 1], error.path());
+    /// ```
+    pub fn derivative(&self) -> Result<Parametrizer<T>, DerivativeError>
+    {
+
+        let term = derivative::differentiate(&self.term)?;
+
+        return Ok(Parametrizer::<T> { term });
+
+    }
+
+    ///Integrates the function symbolically, returning a new Parametrizer computing an
+    ///antiderivative which keeps this one's overflow and non-finite policies. Supports polynomials,
+    ///sums, products and fractions where only one factor or the numerator depends on t, and the
+    ///functions named "sin", "cos", and "exp" of linear functions of t, which are assumed to be the
+    ///standard functions. No constant of integration is added, so polynomial terms are 0 at t = 0,
+    ///while sin(a*t + b), cos(a*t + b), and exp(a*t + b) become -cos(a*t + b)/a, sin(a*t + b)/a,
+    ///and exp(a*t + b)/a, which need not be. The parts of piecewise functions are integrated
+    ///separately and offset so that each part starts where the previous one ended, making the
+    ///antiderivative continuous. Fails with DerivativeErrorKind::Unsupported for anything else,
+    ///including looping piecewise functions. For integer types, the polynomial terms are divided by
+    ///a common denominator, which truncates the result.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    ///
+    /// let speed = Parametrizer::new("3*t*t + 2*t + 1").unwrap();
+    /// let distance = speed.antiderivative().unwrap();
+    ///
+    /// assert_eq!(14.0, distance.evaluate(2.0));
+    /// assert_eq!(speed.evaluate(1.5), distance.derivative().unwrap().evaluate(1.5));
+    ///
+    /// let wave = Parametrizer::new("cos(2*t + 1)").unwrap().antiderivative().unwrap();
+    ///
+    /// assert!((wave.evaluate(0.5) - 2.0_f64.sin() / 2.0).abs() < 1e-12);
+    /// //-cos(t) is -1 at 0
+    /// assert_eq!(-1.0, Parametrizer::new("sin(t)").unwrap().antiderivative().unwrap().evaluate(0.0));
+    ///
+    /// //The second part starts from 1, where the first ended, and the third from 3
+    /// let ramp = Parametrizer::new("p 1>0|2>1|t>2").unwrap().antiderivative().unwrap();
+    ///
+    /// assert_eq!(0.5, ramp.evaluate(0.5));
+    /// assert_eq!(2.0, ramp.evaluate(1.5));
+    /// assert_eq!(3.0 + (9.0 - 4.0) / 2.0, ramp.evaluate(3.0));
+    /// ```
+    ///
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    /// use crate::parametrizer::DerivativeErrorKind;
+    ///
+    /// let product = Parametrizer::<f64>::new("5 + t*sin(t)").unwrap();
+    /// let error = product.antiderivative().err().unwrap();
+    ///
+    /// assert_eq!(DerivativeErrorKind::Unsupported, error.kind());
+    /// assert_eq!(&[1], error.path());
+    ///
+    /// assert_eq!(9, Parametrizer::<i32>::new("t*t").unwrap().antiderivative().unwrap().evaluate(3));
+    /// ```
+    pub fn antiderivative(&self) -> Result<Parametrizer<T>, DerivativeError>
+    {
+
+        let term = antiderivative::antiderivative(&self.term)?;
+
+        return Ok(Parametrizer::<T> { term });
+
+    }
+
+    ///Computes the function and its derivative with respect to t at a specific point, in a single
+    ///pass through the arena using dual numbers. Unlike Parametrizer::derivative, this never fails:
+    ///the derivatives of functions are found as for Parametrizer::derivative where known, and
+    ///otherwise approximated by a central difference, as are the derivatives of noise and
+    ///user-defined terms. Random values are treated as constants once drawn, and the derivative of a
+    ///piecewise function is that of the part which applies at t.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    ///
+    /// let position = Parametrizer::new("3*t*t - 2*t + 1").unwrap();
+    ///
+    /// assert_eq!((9, 10), position.evaluate_with_derivative(2));
+    ///
+    /// let wave = Parametrizer::new("sin(t*t)/t").unwrap();
+    /// let (value, slope) = wave.evaluate_with_derivative(2.0_f64);
+    ///
+    /// assert_eq!(wave.evaluate(2.0), value);
+    /// assert!((slope - wave.derivative().unwrap().evaluate(2.0)).abs() < 1e-12);
+    /// ```
+    ///
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    /// use crate::parametrizer::ParametrizerFunction;
+    ///
+    /// //Without a derivative, the derivative of cbrt is approximated
+    /// let functions = vec![ParametrizerFunction::new("cbrt".to_string(), f64::cbrt)];
+    /// let root = Parametrizer::new_functions("cbrt(t*t)", functions).unwrap();
+    ///
+    /// assert!(root.derivative().is_err());
+    ///
+    /// let (value, slope) = root.evaluate_with_derivative(8.0_f64);
+    ///
+    /// assert_eq!(4.0, value);
+    /// assert!((slope - 1.0 / 3.0).abs() < 1e-8);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics wherever Parametrizer::evaluate would
+    pub fn evaluate_with_derivative(&self, t: T) -> (T, T)
+    {
+
+        let computed = self.term.evaluate_dual(Dual::variable(t));
+
+        return (computed.value(), computed.derivative());
+
+    }
+
+    ///Numerically integrates the function from a to b, returning the estimated value and its
+    ///estimated error. The interval is split at the breakpoints of piecewise terms, including every
+    ///loop of looping ones, and each segment is integrated separately so that jumps between parts
+    ///do not reduce the accuracy. For integer types, whose functions only change at integers as t is
+    ///truncated, the interval is also split at every integer. Returns an error if the function fails
+    ///to evaluate anywhere it is sampled.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    /// use crate::parametrizer::IntegrationMethod;
+    ///
+    /// let speed = Parametrizer::new("3*t*t + 1").unwrap();
+    ///
+    /// let distance = speed.integrate(0.0, 2.0, IntegrationMethod::default()).unwrap();
+    ///
+    /// assert!((distance.value - 10.0).abs() < 1e-10);
+    /// assert!(distance.error < 1e-10);
+    ///
+    /// let gauss = speed.integrate(2.0, 0.0, IntegrationMethod::GaussKronrod { tolerance: 1e-12, max_depth: 10 }).unwrap();
+    /// let trapezoid = speed.integrate(0.0, 2.0, IntegrationMethod::Trapezoid { steps: 100 }).unwrap();
+    ///
+    /// assert!((gauss.value + 10.0).abs() < 1e-12);
+    /// assert!((trapezoid.value - 10.0).abs() < 1e-3);
+    /// assert!((trapezoid.value - 10.0).abs() <= 2.0 * trapezoid.error);
+    /// ```
+    ///
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    /// use crate::parametrizer::IntegrationMethod;
+    ///
+    /// //The envelope jumps from 1 to 4 at t = 0.3, which is integrated exactly
+    /// let envelope = Parametrizer::new("p 1>0|4>0.3|2-t>1").unwrap();
+    /// let area = envelope.integrate(0.0, 1.5, IntegrationMethod::Trapezoid { steps: 3 }).unwrap();
+    ///
+    /// assert!((area.value - (0.3 + 4.0 * 0.7 + 0.375)).abs() < 1e-9);
+    ///
+    /// let steps = Parametrizer::<i32>::new("t*t").unwrap();
+    ///
+    /// assert_eq!(5.0, steps.integrate(0, 3, IntegrationMethod::default()).unwrap().value);
+    /// ```
+    pub fn integrate(&self, a: T, b: T, method: IntegrationMethod) -> Result<Integral, EvalError>
+    {
+
+        return integrate::integrate(&self.term, a, b, method);
+
+    }
+
+    ///Finds a value of t within the bracket at which the function equals the target, e.g. the time
+    ///at which a curve reaches a value. The bracket is split at the breakpoints of piecewise terms,
+    ///and the first segment over which the function crosses the target is searched, so the earliest
+    ///crossing is found when there is only one per segment. Uses Newton's method safeguarded by
+    ///bisection when the function has a symbolic derivative (see Parametrizer::derivative), and
+    ///Brent's method otherwise. Jumps past the target, at breakpoints or poles, are not roots. For
+    ///integer types, only integers at which the function is exactly the target are roots, which are
+    ///found by bisection over the integers.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    /// use crate::parametrizer::RootError;
+    ///
+    /// let growth = Parametrizer::<f64>::new("t*t*t + t").unwrap();
+    ///
+    /// let time = growth.find_root(100.0, (0.0, 10.0)).unwrap();
+    ///
+    /// assert!((growth.evaluate(time) - 100.0).abs() < 1e-9);
+    /// assert_eq!(Err(RootError::NoRoot), growth.find_root(100.0, (0.0, 4.0)));
+    ///
+    /// //The jump from 1 to 3 passes 2 without reaching it, but the last part reaches it at 4
+    /// let steps = Parametrizer::new("p 1>0|3>1|7-t>3").unwrap();
+    ///
+    /// assert_eq!(Ok(5.0), steps.find_root(2.0, (0.0, 6.0)));
+    ///
+    /// let integer = Parametrizer::new("2*t + 1").unwrap();
+    ///
+    /// assert_eq!(Ok(7), integer.find_root(15, (0, 100)));
+    /// assert_eq!(Err(RootError::NoRoot), integer.find_root(16, (0, 100)));
+    /// ```
+    pub fn find_root(&self, target: T, bracket: (T, T)) -> Result<T, RootError>
+    {
+
+        let derivative = self.root_derivative();
+
+        return roots::find_root(&self.term, derivative.as_ref(), target, bracket);
+
+    }
+
+    ///Finds the values of t within the range at which the function is 0, in increasing order. The
+    ///range is sampled at the given number of equal steps, as well as at the breakpoints of
+    ///piecewise terms, and a root is searched for wherever the function changes sign between
+    ///samples, as by Parametrizer::find_root. Roots which are closer together than the sample
+    ///spacing without a change of sign between them, such as double roots, may be missed unless
+    ///they are sampled exactly. Integer types are sampled at integers.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    ///
+    /// let cubic = Parametrizer::<f64>::new("t*t*t - 2*t*t - 5*t + 6").unwrap();
+    ///
+    /// let roots = cubic.find_all_roots((-5.0, 5.0), 100).unwrap();
+    ///
+    /// assert_eq!(3, roots.len());
+    /// assert!((roots[0] + 2.0).abs() < 1e-12 && (roots[1] - 1.0).abs() < 1e-12 && (roots[2] - 3.0).abs() < 1e-12);
+    ///
+    /// let integer = Parametrizer::new("t*t - 9").unwrap();
+    ///
+    /// assert_eq!(Ok(vec![-3, 3]), integer.find_all_roots((-10, 10), 4));
+    /// ```
+    pub fn find_all_roots(&self, range: (T, T), resolution: usize) -> Result<Vec<T>, EvalError>
+    {
+
+        let derivative = self.root_derivative();
+
+        return roots::find_all_roots(&self.term, derivative.as_ref(), range, resolution);
+
+    }
+
+    ///Finds the global minimum and maximum of the function between a and b, and where they occur.
+    ///Each segment between the breakpoints of piecewise terms is sampled at 256 points including its
+    ///ends, and every sampled local extremum is refined by golden section search. When the function
+    ///has a symbolic derivative (see Parametrizer::derivative), the critical points at which it is 0
+    ///are also checked. Values approached but not reached at a breakpoint, i.e. the end of a part,
+    ///are not included. Integer types are sampled at integers, so the result is exact when there
+    ///are at most 256 integers between each pair of breakpoints. Returns an error if the function
+    ///fails to evaluate at a sample, or is NaN at every sample.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    ///
+    /// let cubic = Parametrizer::<f64>::new("t*t*t - 3*t").unwrap();
+    /// let extrema = cubic.extrema(-1.5, 3.0).unwrap();
+    ///
+    /// assert!((extrema.min + 2.0).abs() < 1e-12 && (extrema.min_at - 1.0).abs() < 1e-6);
+    /// assert_eq!((18.0, 3.0), (extrema.max, extrema.max_at));
+    ///
+    /// //The peak of the first part at 1 and the start of the second part are both checked
+    /// let envelope = Parametrizer::<f64>::new("p 2*t - t*t>0|5-t>1.5").unwrap();
+    /// let extrema = envelope.extrema(0.0, 4.0).unwrap();
+    ///
+    /// assert_eq!((0.0, 0.0), (extrema.min, extrema.min_at));
+    /// assert_eq!((3.5, 1.5), (extrema.max, extrema.max_at));
+    ///
+    /// let integer = Parametrizer::<i32>::new("t*t - 10*t").unwrap().extrema(0, 20).unwrap();
+    ///
+    /// assert_eq!((-25, 5, 200, 20), (integer.min, integer.min_at, integer.max, integer.max_at));
+    /// ```
+    pub fn extrema(&self, a: T, b: T) -> Result<Extrema<T>, EvalError>
+    {
+
+        let derivative = self.root_derivative();
+
+        return extrema::extrema(&self.term, derivative.as_ref(), a, b);
+
+    }
+
+    ///Creates the inverse of the function over the domain, which finds the value of t at which the
+    ///function equals a given value, e.g. to resume an easing curve at its current value. The
+    ///function must be strictly increasing or strictly decreasing over the domain, though it may
+    ///jump in the same direction at the breakpoints of piecewise terms. This is checked by sampling
+    ///each segment between breakpoints at 256 points, and, when the function has a symbolic
+    ///derivative (see Parametrizer::derivative), by checking with interval arithmetic (see
+    ///Parametrizer::evaluate_interval) that the derivative does not change sign between the
+    ///samples. Returns InverseError::NotMonotonic if either check fails, and an error if the
+    ///function fails to evaluate at a sample.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    /// use crate::parametrizer::InverseError;
+    ///
+    /// let ease = Parametrizer::<f64>::new("1 - (1 - t)*(1 - t)").unwrap();
+    /// let inverse = ease.inverse((0.0, 1.0)).unwrap();
+    ///
+    /// assert!(inverse.increasing());
+    /// assert!((inverse.evaluate(0.75).unwrap() - 0.5).abs() < 1e-12);
+    ///
+    /// //The curve turns back after 1
+    /// assert_eq!(Some(InverseError::NotMonotonic), ease.inverse((0.0, 2.0)).err());
+    /// ```
+    pub fn inverse(&self, domain: (T, T)) -> Result<Inverse<'_, T>, InverseError>
+    {
+
+        return inverse::inverse(&self.term, self.root_derivative(), domain.0, domain.1);
+
+    }
+
+    //The derivative used by Newton's method and to find critical points, which is only used for
+    //types which do not truncate
+    fn root_derivative(&self) -> Option<term::arenaterm::ArenaTerm<T>>
+    {
+
+        if overflow::is_integer::<T>()
+        {
+
+            return None;
+
+        }
+
+        return derivative::differentiate(&self.term).ok();
+
+    }
+
+    ///Finds an interval which is guaranteed to contain every value of the function for t between a
+    ///and b, unlike sampling, which can miss narrow spikes. Each term maps the interval of its
+    ///inputs to an interval of its outputs, rounding outwards so that the bounds hold despite
+    ///rounding errors:
+    ///- random terms are bounded by their minimum and maximum, or by the support of their
+    ///  distribution, and noise is bounded by -1 and 1
+    ///- piecewise terms combine the parts which apply somewhere in the interval
+    ///- functions are bounded if they are declared monotonic with
+    ///  ParametrizerFunction::with_monotonicity, or are one of the standard functions sin, cos, tan,
+    ///  exp, ln, or sqrt, and are otherwise unbounded, as are user-defined terms
+    ///
+    ///The interval may be wider than the actual range of values, particularly when t appears several
+    ///times. Values which are NaN are not included, and integer values which overflow are only
+    ///included under OverflowPolicy::Wrapping and OverflowPolicy::Saturating. Returns an error
+    ///with the path to the first fraction whose denominator may be 0, or piecewise term whose loop
+    ///value may be 0.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    /// use crate::parametrizer::EvalErrorKind;
+    ///
+    /// let spike = Parametrizer::<f64>::new("1/(t*t + 0.0001)").unwrap();
+    /// let interval = spike.evaluate_interval(-1.0, 1.0);
+    ///
+    /// //The denominator is bounded below by 0.0001, but t*t is computed without knowing t is
+    /// //the same in both factors, so it may be as small as -1
+    /// assert_eq!(EvalErrorKind::DivisionByZero, interval.unwrap_err().kind());
+    ///
+    /// let interval = spike.evaluate_interval(0.5, 1.0).unwrap();
+    ///
+    /// assert!(interval.contains(1.0 / 0.2501) && interval.contains(1.0 / 1.0001));
+    ///
+    /// let random = Parametrizer::<i32>::new("p rd(t<2*t)>0|t-10>5").unwrap();
+    /// let interval = random.evaluate_interval(2, 6).unwrap();
+    ///
+    /// assert_eq!((-5.0, 10.0), (interval.min, interval.max));
+    /// ```
+    pub fn evaluate_interval(&self, a: T, b: T) -> Result<Interval, EvalError>
+    {
+
+        return interval::enclose(&self.term, a, b);
+
+    }
+
+    ///Finds the terms which may fail for t between a and b, without evaluating the function. Reports
+    ///every fraction whose denominator may be 0, random term whose minimum may not be less than its
+    ///maximum, and function which may be applied outside of its domain, as declared with
+    ///ParametrizerFunction::with_domain or known for the standard functions ln and sqrt. Each
+    ///diagnostic gives the path to the term, in the same way as EvalError.
+    ///
+    ///Terms are checked with interval arithmetic like Parametrizer::evaluate_interval, so a term
+    ///which is reported may never actually fail, but every term which can fail is reported.
+    ///Possible failures are checked again on smaller parts of the range before being reported,
+    ///which avoids most false reports. Returns an error if a or b cannot be converted to f64.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    /// use crate::parametrizer::DiagnosticKind;
+    ///
+    /// let function = Parametrizer::<f64>::new("rd(t<2*t) + 1/(t*t - 2*t + 1.5)").unwrap();
+    ///
+    /// let diagnostics = function.analyze(-1.0, 4.0).unwrap();
+    ///
+    /// //The denominator is at least 0.5, but the random term's bounds cross at 0
+    /// assert_eq!(1, diagnostics.len());
+    /// assert_eq!(DiagnosticKind::RandomRange, diagnostics[0].kind());
+    /// assert_eq!(&[0], diagnostics[0].path());
+    ///
+    /// assert!(function.analyze(1.0, 4.0).unwrap().is_empty());
+    /// ```
+    pub fn analyze(&self, a: T, b: T) -> Result<Vec<Diagnostic>, EvalError>
+    {
+
+        return interval::diagnose(&self.term, a, b);
+
+    }
+
+    ///Checks every piecewise term for breakpoints where the function is not smooth, by comparing
+    ///the parts on either side of each breakpoint, and of the seam where a looping term returns to
+    ///0. Reports a C0 discontinuity where the values jump, and a C1 discontinuity where the values
+    ///are continuous but the slopes jump. Values and slopes are continuous if they differ by at most
+    ///tolerance times the larger of 1 and their size. Slopes are approximated by central differences
+    ///with a relative tolerance of at least 1e-6.
+    ///
+    ///Parts containing random terms are skipped, as are slopes which cannot be approximated.
+    ///Returns an error if a part fails to evaluate at a breakpoint.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    /// use crate::parametrizer::Smoothness;
+    ///
+    /// let ramp = Parametrizer::<f64>::new("p 0>0|t - 1>1|t>3").unwrap();
+    /// let discontinuities = ramp.discontinuities(1e-9).unwrap();
+    ///
+    /// //A kink at 1, then a jump from 2 to 3 at 3
+    /// assert_eq!(2, discontinuities.len());
+    /// assert_eq!((Smoothness::C1, 1.0), (discontinuities[0].kind, discontinuities[0].at));
+    /// assert_eq!((Smoothness::C0, 3.0, 2.0, 3.0), (discontinuities[1].kind, discontinuities[1].at, discontinuities[1].left, discontinuities[1].right));
+    /// ```
+    pub fn discontinuities(&self, tolerance: f64) -> Result<Vec<Discontinuity>, EvalError>
+    {
+
+        return continuity::discontinuities(self.term.root(), tolerance);
+
+    }
+
+    ///Writes the function as LaTeX for use in math mode, e.g. for documentation or a UI. Fractions
+    ///are written with \frac, standard functions such as sin and ln with their commands, random
+    ///terms as \mathrm{rand}, and piecewise terms with the cases environment. Parts of looping
+    ///piecewise terms are written in terms of \tau, i.e. t modulo the loop value. Functions passed
+    ///to Parametrizer::new_functions are written with the name given to
+    ///ParametrizerFunction::with_latex, or as an operator named after their identifier.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    ///
+    /// let polynomial = Parametrizer::<f64>::new("(3*t*t - 1)/2 + rd(0<t)").unwrap();
+    ///
+    /// assert_eq!("\\frac{3t \\cdot t - 1}{2} + \\mathrm{rand}\\left(0, t\\right)", polynomial.to_latex());
+    ///
+    /// let steps = Parametrizer::<i32>::new("p 0>0|-t>2").unwrap();
+    ///
+    /// assert_eq!("\\begin{cases} 0 & t < 2 \\\\ -t & t \\ge 2 \\end{cases}", steps.to_latex());
+    /// ```
+    pub fn to_latex(&self) -> String
+    {
+
+        return render::latex(&self.term);
+
+    }
+
+    ///Writes the function as a presentation MathML math element, which browsers can display
+    ///directly. Terms are written as described for Parametrizer::to_latex, except that functions
+    ///passed to Parametrizer::new_functions are always named after their identifier.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    ///
+    /// let line = Parametrizer::<i32>::new("2*t + 1").unwrap();
+    ///
+    /// assert_eq!("<math xmlns=\"http://www.w3.org/1998/Math/MathML\"><mn>2</mn><mo>&#x2062;</mo><mi>t</mi><mo>+</mo><mn>1</mn></math>", line.to_mathml());
+    /// ```
+    pub fn to_mathml(&self) -> String
+    {
+
+        return render::mathml(&self.term);
+
+    }
+
+    ///Sets how integer overflow is handled by addition, multiplication, division, and negation
+    ///throughout the parsed function. Overflow is checked by default, i.e. Parametrizer::evaluate
+    ///panics and Parametrizer::try_evaluate returns an error. Has no effect on floating point types,
+    ///or on terms passed to Parametrizer::from_term, which handle overflow themselves. Note that
+    ///negation is rejected when parsing for unsigned types.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    /// use crate::parametrizer::OverflowPolicy;
+    /// use crate::parametrizer::EvalErrorKind;
+    ///
+    /// let mut square = Parametrizer::<i32>::new("t*t+1").unwrap();
+    ///
+    /// assert_eq!(EvalErrorKind::Overflow, square.try_evaluate(50000).unwrap_err().kind());
+    ///
+    /// square.set_overflow_policy(OverflowPolicy::Saturating);
+    /// assert_eq!(i32::MAX, square.evaluate(50000));
+    ///
+    /// square.set_overflow_policy(OverflowPolicy::Wrapping);
+    /// assert_eq!(50000_i32.wrapping_mul(50000) + 1, square.evaluate(50000));
+    ///
+    /// assert!(Parametrizer::<u32>::new("10-t").is_err());
+    /// ```
+    pub fn set_overflow_policy(&mut self, policy: OverflowPolicy)
+    {
+
+        self.term.set_overflow_policy(policy);
+
+    }
+
+    ///Sets how NaN and infinite values are handled, which propagate by default. Under
+    ///NonFinitePolicy::Error, Parametrizer::try_evaluate returns an error with the path to the first
+    ///term which produced a non-finite value, while Parametrizer::evaluate panics. Under
+    ///NonFinitePolicy::Fallback, non-finite results are replaced by the given value. Has no effect
+    ///on integer types.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    /// use crate::parametrizer::ParametrizerFunction;
+    /// use crate::parametrizer::NonFinitePolicy;
+    /// use crate::parametrizer::EvalErrorKind;
+    ///
+    /// let mut log = Parametrizer::<f64>::new_functions("2+ln(t)*3", vec![ ParametrizerFunction::new("ln".to_string(), f64::ln) ]).unwrap();
+    ///
+    /// assert!(log.evaluate(-1.0).is_nan());
+    ///
+    /// log.set_non_finite_policy(NonFinitePolicy::Error);
+    ///
+    /// let error = log.try_evaluate(-1.0).unwrap_err();
+    ///
+    /// assert_eq!(EvalErrorKind::NonFinite, error.kind());
+    /// assert_eq!(&[1, 0], error.path());
+    /// assert_eq!(Ok(2.0), log.try_evaluate(1.0));
+    ///
+    /// log.set_non_finite_policy(NonFinitePolicy::Fallback(0.0));
+    ///
+    /// assert_eq!(0.0, log.evaluate(-1.0));
+    /// assert_eq!(Ok(0.0), log.try_evaluate(0.0));
+    /// ```
+    pub fn set_non_finite_policy(&mut self, policy: NonFinitePolicy<T>)
+    {
+
+        self.term.set_non_finite_policy(policy);
+
+    }
+
+    ///Replaces the random number generator used for dynamic ("rd(") random values with a ChaCha
+    ///generator seeded with the given seed, so that a sequence of evaluations can be replayed.
+    ///Computed ("rc(") random values were drawn when the string was parsed, so are unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    ///
+    /// let mut noise = Parametrizer::<f64>::new("rd(0<1)").unwrap();
+    ///
+    /// noise.reseed(7);
+    ///
+    /// let values: Vec<f64> = (0..5).map(|t| { return noise.evaluate(t as f64); }).collect();
+    ///
+    /// noise.reseed(7);
+    ///
+    /// let replayed: Vec<f64> = (0..5).map(|t| { return noise.evaluate(t as f64); }).collect();
+    ///
+    /// assert_eq!(values, replayed);
+    /// ```
+    pub fn reseed(&mut self, seed: u64)
+    {
+
+        self.set_rng(ChaCha8Rng::seed_from_u64(seed));
+
+    }
+
+    ///Replaces the random number generator used for dynamic ("rd(") random values with the given
+    ///generator. As with Parametrizer::reseed, computed ("rc(") random values are unchanged.
+    pub fn set_rng<R: RngCore + Send + 'static>(&mut self, rng: R)
+    {
+
+        self.term.set_rng(Some(Box::new(rng)));
+
+    }
+
+}
+
+impl<T: Number> fmt::Display for Parametrizer<T>
+{
+
+    ///Writes the function in the syntax accepted by Parametrizer::new, with as few parentheses as
+    ///possible, so that parsing the output with the same functions gives an equivalent function.
+    ///See Term::format for the terms which cannot be written exactly.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    ///
+    /// let polynomial = Parametrizer::<i32>::new("((3*t)*t) - 2*t + ((1))").unwrap();
+    /// let grouped = Parametrizer::<i32>::new("t*(3*t) - (2*t) + 1").unwrap();
+    ///
+    /// assert_eq!("3*t*t - 2*t + 1", polynomial.to_string());
+    /// //Parentheses which change the order of evaluation are kept, even when they do not change the
+    /// //value
+    /// assert_eq!("t*(3*t) - (2*t) + 1", grouped.to_string());
+    ///
+    /// let piecewise = Parametrizer::<f64>::new("p[4]sin((t))>0|-(1 - t)/2>-1.5").unwrap();
+    ///
+    /// assert_eq!("p[4] sin(t)>0|-(1 - t)/2>-1.5", piecewise.to_string());
+    /// assert_eq!(piecewise.to_string(), Parametrizer::<f64>::new(&piecewise.to_string()).unwrap().to_string());
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
     {
 
-        return (*self.term).evaluate(t);
+        return term::Term::format(&self.term, f);
 
     }
 
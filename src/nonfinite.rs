@@ -0,0 +1,33 @@
+use crate::Number;
+
+///How NaN and infinite values are handled when evaluating floating point functions, e.g. when a
+///user function such as f64::ln is applied outside of its domain. Integer types are always finite,
+///so are unaffected.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum NonFinitePolicy<T: Number>
+{
+
+    ///Non-finite values propagate through the function following IEEE 754 arithmetic
+    #[default]
+    Propagate,
+    ///Non-finite values are an error. Term::try_evaluate returns an error with the path to the
+    ///first term which produced a non-finite value, and Term::evaluate panics.
+    Error,
+    ///Non-finite results are replaced with the given value
+    Fallback(T)
+
+}
+
+//Whether the value is finite. Types which cannot be converted to f64 are assumed to be finite.
+pub(crate) fn is_finite<T: Number>(value: T) -> bool
+{
+
+    return match value.to_f64()
+    {
+
+        Some(v) => v.is_finite(),
+        None => true
+
+    };
+
+}
@@ -0,0 +1,249 @@
+use crate::Number;
+
+///How integer arithmetic behaves when a result cannot be represented by the number type. Applies to
+///addition and multiplication in sequences, scaling (including negation), and division. Floating
+///point types are unaffected and always use their own arithmetic.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverflowPolicy
+{
+
+    ///Overflow is an error, which panics in Term::evaluate and is returned by Term::try_evaluate
+    #[default]
+    Checked,
+    ///Results wrap around at the boundaries of the type, as with i32::wrapping_add
+    Wrapping,
+    ///Results are clamped to the minimum and maximum values of the type, as with i32::saturating_add
+    Saturating
+
+}
+
+#[derive(Clone, Copy)]
+enum Operation
+{
+
+    Add,
+    Subtract,
+    Multiply,
+    Divide
+
+}
+
+impl Operation
+{
+
+    fn plain<T: Number>(self, l: T, r: T) -> T
+    {
+
+        match self
+        {
+
+            Operation::Add => return l + r,
+            Operation::Subtract => return l - r,
+            Operation::Multiply => return l * r,
+            Operation::Divide => return l / r
+
+        }
+
+    }
+
+    fn signed(self, policy: OverflowPolicy, l: i128, r: i128) -> Option<i128>
+    {
+
+        match (policy, self)
+        {
+
+            (OverflowPolicy::Checked, Operation::Add) => return l.checked_add(r),
+            (OverflowPolicy::Checked, Operation::Subtract) => return l.checked_sub(r),
+            (OverflowPolicy::Checked, Operation::Multiply) => return l.checked_mul(r),
+            (OverflowPolicy::Checked, Operation::Divide) => return l.checked_div(r),
+            (OverflowPolicy::Wrapping, Operation::Add) => return Some(l.wrapping_add(r)),
+            (OverflowPolicy::Wrapping, Operation::Subtract) => return Some(l.wrapping_sub(r)),
+            (OverflowPolicy::Wrapping, Operation::Multiply) => return Some(l.wrapping_mul(r)),
+            (OverflowPolicy::Wrapping, Operation::Divide) => return if r == 0 { None } else { Some(l.wrapping_div(r)) },
+            (OverflowPolicy::Saturating, Operation::Add) => return Some(l.saturating_add(r)),
+            (OverflowPolicy::Saturating, Operation::Subtract) => return Some(l.saturating_sub(r)),
+            (OverflowPolicy::Saturating, Operation::Multiply) => return Some(l.saturating_mul(r)),
+            (OverflowPolicy::Saturating, Operation::Divide) => return if r == 0 { None } else { Some(l.saturating_div(r)) }
+
+        }
+
+    }
+
+    fn unsigned(self, policy: OverflowPolicy, l: u128, r: u128) -> Option<u128>
+    {
+
+        match (policy, self)
+        {
+
+            (_, Operation::Divide) => return l.checked_div(r),
+            (OverflowPolicy::Checked, Operation::Add) => return l.checked_add(r),
+            (OverflowPolicy::Checked, Operation::Subtract) => return l.checked_sub(r),
+            (OverflowPolicy::Checked, Operation::Multiply) => return l.checked_mul(r),
+            (OverflowPolicy::Wrapping, Operation::Add) => return Some(l.wrapping_add(r)),
+            (OverflowPolicy::Wrapping, Operation::Subtract) => return Some(l.wrapping_sub(r)),
+            (OverflowPolicy::Wrapping, Operation::Multiply) => return Some(l.wrapping_mul(r)),
+            (OverflowPolicy::Saturating, Operation::Add) => return Some(l.saturating_add(r)),
+            (OverflowPolicy::Saturating, Operation::Subtract) => return Some(l.saturating_sub(r)),
+            (OverflowPolicy::Saturating, Operation::Multiply) => return Some(l.saturating_mul(r))
+
+        }
+
+    }
+
+}
+
+//Integer division truncates, which is what distinguishes integer types from the others
+pub(crate) fn is_integer<T: Number>() -> bool
+{
+
+    return T::one() / (T::one() + T::one()) == T::zero();
+
+}
+
+//Whether the type is an integer type without negative values, so cannot be negated
+pub(crate) fn is_unsigned<T: Number>() -> bool
+{
+
+    return is_integer::<T>() && T::min_value() == T::zero();
+
+}
+
+impl OverflowPolicy
+{
+
+    ///Adds two values, returning None if the sum overflows under the Checked policy
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::OverflowPolicy;
+    ///
+    /// assert_eq!(Some(7), OverflowPolicy::Checked.add(3, 4));
+    /// assert_eq!(None, OverflowPolicy::Checked.add(i8::MAX, 1));
+    /// assert_eq!(Some(i8::MIN), OverflowPolicy::Wrapping.add(i8::MAX, 1));
+    /// assert_eq!(Some(u8::MAX), OverflowPolicy::Saturating.add(250_u8, 10));
+    /// assert_eq!(Some(0.75), OverflowPolicy::Checked.add(0.5, 0.25));
+    /// ```
+    pub fn add<T: Number>(self, l: T, r: T) -> Option<T>
+    {
+
+        return self.apply(Operation::Add, l, r);
+
+    }
+
+    ///Subtracts the right value from the left, returning None if the difference overflows under the
+    ///Checked policy
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::OverflowPolicy;
+    ///
+    /// assert_eq!(Some(-1), OverflowPolicy::Checked.subtract(3, 4));
+    /// assert_eq!(None, OverflowPolicy::Checked.subtract(3_u8, 4));
+    /// assert_eq!(Some(u8::MAX), OverflowPolicy::Wrapping.subtract(3_u8, 4));
+    /// assert_eq!(Some(0), OverflowPolicy::Saturating.subtract(3_u8, 4));
+    /// ```
+    pub fn subtract<T: Number>(self, l: T, r: T) -> Option<T>
+    {
+
+        return self.apply(Operation::Subtract, l, r);
+
+    }
+
+    ///Multiplies two values, returning None if the product overflows under the Checked policy
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::OverflowPolicy;
+    ///
+    /// assert_eq!(None, OverflowPolicy::Checked.multiply(i32::MIN, -1));
+    /// assert_eq!(Some(i32::MIN), OverflowPolicy::Wrapping.multiply(i32::MIN, -1));
+    /// assert_eq!(Some(i32::MAX), OverflowPolicy::Saturating.multiply(i32::MIN, -1));
+    /// assert_eq!(Some(u64::MAX - 1), OverflowPolicy::Wrapping.multiply(u64::MAX, 2));
+    /// assert_eq!(Some(u128::MAX), OverflowPolicy::Saturating.multiply(u128::MAX, 2));
+    /// ```
+    pub fn multiply<T: Number>(self, l: T, r: T) -> Option<T>
+    {
+
+        return self.apply(Operation::Multiply, l, r);
+
+    }
+
+    ///Divides two values, returning None if the quotient overflows under the Checked policy, which
+    ///can only happen when dividing the minimum of a signed type by -1. Also returns None when
+    ///dividing an integer by 0, regardless of the policy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::OverflowPolicy;
+    ///
+    /// assert_eq!(Some(-3), OverflowPolicy::Checked.divide(7, -2));
+    /// assert_eq!(None, OverflowPolicy::Checked.divide(i16::MIN, -1));
+    /// assert_eq!(Some(i16::MAX), OverflowPolicy::Saturating.divide(i16::MIN, -1));
+    /// assert_eq!(None, OverflowPolicy::Wrapping.divide(5, 0));
+    /// ```
+    pub fn divide<T: Number>(self, l: T, r: T) -> Option<T>
+    {
+
+        return self.apply(Operation::Divide, l, r);
+
+    }
+
+    fn apply<T: Number>(self, operation: Operation, l: T, r: T) -> Option<T>
+    {
+
+        if !is_integer::<T>()
+        {
+
+            return Some(operation.plain(l, r));
+
+        }
+
+        let bounds = (T::min_value().to_i128(), T::max_value().to_i128());
+
+        if let ((Some(min), Some(max)), Some(l), Some(r)) = (bounds, l.to_i128(), r.to_i128())
+        {
+
+            //Every type which fits in i128 has a power of two range, so wrapping in i128 and then
+            //reducing to the range of the type gives the same result as wrapping in the type itself
+            let computed = operation.signed(self, l, r)?;
+
+            return match self
+            {
+
+                OverflowPolicy::Checked => T::from_i128(computed),
+                OverflowPolicy::Saturating => T::from_i128(computed.clamp(min, max)),
+                OverflowPolicy::Wrapping =>
+                {
+
+                    let range = max.wrapping_sub(min).wrapping_add(1);
+
+                    //A range of 0 means the type spans all of i128
+                    if range == 0
+                    {
+
+                        T::from_i128(computed)
+
+                    }
+                    else
+                    {
+
+                        T::from_i128(computed.wrapping_sub(min).rem_euclid(range).wrapping_add(min))
+
+                    }
+
+                }
+
+            };
+
+        }
+
+        //Only unsigned types too large for i128 are left, which are assumed to span all of u128
+        return T::from_u128(operation.unsigned(self, l.to_u128()?, r.to_u128()?)?);
+
+    }
+
+}
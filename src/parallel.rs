@@ -0,0 +1,202 @@
+use rayon::prelude::*;
+
+use crate::Number;
+use crate::Parametrizer;
+use crate::overflow;
+
+impl<T: Number> Parametrizer<T>
+{
+
+    ///Evaluates the parametric function at every value in the slice, splitting the work across
+    ///rayon's global thread pool. The outputs are returned in the same order as the inputs. Random
+    ///terms draw from the thread-local random number generator of whichever thread evaluates them,
+    ///unless the parametrizer has its own generator, e.g. from Parametrizer::with_seed. In that case
+    ///one seed is drawn from it for the whole call, and each input is evaluated with its own stream
+    ///of a ChaCha generator seeded with it. Threads then never wait on the shared generator, and a
+    ///seeded parametrizer gives the same outputs however the work is scheduled, although they differ
+    ///from those of evaluating the inputs one at a time.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    ///
+    /// let parametrizer = Parametrizer::new("1+2*t*t").unwrap();
+    /// let inputs : Vec<f64> = (0..1000).map(|i| i as f64).collect();
+    ///
+    /// let outputs = parametrizer.par_evaluate_slice(&inputs);
+    ///
+    /// assert_eq!(1000, outputs.len());
+    /// assert_eq!(1.0, outputs[0]);
+    /// assert_eq!(19.0, outputs[3]);
+    /// assert_eq!(1996003.0, outputs[999]);
+    /// ```
+    pub fn par_evaluate_slice(&self, inputs: &[T]) -> Vec<T>
+    {
+
+        let seed = self.term.stream_seed();
+
+        return inputs.par_iter().enumerate().map(|(i, t)| { return self.par_evaluate(seed, i, *t); }).collect();
+
+    }
+
+    ///Evaluates the parametric function at count evenly spaced points between start and end
+    ///(inclusive) in parallel. Returns an empty vector if count is 0, and only the value at start
+    ///if count is 1. End may be less than start. For integer types the sample points are rounded
+    ///towards start. Random terms are evaluated as described for Parametrizer::par_evaluate_slice.
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    ///
+    /// let float = Parametrizer::new("3*t").unwrap();
+    /// let integer = Parametrizer::new("3*t").unwrap();
+    ///
+    /// assert_eq!(vec![0.0, 1.5, 3.0, 4.5, 6.0], float.par_sample(0.0, 2.0, 5));
+    /// assert_eq!(vec![3, 6, 9], integer.par_sample(1, 3, 3));
+    /// assert!(integer.par_sample(0, 10, 0).is_empty());
+    /// assert_eq!(vec![9, 6, 3], integer.par_sample(3, 1, 3));
+    /// ```
+    ///
+    /// ```
+    /// use crate::parametrizer::Parametrizer;
+    ///
+    /// let random = Parametrizer::new("rd(0<1)").unwrap();
+    /// let samples = random.par_sample(0.0, 1.0, 10000);
+    ///
+    /// let mean = samples.iter().sum::<f64>() / 10000.0;
+    ///
+    /// assert!(samples.iter().all(|s| *s >= 0.0 && *s < 1.0));
+    /// assert!(mean > 0.45 && mean < 0.55);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if count cannot be represented by a floating point type T, or if start and end cannot
+    /// be represented by i128 for an integer type T
+    pub fn par_sample(&self, start: T, end: T, count: usize) -> Vec<T>
+    {
+
+        if count == 0
+        {
+
+            return Vec::new();
+
+        }
+        else if count == 1
+        {
+
+            return vec![self.evaluate(start)];
+
+        }
+
+        let seed = self.term.stream_seed();
+
+        return (0..count).into_par_iter().map(|i|
+        {
+
+            if overflow::is_integer::<T>()
+            {
+
+                return self.par_evaluate(seed, i, integer_point(start, end, i, count - 1));
+
+            }
+
+            let intervals = T::from_usize(count - 1).expect("Unable to convert sample count to generic type T.");
+            let step = T::from_usize(i).expect("Unable to convert sample index to generic type T.");
+
+            return self.par_evaluate(seed, i, start + (end - start) * step / intervals);
+
+        }).collect();
+
+    }
+
+    //Evaluates the input at the index of a parallel evaluation, on its own stream of the seed drawn
+    //from the parametrizer's generator, if it has one
+    fn par_evaluate(&self, seed: Option<u64>, index: usize, t: T) -> T
+    {
+
+        match seed
+        {
+
+            Some(seed) => return self.term.evaluate_stream(seed, index as u64, t),
+            None => return self.evaluate(t)
+
+        }
+
+    }
+
+}
+
+//Computes the point step intervals of the way from start to end in i128, splitting the span into
+//whole parts and a remainder so that neither the span nor its product with step can overflow T
+fn integer_point<T: Number>(start: T, end: T, step: usize, intervals: usize) -> T
+{
+
+    let (start, end) = (start.to_i128().expect("Unable to convert sample bound to i128."), end.to_i128().expect("Unable to convert sample bound to i128."));
+    let (step, intervals) = (step as i128, intervals as i128);
+    let span = end - start;
+
+    let point = start + span / intervals * step + span % intervals * step / intervals;
+
+    return T::from_i128(point).expect("Unable to convert sample point to generic type T.");
+
+}
+
+#[cfg(test)]
+mod parallel_tests
+{
+
+    use crate::Parametrizer;
+
+    #[test]
+    fn test_large_spans ()
+    {
+
+        let identity = Parametrizer::<i32>::new("t").unwrap();
+        let samples = identity.par_sample(0, 1_000_000, 10_000);
+
+        assert_eq!((0, 100, 1_000_000), (samples[0], samples[1], samples[9999]));
+        assert!(samples.windows(2).all(|pair| { return pair[0] < pair[1]; }));
+
+        assert_eq!(vec![i32::MIN, -1, i32::MAX], identity.par_sample(i32::MIN, i32::MAX, 3));
+
+    }
+
+    #[test]
+    fn test_reversed_bounds ()
+    {
+
+        assert_eq!(vec![10, 8, 6, 4, 2, 0], Parametrizer::<u32>::new("t").unwrap().par_sample(10, 0, 6));
+        assert_eq!(vec![7, 4, 0], Parametrizer::<u8>::new("t").unwrap().par_sample(7, 0, 3));
+        assert_eq!(vec![2.0, 1.0, 0.0], Parametrizer::<f64>::new("t").unwrap().par_sample(2.0, 0.0, 3));
+
+    }
+
+    #[test]
+    fn test_seeded ()
+    {
+
+        let inputs : Vec<f64> = (0..2000).map(|i| { return i as f64; }).collect();
+
+        let first = Parametrizer::<f64>::with_seed("rd(t<t+1) + normal(0, 1)", Vec::new(), 11).unwrap();
+        let second = Parametrizer::<f64>::with_seed("rd(t<t+1) + normal(0, 1)", Vec::new(), 11).unwrap();
+
+        let single = rayon::ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+
+        let values = first.par_evaluate_slice(&inputs);
+
+        assert_eq!(values, single.install(|| { return second.par_evaluate_slice(&inputs); }));
+        assert_eq!(first.par_sample(0.0, 10.0, 500), second.par_sample(0.0, 10.0, 500));
+
+        //Each call draws a new seed from the generator
+        let again = first.par_evaluate_slice(&inputs);
+
+        assert_ne!(values, again);
+        assert_eq!(again, second.par_evaluate_slice(&inputs));
+
+        //Evaluating one at a time afterwards still uses the shared generator
+        assert_eq!(first.evaluate(3.0), second.evaluate(3.0));
+
+    }
+
+}
+
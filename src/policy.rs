@@ -0,0 +1,142 @@
+use crate::Number;
+use crate::ParseLimits;
+use crate::Smoothness;
+use crate::term::Subterm;
+use crate::term::TermView;
+
+//The estimated cost of evaluating a single term, excluding its subterms
+const TERM_COST : u64 = 1;
+//The estimated cost of calling a function or generating a random value
+const CALL_COST : u64 = 10;
+
+///Restrictions on the strings accepted by Parametrizer::with_policy, for parsing strings in
+///sandboxed contexts. Besides the ParseLimits, a policy bounds the estimated cost of evaluating the
+///function once, forbids features which are not whitelisted, and may require piecewise functions
+///to be smooth. Parsing fails with ParseErrorKind::CostExceeded, ParseErrorKind::ForbiddenFeature,
+///or ParseErrorKind::Discontinuous when the policy is violated. The default policy allows
+///everything, and individual restrictions can be set using struct update syntax.
+///
+/// # Examples
+///
+/// ```
+/// use crate::parametrizer::Parametrizer;
+/// use crate::parametrizer::ParametrizerFunction;
+/// use crate::parametrizer::Policy;
+/// use crate::parametrizer::ParseErrorKind;
+///
+/// let policy = Policy { allow_random: false, allowed_functions: Some(vec!["sin".to_string()]), ..Policy::default() };
+/// let functions = || { return vec![
+///
+///     ParametrizerFunction::new("sin".to_string(), f64::sin),
+///     ParametrizerFunction::new("exp".to_string(), f64::exp)
+///
+/// ]; };
+///
+/// assert!(Parametrizer::<f64>::with_policy("sin(t)+1", functions(), &policy).is_ok());
+///
+/// let random = Parametrizer::<f64>::with_policy("rd(0<t)", functions(), &policy);
+/// let exp = Parametrizer::<f64>::with_policy("exp(t)", functions(), &policy);
+///
+/// assert_eq!(ParseErrorKind::ForbiddenFeature, random.err().unwrap().kind());
+/// assert_eq!(ParseErrorKind::ForbiddenFeature, exp.err().unwrap().kind());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Policy
+{
+
+    ///The limits on the size and complexity of the string
+    pub limits: ParseLimits,
+    ///The maximum estimated cost of evaluating the function once. See Parametrizer::cost for how
+    ///the cost is estimated.
+    pub max_cost: u64,
+    ///Whether dynamic ("rd(") and computed ("rc(") random values, random distributions, random
+    ///choices, and noise, which is seeded from the random number generator, are allowed
+    pub allow_random: bool,
+    ///The identifiers of the functions which may be called, or None to allow all of the functions
+    ///passed to the constructor. Identifiers are case insensitive.
+    pub allowed_functions: Option<Vec<String>>,
+    ///The smoothness required at the breakpoints of piecewise functions, or None to allow any. See
+    ///Parametrizer::discontinuities for how breakpoints are checked. Breakpoints which cannot be
+    ///checked, because a part fails to evaluate, are rejected.
+    pub require_smoothness: Option<Smoothness>
+
+}
+
+impl Default for Policy
+{
+
+    fn default() -> Policy
+    {
+
+        return Policy { limits: ParseLimits::default(), max_cost: u64::MAX, allow_random: true, allowed_functions: None, require_smoothness: None };
+
+    }
+
+}
+
+impl Policy
+{
+
+    //Whether the function with the given lowercase identifier may be called
+    pub(crate) fn allows_function(&self, identifier: &str) -> bool
+    {
+
+        return match &self.allowed_functions
+        {
+
+            Some(allowed) => allowed.iter().any(|a| { return a.to_lowercase() == identifier; }),
+            None => true
+
+        };
+
+    }
+
+}
+
+//Estimates the cost of evaluating a term once. Every term costs TERM_COST plus the cost of its
+//subterms, except that function calls and random terms cost CALL_COST, noise costs CALL_COST per
+//octave, piecewise terms cost one per part for selecting the part plus the cost of the most
+//expensive part, as only one is evaluated, and random choices likewise cost their weights plus the
+//most expensive choice.
+pub(crate) fn estimate_cost<T: Number>(term: Subterm<'_, T>) -> u64
+{
+
+    let sum = |terms: &[Subterm<'_, T>], base: u64|
+    {
+
+        return terms.iter().fold(base, |cost, term| { return cost.saturating_add(estimate_cost(*term)); });
+
+    };
+
+    match term.to_view()
+    {
+
+        TermView::Constant(_) | TermView::Variable | TermView::Opaque => return TERM_COST,
+        TermView::Sequence(_, terms) => return sum(&terms, TERM_COST),
+        TermView::Scalar(subterm, _) => return sum(&[subterm], TERM_COST),
+        TermView::Fraction(numerator, denominator) => return sum(&[numerator, denominator], TERM_COST),
+        TermView::Function(subterm, _, _) => return sum(&[subterm], CALL_COST),
+        TermView::Random(min, max) => return sum(&[min, max], CALL_COST),
+        TermView::Distribution(_, parameters) => return sum(&parameters, CALL_COST),
+        TermView::Noise(subterm, noise) => return sum(&[subterm], CALL_COST.saturating_mul(u64::from(noise.octaves()))),
+        TermView::Choice(choices) =>
+        {
+
+            //Every weight is evaluated, but only one of the choices
+            let weights : Vec<Subterm<'_, T>> = choices.iter().map(|choice| { return choice.0; }).collect();
+
+            return choices.iter().map(|choice| { return estimate_cost(choice.1); }).max().unwrap_or(0).saturating_add(sum(&weights, CALL_COST));
+
+        },
+        TermView::Piecewise(parts, _) =>
+        {
+
+            let selection = (parts.len() as u64).saturating_add(TERM_COST);
+
+            return parts.iter().map(|part| { return estimate_cost(part.0); }).max().unwrap_or(0).saturating_add(selection);
+
+        }
+
+    }
+
+}
@@ -0,0 +1,586 @@
+use crate::Number;
+use crate::format;
+use crate::format::Shape;
+use crate::term::Subterm;
+use crate::term::TermView;
+use crate::term::arenaterm::ArenaTerm;
+use crate::term::sequenceterm::SequenceOperations;
+
+//The LaTeX commands for standard functions, by identifier
+const STANDARD_FUNCTIONS : [(&str, &str); 18] = [
+
+    ("sin", "\\sin"), ("cos", "\\cos"), ("tan", "\\tan"), ("sec", "\\sec"), ("csc", "\\csc"), ("cot", "\\cot"),
+    ("asin", "\\arcsin"), ("acos", "\\arccos"), ("atan", "\\arctan"), ("arcsin", "\\arcsin"), ("arccos", "\\arccos"), ("arctan", "\\arctan"),
+    ("sinh", "\\sinh"), ("cosh", "\\cosh"), ("tanh", "\\tanh"), ("exp", "\\exp"), ("ln", "\\ln"), ("log", "\\log")
+
+];
+
+//Written for functions without an identifier
+const UNNAMED : &str = "f";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Notation
+{
+
+    Latex,
+    MathMl
+
+}
+
+//A rendered term, with its leading minus sign, if any, removed so that sums can write it as
+//subtraction
+struct Rendered
+{
+
+    text: String,
+    negative: bool,
+    shape: Shape
+
+}
+
+impl Rendered
+{
+
+    fn new(text: String, shape: Shape) -> Rendered
+    {
+
+        return Rendered { text, negative: false, shape };
+
+    }
+
+}
+
+//Walks the tree of an arena, writing each term in the notation. Parts of looping piecewise terms
+//are written in terms of tau, the input after looping.
+struct Renderer<'a, T: Number>
+{
+
+    notation: Notation,
+    arena: &'a ArenaTerm<T>,
+    looping: bool
+
+}
+
+//Writes the function as LaTeX, for use in math mode
+pub(crate) fn latex<T: Number>(arena: &ArenaTerm<T>) -> String
+{
+
+    let mut renderer = Renderer { notation: Notation::Latex, arena, looping: false };
+    let rendered = renderer.render(arena.root());
+
+    return renderer.full(rendered);
+
+}
+
+//Writes the function as a presentation MathML math element
+pub(crate) fn mathml<T: Number>(arena: &ArenaTerm<T>) -> String
+{
+
+    let mut renderer = Renderer { notation: Notation::MathMl, arena, looping: false };
+    let rendered = renderer.render(arena.root());
+
+    return format!("<math xmlns=\"http://www.w3.org/1998/Math/MathML\">{}</math>", renderer.full(rendered));
+
+}
+
+//Escapes the characters which cannot appear in the text of a MathML element
+fn escape(text: &str) -> String
+{
+
+    return text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+
+}
+
+impl<T: Number> Renderer<'_, T>
+{
+
+    //Chooses between the LaTeX and the content of a MathML element
+    fn pick(&self, latex: &str, mathml: &str) -> String
+    {
+
+        match self.notation
+        {
+
+            Notation::Latex => return latex.to_string(),
+            Notation::MathMl => return mathml.to_string()
+
+        }
+
+    }
+
+    fn operator(&self, latex: &str, mathml: &str) -> String
+    {
+
+        return self.pick(latex, &format!("<mo>{}</mo>", mathml));
+
+    }
+
+    fn identifier(&self, name: &str) -> String
+    {
+
+        return self.pick(&format!("\\mathrm{{{}}}", name), &format!("<mi>{}</mi>", escape(name)));
+
+    }
+
+    fn variable(&self) -> String
+    {
+
+        return if self.looping { self.pick("\\tau", "<mi>&#x3C4;</mi>") } else { self.pick("t", "<mi>t</mi>") };
+
+    }
+
+    fn number(&self, written: &str) -> String
+    {
+
+        match written
+        {
+
+            "inf" => return self.pick("\\infty", "<mi>&#x221E;</mi>"),
+            "NaN" => return self.identifier("NaN"),
+            _ => return self.pick(written, &format!("<mn>{}</mn>", written))
+
+        }
+
+    }
+
+    fn group(&self, text: &str) -> String
+    {
+
+        return self.pick(&format!("\\left({}\\right)", text), &format!("<mrow><mo>(</mo>{}<mo>)</mo></mrow>", text));
+
+    }
+
+    fn row(&self, text: &str) -> String
+    {
+
+        return self.pick(text, &format!("<mrow>{}</mrow>", text));
+
+    }
+
+    //Writes a function applied to its arguments, given the name already written in the notation
+    fn call(&self, name: &str, arguments: &[String]) -> String
+    {
+
+        let arguments = arguments.join(&self.operator(", ", ","));
+
+        return format!("{}{}{}", name, self.pick("", "<mo>&#x2061;</mo>"), self.group(&arguments));
+
+    }
+
+    //Writes a rendered term with its leading minus sign
+    fn full(&self, rendered: Rendered) -> String
+    {
+
+        if rendered.negative
+        {
+
+            return format!("{}{}", self.operator("-", "&#x2212;"), rendered.text);
+
+        }
+
+        return rendered.text;
+
+    }
+
+    //Writes a rendered term, in parentheses if it has one of the given shapes or starts with a minus
+    //sign which would otherwise follow an operator
+    fn operand(&self, rendered: Rendered, wrapped: &[Shape], leading_minus: bool) -> Rendered
+    {
+
+        if wrapped.contains(&rendered.shape) || (rendered.negative && !leading_minus)
+        {
+
+            return Rendered::new(self.group(&self.full(rendered)), Shape::Atom);
+
+        }
+
+        return rendered;
+
+    }
+
+    fn render(&mut self, term: Subterm<'_, T>) -> Rendered
+    {
+
+        match term.to_view()
+        {
+
+            TermView::Constant(c) => return self.constant(c),
+            TermView::Variable => return Rendered::new(self.variable(), Shape::Atom),
+            TermView::Sequence(SequenceOperations::Addition, terms) =>
+            {
+
+                if terms.is_empty()
+                {
+
+                    return Rendered::new(self.number("0"), Shape::Constant);
+
+                }
+
+                let mut sum = Rendered::new(String::new(), Shape::Sum);
+
+                for (i, term) in terms.into_iter().enumerate()
+                {
+
+                    let rendered = self.render(term);
+                    let rendered = self.operand(rendered, &[Shape::Sum], true);
+
+                    if i == 0
+                    {
+
+                        sum.negative = rendered.negative;
+
+                    }
+                    else if rendered.negative
+                    {
+
+                        sum.text.push_str(&self.operator(" - ", "&#x2212;"));
+
+                    }
+                    else
+                    {
+
+                        sum.text.push_str(&self.operator(" + ", "+"));
+
+                    }
+
+                    sum.text.push_str(&rendered.text);
+
+                }
+
+                return sum;
+
+            },
+            TermView::Sequence(SequenceOperations::Multiplication, terms) =>
+            {
+
+                if terms.is_empty()
+                {
+
+                    return Rendered::new(self.number("1"), Shape::Constant);
+
+                }
+
+                let factors = terms.into_iter().map(|term| { return self.render(term); }).collect();
+
+                return self.product(factors);
+
+            },
+            TermView::Scalar(term, scale) =>
+            {
+
+                let rendered = self.render(term);
+
+                if format::is_negation(scale)
+                {
+
+                    let mut negated = self.operand(rendered, &[Shape::Sum], false);
+
+                    negated.negative = true;
+                    negated.shape = Shape::Negation;
+
+                    return negated;
+
+                }
+
+                let scale = self.constant(scale);
+
+                return self.product(vec![scale, rendered]);
+
+            },
+            TermView::Fraction(numerator, denominator) =>
+            {
+
+                //A minus sign in the numerator is written before the fraction, as in -\frac{1}{t}
+                let (mut numerator, denominator) = (self.render(numerator), self.render(denominator));
+                let negative = numerator.negative && numerator.shape != Shape::Sum;
+
+                numerator.negative &= !negative;
+
+                let (numerator, denominator) = (self.full(numerator), self.full(denominator));
+                let text = self.pick(&format!("\\frac{{{}}}{{{}}}", numerator, denominator), &format!("<mfrac><mrow>{}</mrow><mrow>{}</mrow></mfrac>", numerator, denominator));
+
+                return Rendered { text, negative, shape: Shape::Fraction };
+
+            },
+            TermView::Function(term, _, identifier) =>
+            {
+
+                let argument = self.render(term);
+                let argument = self.full(argument);
+
+                return Rendered::new(self.function(identifier.unwrap_or(UNNAMED), argument), Shape::Atom);
+
+            },
+            TermView::Random(min, max) =>
+            {
+
+                let (min, max) = (self.render(min), self.render(max));
+                let bounds = [self.full(min), self.full(max)];
+
+                return Rendered::new(self.call(&self.identifier("rand"), &bounds), Shape::Atom);
+
+            },
+            TermView::Distribution(distribution, terms) =>
+            {
+
+                let parameters = terms.into_iter().map(|term| { let rendered = self.render(term); return self.full(rendered); }).collect::<Vec<String>>();
+
+                return Rendered::new(self.call(&self.identifier(distribution.identifier()), &parameters), Shape::Atom);
+
+            },
+            TermView::Choice(choices) =>
+            {
+
+                let mut written = Vec::new();
+
+                for (weight, term) in choices
+                {
+
+                    let (weight, term) = (self.render(weight), self.render(term));
+
+                    written.push(format!("{}{}{}", self.full(weight), self.operator(" : ", ":"), self.full(term)));
+
+                }
+
+                return Rendered::new(self.call(&self.identifier("choice"), &written), Shape::Atom);
+
+            },
+            TermView::Noise(term, noise) =>
+            {
+
+                let argument = self.render(term);
+                let mut arguments = vec![self.full(argument)];
+
+                if noise.octaves() == 1
+                {
+
+                    return Rendered::new(self.call(&self.identifier(noise.kind().identifier()), &arguments), Shape::Atom);
+
+                }
+
+                arguments.push(self.number(&noise.octaves().to_string()));
+
+                if noise.lacunarity() != format::DEFAULT_LACUNARITY || noise.gain() != format::DEFAULT_GAIN
+                {
+
+                    arguments.push(self.number(&noise.lacunarity().to_string()));
+                    arguments.push(self.number(&noise.gain().to_string()));
+
+                }
+
+                return Rendered::new(self.call(&self.identifier("fbm"), &arguments), Shape::Atom);
+
+            },
+            TermView::Piecewise(parts, cycle) => return Rendered::new(self.piecewise(&parts, cycle), Shape::Unparseable),
+            TermView::Opaque => return Rendered::new(self.pick("\\square", "<mi>&#x25A1;</mi>"), Shape::Atom)
+
+        }
+
+    }
+
+    fn constant(&self, c: T) -> Rendered
+    {
+
+        let written = format::number(c);
+
+        return match written.strip_prefix('-')
+        {
+
+            Some(rest) => Rendered { text: self.number(rest), negative: true, shape: Shape::Constant },
+            None => Rendered::new(self.number(&written), Shape::Constant)
+
+        };
+
+    }
+
+    //Writes factors next to each other when a number is followed by a variable, call, or
+    //parentheses, as in 2t, and with a multiplication dot otherwise
+    fn product(&self, factors: Vec<Rendered>) -> Rendered
+    {
+
+        let mut product = Rendered::new(String::new(), Shape::Product);
+        let mut previous = Shape::Product;
+
+        for (i, factor) in factors.into_iter().enumerate()
+        {
+
+            let shape = factor.shape;
+            let factor = self.operand(factor, &[Shape::Sum], i == 0);
+
+            if i == 0
+            {
+
+                product.negative = factor.negative;
+
+            }
+            else if previous == Shape::Constant && factor.shape == Shape::Atom && shape != Shape::Constant
+            {
+
+                product.text.push_str(&self.pick("", "<mo>&#x2062;</mo>"));
+
+            }
+            else
+            {
+
+                product.text.push_str(&self.operator(" \\cdot ", "&#x22C5;"));
+
+            }
+
+            product.text.push_str(&factor.text);
+            previous = shape;
+
+        }
+
+        return product;
+
+    }
+
+    fn function(&self, identifier: &str, argument: String) -> String
+    {
+
+        if let Some(latex) = self.arena.function_latex(identifier).filter(|_| { return self.notation == Notation::Latex; })
+        {
+
+            return self.call(latex, &[argument]);
+
+        }
+
+        match identifier
+        {
+
+            "sqrt" => return self.pick(&format!("\\sqrt{{{}}}", argument), &format!("<msqrt>{}</msqrt>", argument)),
+            "abs" => return self.pick(&format!("\\left|{}\\right|", argument), &format!("<mrow><mo>|</mo>{}<mo>|</mo></mrow>", argument)),
+            _ => ()
+
+        }
+
+        let name = match STANDARD_FUNCTIONS.iter().find(|f| { return f.0 == identifier; })
+        {
+
+            Some(standard) => self.pick(standard.1, &format!("<mi>{}</mi>", identifier)),
+            None => self.pick(&format!("\\operatorname{{{}}}", identifier), &format!("<mi>{}</mi>", escape(identifier)))
+
+        };
+
+        return self.call(&name, &[argument]);
+
+    }
+
+    //Writes the parts as cases, each applying from its after value until the next part's, with the
+    //first applying before every other part and the last applying after
+    fn piecewise(&mut self, parts: &[(Subterm<'_, T>, T)], cycle: Option<T>) -> String
+    {
+
+        let outer = self.variable();
+        let looping = self.looping;
+
+        self.looping = cycle.is_some();
+
+        let variable = self.variable();
+        let mut rows = Vec::new();
+
+        for (i, part) in parts.iter().enumerate()
+        {
+
+            let rendered = self.render(part.0);
+            let value = self.full(rendered);
+
+            let after = |j: usize| { let written = self.constant(parts[j].1); return self.full(written); };
+
+            let condition = if parts.len() == 1
+            {
+
+                String::new()
+
+            }
+            else if i == 0
+            {
+
+                format!("{}{}{}", variable, self.operator(" < ", "&lt;"), after(1))
+
+            }
+            else if i == parts.len() - 1
+            {
+
+                format!("{}{}{}", variable, self.operator(" \\ge ", "&#x2265;"), after(i))
+
+            }
+            else
+            {
+
+                format!("{}{}{}{}{}", after(i), self.operator(" \\le ", "&#x2264;"), variable, self.operator(" < ", "&lt;"), after(i + 1))
+
+            };
+
+            rows.push(self.pick(&format!("{} & {}", value, condition), &format!("<mtr><mtd>{}</mtd><mtd>{}</mtd></mtr>", self.row(&value), self.row(&condition))));
+
+        }
+
+        self.looping = looping;
+
+        let mut cases = self.pick(&format!("\\begin{{cases}} {} \\end{{cases}}", rows.join(" \\\\ ")), &format!("<mrow><mo>{{</mo><mtable columnalign=\"left\">{}</mtable></mrow>", rows.concat()));
+
+        if let Some(c) = cycle
+        {
+
+            let c = self.constant(c);
+            let c = self.full(c);
+
+            cases.push_str(&self.pick(&format!(", \\quad \\tau = {} \\bmod {}", outer, c), &format!("<mo>,</mo><mspace width=\"1em\"/><mi>&#x3C4;</mi><mo>=</mo>{}<mo>mod</mo>{}", outer, c)));
+
+        }
+
+        return cases;
+
+    }
+
+}
+
+#[cfg(test)]
+mod render_tests
+{
+
+    use crate::Parametrizer;
+    use crate::ParametrizerFunction;
+
+    #[test]
+    fn test_latex ()
+    {
+
+        let latex = |param: &str| { return Parametrizer::<f64>::new(param).unwrap().to_latex(); };
+
+        assert_eq!("3t \\cdot t - 2t + 1", latex("3*t*t - 2*t + 1"));
+        assert_eq!("\\frac{1 - t}{2} \\cdot \\left(t + 1\\right)", latex("(1 - t)/2*(t+1)"));
+        assert_eq!("-\\left(t + 1\\right) \\cdot \\left(-2\\right) - \\sin\\left(-t\\right)", latex("-(t+1)*(-2) - sin(-t)"));
+        assert_eq!("\\mathrm{rand}\\left(0, 2t\\right) + \\mathrm{normal}\\left(t, 1\\right)", latex("rd(0<2*t) + normal(t, 1)"));
+        assert_eq!("\\begin{cases} t & t < 1 \\\\ 2 & 1 \\le t < 3 \\\\ -t & t \\ge 3 \\end{cases}", latex("p t>0|2>1|-t>3"));
+        assert_eq!("\\begin{cases} \\tau & \\tau < 2 \\\\ 4 - \\tau & \\tau \\ge 2 \\end{cases}, \\quad \\tau = t \\bmod 4", latex("p[4]t>0|4-t>2"));
+
+        let functions = vec![
+
+            ParametrizerFunction::new("sqrt".to_string(), f64::sqrt),
+            ParametrizerFunction::new("sinc".to_string(), f64::sin).with_latex("\\operatorname{sinc}".to_string()),
+            ParametrizerFunction::new("erf".to_string(), f64::tanh)
+
+        ];
+
+        let special = Parametrizer::<f64>::new_functions("sqrt(t) + sinc(t) + erf(t)", functions).unwrap();
+
+        assert_eq!("\\sqrt{t} + \\operatorname{sinc}\\left(t\\right) + \\operatorname{erf}\\left(t\\right)", special.to_latex());
+
+    }
+
+    #[test]
+    fn test_mathml ()
+    {
+
+        let mathml = |param: &str| { return Parametrizer::<i32>::new(param).unwrap().to_mathml(); };
+        let math = |content: &str| { return format!("<math xmlns=\"http://www.w3.org/1998/Math/MathML\">{}</math>", content); };
+
+        assert_eq!(math("<mn>2</mn><mo>&#x2062;</mo><mi>t</mi><mo>&#x2212;</mo><mfrac><mrow><mn>1</mn></mrow><mrow><mi>t</mi></mrow></mfrac>"), mathml("2*t - 1/t"));
+        assert_eq!(math("<mi>sin</mi><mo>&#x2061;</mo><mrow><mo>(</mo><mo>&#x2212;</mo><mi>t</mi><mo>)</mo></mrow>"), mathml("sin(-t)"));
+        assert_eq!(math("<mrow><mo>{</mo><mtable columnalign=\"left\"><mtr><mtd><mrow><mi>t</mi></mrow></mtd><mtd><mrow><mi>t</mi><mo>&lt;</mo><mn>5</mn></mrow></mtd></mtr><mtr><mtd><mrow><mn>5</mn></mrow></mtd><mtd><mrow><mi>t</mi><mo>&#x2265;</mo><mn>5</mn></mrow></mtd></mtr></mtable></mrow>"), mathml("p t>0|5>5"));
+
+    }
+
+}
@@ -0,0 +1,562 @@
+use std::fmt;
+
+use crate::Number;
+use crate::EvalError;
+use crate::EvalErrorKind;
+use crate::overflow;
+use crate::sampler;
+use crate::sampler::Sampler;
+use crate::term::arenaterm::ArenaTerm;
+
+//The most iterations of Brent's or Newton's method before the current estimate is accepted
+const MAX_ITERATIONS : usize = 200;
+
+///The reason why a root could not be found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RootError
+{
+
+    ///The function does not reach the target anywhere it was searched, or only jumps past it
+    NoRoot,
+    ///The function failed to evaluate while searching
+    Evaluation(EvalError)
+
+}
+
+impl From<EvalError> for RootError
+{
+
+    fn from(error: EvalError) -> RootError
+    {
+
+        return RootError::Evaluation(error);
+
+    }
+
+}
+
+impl fmt::Display for RootError
+{
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+
+        match self
+        {
+
+            RootError::NoRoot => return write!(f, "Parametrizer found no root, as the function does not reach the target within the bracket"),
+            RootError::Evaluation(error) => return write!(f, "Parametrizer failed to find a root due to an evaluation error: {}", error)
+
+        }
+
+    }
+
+}
+
+//Solves f(t) = target, working with g(t) = f(t) - target in f64
+struct Solver<'a, T: Number>
+{
+
+    sampler: Sampler<'a, T>,
+    derivative: Option<Sampler<'a, T>>,
+    target: f64
+
+}
+
+//Finds the first root within the bracket, searching the segments between piecewise breakpoints in
+//order
+pub(crate) fn find_root<T: Number>(term: &ArenaTerm<T>, derivative: Option<&ArenaTerm<T>>, target: T, bracket: (T, T)) -> Result<T, RootError>
+{
+
+    let solver = Solver::new(term, derivative, target)?;
+    let (start, end) = bounds(bracket.0, bracket.1)?;
+
+    let points = sampler::segments(term, start, end);
+    let last = points.len() - 2;
+
+    for (i, segment) in points.windows(2).enumerate()
+    {
+
+        if let Some(root) = solver.search(segment[0], segment[1], i == last)?
+        {
+
+            return T::from_f64(root).ok_or(RootError::NoRoot);
+
+        }
+
+    }
+
+    return Err(RootError::NoRoot);
+
+}
+
+//Finds every root within the range which is separated from the others by a change of sign between
+//the sample points, or which is itself a sample point
+pub(crate) fn find_all_roots<T: Number>(term: &ArenaTerm<T>, derivative: Option<&ArenaTerm<T>>, range: (T, T), resolution: usize) -> Result<Vec<T>, EvalError>
+{
+
+    let solver = Solver::new(term, derivative, T::zero())?;
+    let (start, end) = bounds(range.0, range.1)?;
+
+    let mut step = (end - start) / resolution.max(1) as f64;
+
+    //Integer types are sampled at integers
+    if overflow::is_integer::<T>()
+    {
+
+        step = step.ceil().max(1.0);
+
+    }
+
+    let segments = sampler::segments(term, start, end);
+    let mut points = Vec::new();
+
+    //Samples each segment separately, marking the breakpoints at which the next segment starts
+    for (i, segment) in segments.windows(2).enumerate()
+    {
+
+        let mut k = 0.0;
+
+        while segment[0] + k * step < segment[1] - step * 1e-9
+        {
+
+            points.push((segment[0] + k * step, false));
+            k += 1.0;
+
+        }
+
+        points.push((segment[1], i + 2 < segments.len()));
+
+    }
+
+    let mut roots : Vec<f64> = Vec::new();
+
+    for pair in points.windows(2)
+    {
+
+        let ((a, _), (b, breakpoint)) = (pair[0], pair[1]);
+
+        if b <= a
+        {
+
+            continue;
+
+        }
+
+        if let Some(root) = solver.search(a, b, !breakpoint)?
+        {
+
+            if roots.last() != Some(&root)
+            {
+
+                roots.push(root);
+
+            }
+
+        }
+
+    }
+
+    return Ok(roots.into_iter().filter_map(T::from_f64).collect());
+
+}
+
+fn to_f64<T: Number>(value: T) -> Result<f64, EvalError>
+{
+
+    return value.to_f64().ok_or(EvalError::new(EvalErrorKind::FunctionConversion));
+
+}
+
+fn bounds<T: Number>(a: T, b: T) -> Result<(f64, f64), EvalError>
+{
+
+    let a = to_f64(a)?;
+    let b = to_f64(b)?;
+
+    return Ok((a.min(b), a.max(b)));
+
+}
+
+impl<'a, T: Number> Solver<'a, T>
+{
+
+    fn new(term: &'a ArenaTerm<T>, derivative: Option<&'a ArenaTerm<T>>, target: T) -> Result<Solver<'a, T>, EvalError>
+    {
+
+        return Ok(Solver { sampler: Sampler::new(term), derivative: derivative.map(Sampler::new), target: to_f64(target)? });
+
+    }
+
+    fn g(&self, x: f64) -> Result<f64, EvalError>
+    {
+
+        return Ok(self.sampler.evaluate(x)? - self.target);
+
+    }
+
+    //Searches a segment within which the function is continuous, other than at poles. The value at
+    //the end of the segment is taken from just inside it, unless the end is included because no
+    //other segment starts there.
+    fn search(&self, a: f64, b: f64, include_end: bool) -> Result<Option<f64>, EvalError>
+    {
+
+        let integer = overflow::is_integer::<T>();
+        let ga = self.g(a)?;
+
+        if ga == 0.0
+        {
+
+            return Ok(Some(a));
+
+        }
+
+        //For integer types, the last integer before the end is inside the segment
+        let (b, include_end) = if integer && !include_end { ((b - 1.0).ceil(), true) } else { (b, include_end) };
+
+        if b <= a
+        {
+
+            return Ok(None);
+
+        }
+
+        let gb = if include_end { self.g(b)? } else { self.sampler.evaluate_inside(b, a)? - self.target };
+
+        if gb == 0.0 && include_end
+        {
+
+            return Ok(Some(b));
+
+        }
+
+        if ga.signum() == gb.signum() || ga.is_nan() || gb.is_nan()
+        {
+
+            return Ok(None);
+
+        }
+
+        if integer
+        {
+
+            return self.bisect_integers(a, b, ga);
+
+        }
+
+        let root = match &self.derivative
+        {
+
+            Some(derivative) => self.newton(derivative, a, b, ga),
+            None => self.brent(a, b, ga, gb)
+
+        };
+
+        //A jump across the target, such as at a pole, is found like a root, but the function stays
+        //far from the target on both sides of it. Failing to evaluate between the ends of the
+        //segment, such as exactly at a pole, likewise means there is no root.
+        match root.and_then(|root| { return Ok((root, self.g(root)?)); })
+        {
+
+            Ok((root, value)) if value.abs() <= ga.abs().min(gb.abs()) => return Ok(Some(root)),
+            _ => return Ok(None)
+
+        }
+
+    }
+
+    //Finds an integer at which the function is exactly the target, where the function changes sign
+    //between a and b, by bisection over the integers
+    fn bisect_integers(&self, a: f64, b: f64, ga: f64) -> Result<Option<f64>, EvalError>
+    {
+
+        let (mut low, mut high) = (a.ceil(), b.floor());
+
+        if low > high
+        {
+
+            return Ok(None);
+
+        }
+
+        if self.g(low)? == 0.0
+        {
+
+            return Ok(Some(low));
+
+        }
+
+        while high - low > 1.0
+        {
+
+            let middle = ((low + high) / 2.0).floor();
+            let gm = self.g(middle)?;
+
+            if gm == 0.0
+            {
+
+                return Ok(Some(middle));
+
+            }
+
+            if gm.signum() == ga.signum()
+            {
+
+                low = middle;
+
+            }
+            else
+            {
+
+                high = middle;
+
+            }
+
+        }
+
+        return Ok(if self.g(high)? == 0.0 { Some(high) } else { None });
+
+    }
+
+    //Brent's method, combining bisection with the secant method and inverse quadratic interpolation
+    fn brent(&self, a: f64, b: f64, ga: f64, gb: f64) -> Result<f64, EvalError>
+    {
+
+        let (mut a, mut b, mut fa, mut fb) = (a, b, ga, gb);
+        let (mut c, mut fc) = (a, fa);
+        let mut d = b - a;
+        let mut e = d;
+
+        for _ in 0..MAX_ITERATIONS
+        {
+
+            if fb.signum() == fc.signum()
+            {
+
+                c = a;
+                fc = fa;
+                d = b - a;
+                e = d;
+
+            }
+
+            if fc.abs() < fb.abs()
+            {
+
+                a = b;
+                b = c;
+                c = a;
+                fa = fb;
+                fb = fc;
+                fc = fa;
+
+            }
+
+            let tolerance = 2.0 * f64::EPSILON * b.abs() + f64::MIN_POSITIVE;
+            let m = (c - b) / 2.0;
+
+            if m.abs() <= tolerance || fb == 0.0
+            {
+
+                return Ok(b);
+
+            }
+
+            if e.abs() >= tolerance && fa.abs() > fb.abs()
+            {
+
+                let s = fb / fa;
+
+                let (mut p, mut q) = if a == c
+                {
+
+                    (2.0 * m * s, 1.0 - s)
+
+                }
+                else
+                {
+
+                    let q = fa / fc;
+                    let r = fb / fc;
+
+                    (s * (2.0 * m * q * (q - r) - (b - a) * (r - 1.0)), (q - 1.0) * (r - 1.0) * (s - 1.0))
+
+                };
+
+                if p > 0.0
+                {
+
+                    q = -q;
+
+                }
+                else
+                {
+
+                    p = -p;
+
+                }
+
+                if 2.0 * p < (3.0 * m * q - (tolerance * q).abs()).min((e * q).abs())
+                {
+
+                    e = d;
+                    d = p / q;
+
+                }
+                else
+                {
+
+                    d = m;
+                    e = m;
+
+                }
+
+            }
+            else
+            {
+
+                d = m;
+                e = m;
+
+            }
+
+            a = b;
+            fa = fb;
+            b += if d.abs() > tolerance { d } else { tolerance.copysign(m) };
+            fb = self.g(b)?;
+
+        }
+
+        return Ok(b);
+
+    }
+
+    //Newton's method using the symbolic derivative, falling back to bisection whenever a step
+    //would leave the bracket or converge too slowly
+    fn newton(&self, derivative: &Sampler<'_, T>, a: f64, b: f64, ga: f64) -> Result<f64, EvalError>
+    {
+
+        //The bracket is kept with a negative value at low and a positive one at high
+        let (mut low, mut high) = if ga < 0.0 { (a, b) } else { (b, a) };
+
+        let mut x = (a + b) / 2.0;
+        let mut previous = (b - a).abs();
+        let mut step = previous;
+        let mut gx = self.g(x)?;
+        let mut slope = derivative.evaluate(x)?;
+
+        for _ in 0..MAX_ITERATIONS
+        {
+
+            if gx == 0.0
+            {
+
+                return Ok(x);
+
+            }
+
+            let leaves = ((x - high) * slope - gx) * ((x - low) * slope - gx) > 0.0;
+
+            if leaves || (2.0 * gx).abs() > (previous * slope).abs() || !slope.is_finite()
+            {
+
+                previous = step;
+                step = (high - low) / 2.0;
+                x = low + step;
+
+            }
+            else
+            {
+
+                previous = step;
+                step = gx / slope;
+                x -= step;
+
+            }
+
+            if step.abs() <= 2.0 * f64::EPSILON * x.abs() + f64::MIN_POSITIVE
+            {
+
+                return Ok(x);
+
+            }
+
+            gx = self.g(x)?;
+            slope = derivative.evaluate(x)?;
+
+            if gx < 0.0
+            {
+
+                low = x;
+
+            }
+            else
+            {
+
+                high = x;
+
+            }
+
+        }
+
+        return Ok(x);
+
+    }
+
+}
+
+#[cfg(test)]
+mod roots_tests
+{
+
+    use crate::Parametrizer;
+    use crate::ParametrizerFunction;
+    use super::RootError;
+
+    #[test]
+    fn test_methods ()
+    {
+
+        //cbrt has no symbolic derivative, so Brent's method is used, and sin uses Newton's method
+        let functions = || { return vec![ParametrizerFunction::new("cbrt".to_string(), f64::cbrt), ParametrizerFunction::new("sin".to_string(), f64::sin)]; };
+
+        let root = Parametrizer::<f64>::new_functions("cbrt(t) - 1.5", functions()).unwrap();
+        let wave = Parametrizer::<f64>::new_functions("sin(t)", functions()).unwrap();
+
+        assert!((root.find_root(0.0, (0.0, 10.0)).unwrap() - 3.375).abs() < 1e-12);
+
+        let roots = wave.find_all_roots((0.5, 10.0), 50).unwrap();
+
+        assert_eq!(3, roots.len());
+
+        for (i, r) in roots.iter().enumerate()
+        {
+
+            assert!((r - std::f64::consts::PI * (i + 1) as f64).abs() < 1e-12);
+
+        }
+
+    }
+
+    #[test]
+    fn test_discontinuities ()
+    {
+
+        let pole = Parametrizer::<f64>::new("1/(t-1)").unwrap();
+        let jump = Parametrizer::<f64>::new("p t-2>0|t>1").unwrap();
+        let integer = Parametrizer::<i32>::new("p t-5>0|t-20>10|0>30").unwrap();
+
+        assert_eq!(Err(RootError::NoRoot), pole.find_root(0.0, (0.0, 3.0)));
+        assert!(pole.find_all_roots((0.0, 3.0), 7).unwrap().is_empty());
+
+        //The first part reaches 0 at 2 only after it stops applying, and the second never does
+        assert_eq!(Err(RootError::NoRoot), jump.find_root(0.0, (0.0, 3.0)));
+        assert_eq!(Ok(0.5), jump.find_root(-1.5, (-3.0, 3.0)));
+
+        //Only the first of the integers at which the last part is 0 is found, as there is no change of
+        //sign between them
+        assert_eq!(Ok(vec![5, 20, 30]), integer.find_all_roots((0, 32), 3));
+
+    }
+
+}
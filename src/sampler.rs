@@ -0,0 +1,74 @@
+use crate::Number;
+use crate::EvalError;
+use crate::EvalErrorKind;
+use crate::term::Term;
+use crate::term::arenaterm::ArenaTerm;
+use crate::term::piecewiseterm;
+
+//The ends of the segments between start and end within which every piecewise term of the function
+//stays on the same part, i.e. start, then the breakpoints in order, then end
+pub(crate) fn segments<T: Number>(term: &ArenaTerm<T>, start: f64, end: f64) -> Vec<f64>
+{
+
+    let mut points = piecewiseterm::breakpoints(term.root(), start, end);
+
+    points.insert(0, start);
+    points.push(end);
+
+    return points;
+
+}
+
+//Evaluates a function at f64 values of t, for numerical methods which work in f64 regardless of
+//the number type
+pub(crate) struct Sampler<'a, T: Number>
+{
+
+    term: &'a ArenaTerm<T>
+
+}
+
+impl<'a, T: Number> Sampler<'a, T>
+{
+
+    pub(crate) fn new(term: &'a ArenaTerm<T>) -> Sampler<'a, T>
+    {
+
+        return Sampler { term };
+
+    }
+
+    pub(crate) fn evaluate(&self, x: f64) -> Result<f64, EvalError>
+    {
+
+        let t = T::from_f64(x).ok_or(EvalError::new(EvalErrorKind::FunctionConversion))?;
+
+        return self.term.try_evaluate(t)?.to_f64().ok_or(EvalError::new(EvalErrorKind::FunctionConversion));
+
+    }
+
+    //Evaluates the function just inside the end of a segment, so that a piecewise term is evaluated
+    //on the part which applies within the segment rather than the one starting at its end. The
+    //point is moved by the smallest relative step which the type can represent.
+    pub(crate) fn evaluate_inside(&self, x: f64, toward: f64) -> Result<f64, EvalError>
+    {
+
+        for step in [1e-12, 1e-9, 1e-6]
+        {
+
+            let moved = x + (toward - x).signum() * step * x.abs().max(1.0);
+
+            if (moved - x).abs() < (toward - x).abs() && T::from_f64(moved).and_then(|m| { return m.to_f64(); }) != Some(x)
+            {
+
+                return self.evaluate(moved);
+
+            }
+
+        }
+
+        return self.evaluate(x);
+
+    }
+
+}
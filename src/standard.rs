@@ -0,0 +1,135 @@
+use std::f64::consts::PI;
+
+use crate::Interval;
+use crate::Monotonicity;
+
+//An expression of x written in terms of a standard function g of x, used for the derivatives and
+//antiderivatives of the standard functions
+#[derive(Clone, Copy)]
+pub(crate) enum Expression
+{
+
+    //scale*g(x)
+    Scaled(f64, &'static str),
+    //1/(scale*g(x)^power), or 1/(scale*x^power) without g
+    Reciprocal(f64, Option<&'static str>, u32)
+
+}
+
+impl Expression
+{
+
+    pub(crate) fn evaluate(&self, x: f64) -> f64
+    {
+
+        match *self
+        {
+
+            Expression::Scaled(scale, g) => return scale * function(g)(x),
+            Expression::Reciprocal(scale, g, power) =>
+            {
+
+                let base = g.map_or(x, |g| { return function(g)(x); });
+
+                return 1.0 / (scale * base.powi(power as i32));
+
+            }
+
+        }
+
+    }
+
+}
+
+//How the values of a standard function over a range of x are bounded
+#[derive(Clone, Copy)]
+pub(crate) enum Bounds
+{
+
+    Monotonic(Monotonicity),
+    //Periodic with a period of 2pi, with a maximum at the given x and a minimum pi after it
+    Periodic(f64),
+    //Increasing between poles at the first value plus any multiple of the second
+    Poles(f64, f64)
+
+}
+
+//What is known about the function with a standard identifier, which is assumed wherever the
+//ParametrizerFunction with that identifier does not declare it itself
+#[derive(Clone, Copy)]
+pub(crate) struct Standard
+{
+
+    pub(crate) function: fn(f64) -> f64,
+    pub(crate) derivative: Expression,
+    pub(crate) antiderivative: Option<Expression>,
+    pub(crate) bounds: Option<Bounds>,
+    pub(crate) domain: Option<Interval>
+
+}
+
+//Finds the standard function with the identifier, if there is one
+pub(crate) fn find(identifier: &str) -> Option<Standard>
+{
+
+    let standard = match identifier
+    {
+
+        "sin" => Standard { function: f64::sin, derivative: Expression::Scaled(1.0, "cos"), antiderivative: Some(Expression::Scaled(-1.0, "cos")), bounds: Some(Bounds::Periodic(PI / 2.0)), domain: None },
+        "cos" => Standard { function: f64::cos, derivative: Expression::Scaled(-1.0, "sin"), antiderivative: Some(Expression::Scaled(1.0, "sin")), bounds: Some(Bounds::Periodic(0.0)), domain: None },
+        "tan" => Standard { function: f64::tan, derivative: Expression::Reciprocal(1.0, Some("cos"), 2), antiderivative: None, bounds: Some(Bounds::Poles(PI / 2.0, PI)), domain: None },
+        "exp" => Standard { function: f64::exp, derivative: Expression::Scaled(1.0, "exp"), antiderivative: Some(Expression::Scaled(1.0, "exp")), bounds: Some(Bounds::Monotonic(Monotonicity::Increasing)), domain: None },
+        //The logarithm of 0 is not finite, so its domain starts at the smallest positive value
+        "ln" => Standard { function: f64::ln, derivative: Expression::Reciprocal(1.0, None, 1), antiderivative: None, bounds: Some(Bounds::Monotonic(Monotonicity::Increasing)), domain: Some(Interval { min: f64::from_bits(1), max: f64::INFINITY }) },
+        "sqrt" => Standard { function: f64::sqrt, derivative: Expression::Reciprocal(2.0, Some("sqrt"), 1), antiderivative: None, bounds: Some(Bounds::Monotonic(Monotonicity::Increasing)), domain: Some(Interval { min: 0.0, max: f64::INFINITY }) },
+        _ => return None
+
+    };
+
+    return Some(standard);
+
+}
+
+//The standard function with the identifier, which expressions only refer to if it exists
+pub(crate) fn function(identifier: &str) -> fn(f64) -> f64
+{
+
+    return find(identifier).map(|standard| { return standard.function; }).expect("Expressions only refer to standard functions.");
+
+}
+
+#[cfg(test)]
+mod standard_tests
+{
+
+    use crate::Parametrizer;
+    use crate::ParametrizerFunction;
+
+    #[test]
+    fn test_derivatives ()
+    {
+
+        for identifier in ["sin", "cos", "tan", "exp", "ln", "sqrt"]
+        {
+
+            let function = super::function(identifier);
+            let parametrizer = Parametrizer::<f64>::new_functions(&format!("{}(t)", identifier), vec![ParametrizerFunction::new(identifier.to_string(), function)]).unwrap();
+            let derivative = parametrizer.derivative().unwrap();
+
+            for t in [0.3, 1.2, 2.5]
+            {
+
+                let (value, slope) = parametrizer.evaluate_with_derivative(t);
+
+                assert_eq!(function(t), value);
+                //The symbolic and dual derivatives agree with each other and with a central difference
+                assert!((derivative.evaluate(t) - slope).abs() < 1e-12);
+                assert!((function(t + 1e-6) - function(t - 1e-6) - 2e-6 * slope).abs() < 1e-9);
+
+            }
+
+        }
+
+    }
+
+}
@@ -1,5 +1,8 @@
+use std::fmt;
+
 use crate::Number;
 use rand::Rng;
+use rand::RngCore;
 
 pub mod constantterm;
 pub mod variableterm;
@@ -9,15 +12,29 @@ pub mod randomterm;
 pub mod piecewiseterm;
 pub mod fractionterm;
 pub mod functionterm;
+pub mod distributionterm;
+pub mod choiceterm;
+pub mod noiseterm;
+pub mod arenaterm;
 
 use super::ParametrizerError;
+use super::ParseErrorKind;
+use super::ParseLimits;
+use super::Policy;
+use super::EvalError;
 use super::ParametrizerFunction;
 
 const DYNAMIC_RANDOM_IDENTIFIER : &str = "rd(";
 const COMPUTED_RANDOM_IDENTIFIER : &str = "rc(";
+const CHOICE_IDENTIFIER : &str = "choice";
+const NOISE_IDENTIFIER : &str = "noise";
+const FBM_IDENTIFIER : &str = "fbm";
 
 const PIECEWISE_IDENTIFIER : &str = "p";
 
+//Constants which can be referred to by name
+const NAMED_CONSTANTS : [(&str, f64); 2] = [("pi", std::f64::consts::PI), ("e", std::f64::consts::E)];
+
 ///A trait used to represent a particular component of a parametrized function
 pub trait Term<T: Number>
 {
@@ -25,6 +42,237 @@ pub trait Term<T: Number>
     ///Takes in the parameter t and evaluates the output of the term
     fn evaluate(&self, t: T) -> T;
 
+    ///Evaluates the term like Term::evaluate, but returns an error instead of panicking when the
+    ///term or one of its subterms fails. User-defined terms which do not implement this are
+    ///evaluated with Term::evaluate, so may still panic.
+    fn try_evaluate(&self, t: T) -> Result<T, EvalError>
+    {
+
+        return Ok(self.evaluate(t));
+
+    }
+
+    ///Describes the structure of the term so that term trees can be walked without evaluating
+    ///them, e.g. for compilation. User-defined terms do not need to implement this, in which case
+    ///they are treated as opaque and can only be evaluated.
+    fn view(&self) -> TermView<'_, T>
+    {
+
+        return TermView::Opaque;
+
+    }
+
+    ///Writes the term in the syntax accepted by the parser, with as few parentheses as are needed
+    ///for parsing the output to give an equivalent term. The default implementation writes the
+    ///structure described by Term::view, so user-defined terms which implement neither are written
+    ///as "<opaque>", which cannot be parsed. Functions without an identifier are written as
+    ///"<function>(", and scalars other than -1 as products, which parse to an equivalent product.
+    ///Computed random values and the seeds of noise are not part of the syntax, so are parsed again
+    ///as a new random value or noise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::term::Term;
+    /// use crate::parametrizer::term::scalarterm::ScalarTerm;
+    /// use crate::parametrizer::term::sequenceterm::SequenceTerm;
+    /// use crate::parametrizer::term::sequenceterm::SequenceOperations;
+    /// use crate::parametrizer::term::variableterm::VariableTerm;
+    /// use crate::parametrizer::term::constantterm::ConstantTerm;
+    ///
+    /// struct Written<'a>(&'a dyn Term<i32>);
+    ///
+    /// impl std::fmt::Display for Written<'_>
+    /// {
+    ///
+    ///     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result
+    ///     {
+    ///
+    ///         return self.0.format(f);
+    ///
+    ///     }
+    ///
+    /// }
+    ///
+    /// let sum = SequenceTerm::new(vec![Box::new(VariableTerm::new()), Box::new(ConstantTerm::new(1))], SequenceOperations::Addition);
+    /// let negated = ScalarTerm::new(Box::new(sum), -1);
+    ///
+    /// assert_eq!("-(t + 1)", Written(&negated).to_string());
+    /// ```
+    fn format(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+
+        return super::format::write_view(self.view(), f);
+
+    }
+
+}
+
+///A borrowed description of a single term and its direct subterms, as returned by Term::view
+pub enum TermView<'a, T: Number>
+{
+
+    ///A ConstantTerm with its value
+    Constant(T),
+    ///A VariableTerm
+    Variable,
+    ///A SequenceTerm with its operation and terms
+    Sequence(sequenceterm::SequenceOperations, Vec<Subterm<'a, T>>),
+    ///A ScalarTerm with its subterm and scale
+    Scalar(Subterm<'a, T>, T),
+    ///A FractionTerm with its numerator and denominator
+    Fraction(Subterm<'a, T>, Subterm<'a, T>),
+    ///A FunctionTerm with its subterm, function, and the function's identifier if it has one
+    Function(Subterm<'a, T>, fn(f64) -> f64, Option<&'a str>),
+    ///A RandomTerm with its min and max terms
+    Random(Subterm<'a, T>, Subterm<'a, T>),
+    ///A DistributionTerm with its distribution and parameter terms
+    Distribution(distributionterm::Distribution, Vec<Subterm<'a, T>>),
+    ///A ChoiceTerm with the weight and term of each choice
+    Choice(Vec<(Subterm<'a, T>, Subterm<'a, T>)>),
+    ///A NoiseTerm with its subterm and noise
+    Noise(Subterm<'a, T>, &'a noiseterm::Noise),
+    ///A PiecewiseTerm with its terms, the times after which they apply, and its loop value
+    Piecewise(Vec<(Subterm<'a, T>, T)>, Option<T>),
+    ///A term whose structure is unknown, such as a user-defined term
+    Opaque
+
+}
+
+impl<'a, T: Number> TermView<'a, T>
+{
+
+    //The direct subterms of the term, in the order used for the paths of errors
+    pub(crate) fn subterms(&self) -> Vec<Subterm<'a, T>>
+    {
+
+        match self
+        {
+
+            TermView::Constant(_) | TermView::Variable | TermView::Opaque => return Vec::new(),
+            TermView::Sequence(_, terms) | TermView::Distribution(_, terms) => return terms.clone(),
+            TermView::Scalar(subterm, _) | TermView::Function(subterm, _, _) | TermView::Noise(subterm, _) => return vec![*subterm],
+            TermView::Fraction(first, second) | TermView::Random(first, second) => return vec![*first, *second],
+            TermView::Choice(choices) => return choices.iter().flat_map(|choice| { return [choice.0, choice.1]; }).collect(),
+            TermView::Piecewise(parts, _) => return parts.iter().map(|part| { return part.0; }).collect()
+
+        }
+
+    }
+
+}
+
+///A reference to a subterm within a TermView. Subterms are either standalone terms or nodes stored
+///inside an ArenaTerm, and can be evaluated and viewed like any other term.
+#[derive(Clone, Copy)]
+pub struct Subterm<'a, T: Number>
+{
+
+    reference: SubtermReference<'a, T>
+
+}
+
+#[derive(Clone, Copy)]
+enum SubtermReference<'a, T: Number>
+{
+
+    Term(&'a (dyn Term<T> + Send + Sync)),
+    Node(&'a arenaterm::ArenaTerm<T>, usize)
+
+}
+
+impl<'a, T: Number> Subterm<'a, T>
+{
+
+    pub(crate) fn node(arena: &'a arenaterm::ArenaTerm<T>, index: usize) -> Subterm<'a, T>
+    {
+
+        return Subterm { reference: SubtermReference::Node(arena, index) };
+
+    }
+
+    ///Views the subterm. Unlike Term::view, the resulting TermView borrows from the underlying term
+    ///rather than from this reference, so it can outlive the Subterm itself.
+    pub fn to_view(self) -> TermView<'a, T>
+    {
+
+        match self.reference
+        {
+
+            SubtermReference::Term(term) => return term.view(),
+            SubtermReference::Node(arena, index) => return arena.view_node(index)
+
+        }
+
+    }
+
+}
+
+impl<'a, T: Number> From<&'a (dyn Term<T> + Send + Sync)> for Subterm<'a, T>
+{
+
+    fn from(term: &'a (dyn Term<T> + Send + Sync)) -> Subterm<'a, T>
+    {
+
+        return Subterm { reference: SubtermReference::Term(term) };
+
+    }
+
+}
+
+impl<T: Number> Term<T> for Subterm<'_, T>
+{
+
+    ///Evaluates the referenced term or node
+    fn evaluate(&self, t: T) -> T
+    {
+
+        match self.reference
+        {
+
+            SubtermReference::Term(term) => return term.evaluate(t),
+            SubtermReference::Node(arena, index) => return arena.evaluate_node(index, t)
+
+        }
+
+    }
+
+    ///Evaluates the referenced term or node, returning an error instead of panicking
+    fn try_evaluate(&self, t: T) -> Result<T, EvalError>
+    {
+
+        match self.reference
+        {
+
+            SubtermReference::Term(term) => return term.try_evaluate(t),
+            SubtermReference::Node(arena, index) => return arena.try_evaluate_node(index, t)
+
+        }
+
+    }
+
+    ///Views the referenced term or node
+    fn view(&self) -> TermView<'_, T>
+    {
+
+        return self.to_view();
+
+    }
+
+    ///Writes the referenced term or node
+    fn format(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+
+        match self.reference
+        {
+
+            SubtermReference::Term(term) => return term.format(f),
+            SubtermReference::Node(arena, index) => return arena.format_node(index, f)
+
+        }
+
+    }
+
 }
 
 ///Entry function for parametrizing, which does some QoL formatting on the param string
@@ -45,6 +293,17 @@ pub trait Term<T: Number>
 pub fn create_parametrization<T: Number>(text: &str, functions: &[ParametrizerFunction]) -> Result<Box<dyn Term<T> + Send + Sync>, ParametrizerError>
 {
 
+    return Ok(Box::new(create_arena(text, functions, &Policy::default(), None)?));
+
+}
+
+//Performs the formatting of create_parametrization, returning the parsed ArenaTerm itself. The
+//length limit applies to the text before formatting, as formatting may lengthen it.
+pub(crate) fn create_arena<T: Number>(text: &str, functions: &[ParametrizerFunction], policy: &Policy, rng: Option<Box<dyn RngCore + Send>>) -> Result<arenaterm::ArenaTerm<T>, ParametrizerError>
+{
+
+    check_length(text, &policy.limits)?;
+
     let mut lower = text.to_lowercase();
     lower.retain(|c| { return !c.is_whitespace(); }); //Allow users to use comfortable spacing
     lower = lower.replace("\\", "/"); //Allow users to use either division symbol
@@ -52,7 +311,24 @@ pub fn create_parametrization<T: Number>(text: &str, functions: &[ParametrizerFu
 
     let param = &(lower[0..]);
 
-    return quick_parametrization(param, functions);
+    return parse_arena(param, functions, policy, rng);
+
+}
+
+fn check_length(text: &str, limits: &ParseLimits) -> Result<(), ParametrizerError>
+{
+
+    if text.len() > limits.max_length
+    {
+
+        //Avoid copying the whole of an overly long string into the error
+        let start : String = text.chars().take(32).collect();
+
+        return Err(ParametrizerError { param: format!("{}...", start), reason: "The string is longer than the maximum length.", kind: ParseErrorKind::InputTooLong });
+
+    }
+
+    return Ok(());
 
 }
 
@@ -89,22 +365,103 @@ pub fn create_parametrization<T: Number>(text: &str, functions: &[ParametrizerFu
 /// assert_eq!(8.0, p2.evaluate(30.1));
 /// ```
 pub fn quick_parametrization<T: Number>(param: &str, functions: &[ParametrizerFunction]) ->Result<Box<dyn Term<T> + Send + Sync>, ParametrizerError>
+{
+
+    return Ok(Box::new(quick_arena(param, functions, &Policy::default(), None)?));
+
+}
+
+//Performs the parsing of quick_parametrization, returning the parsed ArenaTerm itself
+pub(crate) fn quick_arena<T: Number>(param: &str, functions: &[ParametrizerFunction], policy: &Policy, rng: Option<Box<dyn RngCore + Send>>) -> Result<arenaterm::ArenaTerm<T>, ParametrizerError>
+{
+
+    check_length(param, &policy.limits)?;
+
+    return parse_arena(param, functions, policy, rng);
+
+}
+
+//Parses the string, then checks that the estimated cost and smoothness of the result are within the
+//policy. The random number generator, if given, is used for computed random values and kept for
+//dynamic ones.
+fn parse_arena<T: Number>(param: &str, functions: &[ParametrizerFunction], policy: &Policy, rng: Option<Box<dyn RngCore + Send>>) -> Result<arenaterm::ArenaTerm<T>, ParametrizerError>
+{
+
+    let mut parser = Parser::new(functions, policy);
+    parser.arena.set_rng(rng);
+
+    let arena = parse_top(param, parser)?;
+
+    if super::policy::estimate_cost(arena.root()) > policy.max_cost
+    {
+
+        return Err(ParametrizerError { param: param.to_string(), reason: "The estimated cost of evaluating the string exceeds the maximum cost.", kind: ParseErrorKind::CostExceeded });
+
+    }
+
+    if let Some(required) = policy.require_smoothness
+    {
+
+        let smooth = match super::continuity::discontinuities(arena.root(), super::continuity::POLICY_TOLERANCE)
+        {
+
+            Ok(found) => found.iter().all(|d| { return d.kind > required; }),
+            Err(_) => false
+
+        };
+
+        if !smooth
+        {
+
+            return Err(ParametrizerError { param: param.to_string(), reason: "A piecewise function is not as smooth as required by the policy.", kind: ParseErrorKind::Discontinuous });
+
+        }
+
+    }
+
+    return Ok(arena);
+
+}
+
+fn parse_top<T: Number>(param: &str, mut parser: Parser<T>) -> Result<arenaterm::ArenaTerm<T>, ParametrizerError>
 {
 
     //Check to see if the string starts with a function identifier, which is necessary because
     //identifiers may start with PIECEWISE_IDENTIFIER
-    for function in functions
+    for function in parser.functions
     {
 
         if param.starts_with(function.shorthand())
         {
 
-            return parametrize_string(param, functions);
+            parser.parse_node(param, 1)?;
+
+            return Ok(parser.arena);
 
         }
 
     }
 
+    //Likewise for built-in calls, such as "poisson(" and "perlin("
+    if starts_with_builtin(param)
+    {
+
+        parser.parse_node(param, 1)?;
+
+        return Ok(parser.arena);
+
+    }
+
+    //A leading pi followed by an operator is a constant rather than a piecewise term
+    if param == "pi" || (param.starts_with("pi") && param[2..].starts_with(|c| { return c == '+' || c == '*' || c == '/'; }))
+    {
+
+        parser.parse_node(param, 1)?;
+
+        return Ok(parser.arena);
+
+    }
+
     if param.starts_with(PIECEWISE_IDENTIFIER) //Piecewise case
     {
 
@@ -123,18 +480,20 @@ pub fn quick_parametrization<T: Number>(param: &str, functions: &[ParametrizerFu
             {
 
                 Some(i) => i,
-                None => return Err(ParametrizerError { param: simplified_string.to_string(), reason: "Unable to find closing bracket for looping piecewise term." })
+                None => return Err(ParametrizerError { param: simplified_string.to_string(), reason: "Unable to find closing bracket for looping piecewise term.", kind: ParseErrorKind::Syntax })
 
             };
 
 
+            //A negative loop value has been formatted as "+-"
             let loop_string = &(simplified_string[1..closing_index]);
+            let loop_string = if loop_string.starts_with("+") { &(loop_string[1..]) } else { loop_string };
 
             loop_value = match loop_string.parse()
             {
 
                 Ok(l) => l,
-                Err(_e) => return Err(ParametrizerError { param: loop_string.to_string(), reason: "Could not parse the loop value for looping piecewise term." })
+                Err(_e) => return Err(ParametrizerError { param: loop_string.to_string(), reason: "Could not parse the loop value for looping piecewise term.", kind: ParseErrorKind::Syntax })
 
             };
 
@@ -146,7 +505,14 @@ pub fn quick_parametrization<T: Number>(param: &str, functions: &[ParametrizerFu
 
         let parts : Vec<&str> = parts_string.split("|").collect();
 
-        let mut piecewise = if looping { piecewiseterm::PiecewiseTerm::looping(loop_value) } else { piecewiseterm::PiecewiseTerm::new() };
+        if parts.len() > parser.policy.limits.max_piecewise_parts
+        {
+
+            return Err(ParametrizerError { param: param.to_string(), reason: "The piecewise term has more than the maximum number of parts.", kind: ParseErrorKind::TooManyPiecewiseParts });
+
+        }
+
+        let mut piecewise = Vec::new();
 
         for part in parts
         {
@@ -156,30 +522,36 @@ pub fn quick_parametrization<T: Number>(param: &str, functions: &[ParametrizerFu
             if part_info.len() != 2
             {
 
-                return Err(ParametrizerError { param: part.to_string(), reason: "Unexpected number of splits for piecewise part. Each part should be separated by an = sign and contain a term and a number separated by a >" });
+                return Err(ParametrizerError { param: part.to_string(), reason: "Unexpected number of splits for piecewise part. Each part should be separated by an = sign and contain a term and a number separated by a >", kind: ParseErrorKind::Syntax });
 
             }
 
-            let term = parametrize_string(part_info[0], functions)?;
+            let term = parser.parse_node(part_info[0], 2)?;
 
-            let time = match part_info[1].parse()
+            let time_string = if part_info[1].starts_with("+") { &(part_info[1][1..]) } else { part_info[1] };
+
+            let time = match time_string.parse()
             {
 
                 Ok(t) => t,
-                Err(_e) => return Err(ParametrizerError { param: part.to_string(), reason: "Could not parse the time value for piecewise part."})
+                Err(_e) => return Err(ParametrizerError { param: part.to_string(), reason: "Could not parse the time value for piecewise part.", kind: ParseErrorKind::Syntax })
 
             };
 
-            piecewise.add_part(term, time);
+            piecewise.push((term, time));
 
         }
 
-        return Ok(Box::new(piecewise));
+        parser.add(param, arenaterm::ArenaNode::Piecewise(piecewise, if looping { Some(loop_value) } else { None }))?;
+
+        return Ok(parser.arena);
 
     }
 
     //Not piecewise, recurse normally
-    return parametrize_string(param, functions);
+    parser.parse_node(param, 1)?;
+
+    return Ok(parser.arena);
 
 }
 
@@ -265,232 +637,681 @@ pub fn quick_parametrization<T: Number>(param: &str, functions: &[ParametrizerFu
 pub fn parametrize_string<T: Number>(param: &str, functions: &[ParametrizerFunction]) -> Result<Box<dyn Term<T> + Send + Sync>, ParametrizerError>
 {
 
-    //Terminal case: check if the passed in string is simply "t", in which case we want a variable
-    //term to use in our calculations
-    if param.eq("t")
-    {
-
-        return Ok(Box::new(variableterm::VariableTerm::new()));
+    let policy = Policy::default();
+    let mut parser = Parser::new(functions, &policy);
 
-    }
+    parser.parse_node(param, 1)?;
 
-    //Terminal case: check if the passed in string can be parsed into a number of the desired type,
-    //in which case we want a constant term returning that number
-    let c = param.parse();
-    match c
-    {
+    return Ok(Box::new(parser.arena));
 
-        Ok(c) => return Ok(Box::new(constantterm::ConstantTerm::new(c))),
-        Err(_e) => ()
+}
 
-    };
+//The state of a single parse, which builds the arena while enforcing the Policy and its ParseLimits
+struct Parser<'a, T: Number>
+{
 
-    //Simplification case: If the entire string is in parentheses, slice them off and recurse
-    let length = param.len();
-    if param.starts_with("(") && param.ends_with(")")
-    {
+    functions: &'a [ParametrizerFunction],
+    policy: &'a Policy,
+    arena: arenaterm::ArenaTerm<T>,
+    nodes: usize,
+    function_calls: usize
 
-        return parametrize_string::<T>(&(param[1..length - 1]), functions);
+}
 
-    }
+impl<'a, T: Number> Parser<'a, T>
+{
 
-    //Simplification case: If the first character is a +, then remove it and recurse. Happens
-    //because a leading - was replaced by +- in create_parametrization
-    if param.starts_with("+")
+    fn new(functions: &'a [ParametrizerFunction], policy: &'a Policy) -> Parser<'a, T>
     {
 
-        return parametrize_string::<T>(&(param[1..]), functions);
+        return Parser { functions, policy, arena: arenaterm::ArenaTerm::new(), nodes: 0, function_calls: 0 };
 
     }
 
-    //Recursive case: If there is an addition symbol, we may need to split. PROCESSED before
-    //multiplication so that multiplication is PERFORMED first
-    if param.contains('+')
+    //Adds a node to the arena, failing if there are already as many nodes as the limit allows
+    fn add(&mut self, param: &str, node: arenaterm::ArenaNode<T>) -> Result<usize, ParametrizerError>
     {
 
-        let terms = respectful_symbol_split(param, '+', '(', ')')?;
-
-        if terms.len() > 1 //If we actually split, then create a SequenceTerm adding up the values. If there is no split, continue to a different case
+        if self.nodes >= self.policy.limits.max_nodes
         {
 
-            let mut sum_terms = Vec::new();
-
-            for term in terms
-            {
-
-                let new_term = parametrize_string(term, functions)?;
-
-                sum_terms.push(new_term);
+            return Err(ParametrizerError { param: param.to_string(), reason: "The string contains more than the maximum number of terms.", kind: ParseErrorKind::TooManyNodes });
 
-            }
+        }
 
-            return Ok(Box::new(sequenceterm::SequenceTerm::new(sum_terms, sequenceterm::SequenceOperations::Addition)));
+        self.nodes += 1;
 
-        }
+        return Ok(self.arena.add(node));
 
     }
 
-    //Recursive case: If there is a multiplication symbol, we may need to split. PROCESSED after
-    //addition so that multiplication is PERFORMED first
-    if param.contains('*')
+    //Counts a call of a function, random term, distribution, choice, or noise, failing if there are
+    //more than the limit allows
+    fn count_call(&mut self, param: &str) -> Result<(), ParametrizerError>
     {
 
-        let terms = respectful_symbol_split(param, '*', '(', ')')?;
+        self.function_calls += 1;
 
-        if terms.len() > 1 //If we actually split, then create a SequenceTerm multiplying the values. If there is no split, continue to a different case
+        if self.function_calls > self.policy.limits.max_function_calls
         {
 
-            let mut product_terms = Vec::new();
+            return Err(ParametrizerError { param: param.to_string(), reason: "The string contains more than the maximum number of function calls.", kind: ParseErrorKind::TooManyFunctionCalls });
 
-            for term in terms
-            {
+        }
+
+        return Ok(());
 
-                let new_term = parametrize_string(term, functions)?;
+    }
 
-                product_terms.push(new_term);
+    fn check_random(&self, param: &str) -> Result<(), ParametrizerError>
+    {
 
-            }
+        if !self.policy.allow_random
+        {
 
-            return Ok(Box::new(sequenceterm::SequenceTerm::new(product_terms, sequenceterm::SequenceOperations::Multiplication)));
+            return Err(ParametrizerError { param: param.to_string(), reason: "Random values are not allowed by the policy.", kind: ParseErrorKind::ForbiddenFeature });
 
         }
 
+        return Ok(());
+
     }
 
-    //Recursive case: Check for a division sign and use the splitting algorithm. If the split
-    //returns more than two terms, then we throw an error because division is not associative and
-    //we won't know how to proceed
-    if param.contains('/') 
+    //Parses a term which must not depend on t or on dynamic random values and evaluates it, then
+    //discards its nodes, which no longer count against the limit on nodes
+    fn fold_constant(&mut self, param: &str, term: &str, depth: usize) -> Result<T, ParametrizerError>
     {
 
-        let terms = respectful_symbol_split(param, '/', '(', ')')?;
+        let (length, nodes) = (self.arena.node_count(), self.nodes);
+        let index = self.parse_node(term, depth + 1)?;
 
-        if terms.len() > 1
-        {
+        let constant = is_constant(Subterm::node(&self.arena, index));
+        let value = self.arena.try_evaluate_node(index, T::zero());
 
-            if terms.len() > 2
-            {
+        self.arena.truncate(length);
+        self.nodes = nodes;
 
-                return Err(ParametrizerError { param: param.to_string(), reason: "More than one division symbol in a term." });
+        if !constant
+        {
 
-            }
+            return Err(ParametrizerError { param: param.to_string(), reason: "The bounds for computed random generation must not depend on t or on dynamic random values.", kind: ParseErrorKind::Syntax });
 
-            let numerator = parametrize_string(terms[0], functions)?;
-            let denominator = parametrize_string(terms[1], functions)?;
+        }
 
-            return Ok(Box::new(fractionterm::FractionTerm::new(numerator, denominator)));
+        return match value
+        {
 
-        }
+            Ok(v) => Ok(v),
+            Err(_e) => Err(ParametrizerError { param: param.to_string(), reason: "Could not evaluate the bounds for computed random generation.", kind: ParseErrorKind::Syntax })
+
+        };
 
     }
 
-    //Recursive case: Check for a negative sign leading the term. As we have remove the top level
-    //of binary operations, negate the remaining term
-    if param.starts_with("-")
+    //Parses the term of a noise call and adds the noise created from a seed drawn from the random
+    //number generator
+    fn parse_noise<F: FnOnce(u64) -> noiseterm::Noise>(&mut self, param: &str, argument: &str, depth: usize, noise: F) -> Result<usize, ParametrizerError>
     {
 
-        let term = parametrize_string(&(param[1..]), functions)?;
+        self.check_random(param)?;
+        self.count_call(param)?;
 
-        return Ok(Box::new(scalarterm::ScalarTerm::new(term, T::zero() - T::one())));
+        let term = self.parse_node(argument, depth + 1)?;
+        let seed = self.arena.with_rng(|rng| { return rng.next_u64(); });
+
+        return self.add(param, arenaterm::ArenaNode::Noise(term, Box::new(noise(seed))));
 
     }
 
-    //Recursive case: Check for a leading "rd", which designates a dynamic random value which
-    //changes each time evaluate is called. It is bounded between the first and second term.
-    if param.starts_with(DYNAMIC_RANDOM_IDENTIFIER) && param.ends_with(")")
+    //Recursively parses the param string into nodes of the arena, adding each node after its children
+    //and returning the index of the node representing the whole string. The depth is the number of
+    //nested calls, starting from 1.
+    fn parse_node(&mut self, param: &str, depth: usize) -> Result<usize, ParametrizerError>
     {
 
-        let simplified_param = &(param[DYNAMIC_RANDOM_IDENTIFIER.len()..param.len() - 1]);
-        let splits : Vec<&str> = simplified_param.split("<").collect();
-
-        if splits.len() != 2
+        if depth > self.policy.limits.max_depth
         {
 
-            return Err(ParametrizerError { param: param.to_string(), reason: "Random parametrization did not split into exactly two terms." });
+            return Err(ParametrizerError { param: param.to_string(), reason: "The string is nested deeper than the maximum depth.", kind: ParseErrorKind::NestingTooDeep });
 
         }
 
-        let min = parametrize_string(splits[0], functions)?;
-        let max = parametrize_string(splits[1], functions)?;
+        //Terminal case: check if the passed in string is simply "t", in which case we want a variable
+        //term to use in our calculations
+        if param.eq("t")
+        {
 
-        return Ok(Box::new(randomterm::RandomTerm::new(min, max)));
+            return self.add(param, arenaterm::ArenaNode::Variable);
 
-    }
+        }
 
-    //Recursive case:: Check for a leading predefined function shorthand and create a function term
-    //using it and the interior term
-    for function in functions
-    {
+        //Terminal case: check if the passed in string can be parsed into a number of the desired type,
+        //in which case we want a constant term returning that number
+        let c = param.parse();
+        match c
+        {
+
+            Ok(c) => return self.add(param, arenaterm::ArenaNode::Constant(c)),
+            Err(_e) => ()
+
+        };
 
-        let shorthand = function.shorthand();
-        if param.starts_with(shorthand) && param.ends_with(")")
+        //Terminal case: check if the passed in string is a named constant, which is converted from
+        //f64 like the output of a function, so is truncated for integer types
+        for (name, value) in NAMED_CONSTANTS.iter()
         {
 
-            let simplified_param = &(param[shorthand.len()..param.len() - 1]);
+            if param == *name
+            {
+
+                return match T::from_f64(*value)
+                {
 
-            let term = parametrize_string(simplified_param, functions)?;
+                    Some(c) => self.add(param, arenaterm::ArenaNode::Constant(c)),
+                    None => Err(ParametrizerError { param: param.to_string(), reason: "Could not convert the named constant to the generic type T from f64.", kind: ParseErrorKind::Syntax })
 
-            return Ok(Box::new(functionterm::FunctionTerm::new(term, function.function())));
+                };
+
+            }
 
         }
 
-    }
+        //Simplification case: If the entire string is in parentheses, slice them off and recurse
+        let length = param.len();
+        if is_wrapped(param)
+        {
 
-    //Terminal case: Check for a leading "rc", which designates a computed random value which is
-    //calculated at parametrize time and never changes.
-    if param.starts_with(COMPUTED_RANDOM_IDENTIFIER) && param.ends_with(")")
-    {
+            return self.parse_node(&(param[1..length - 1]), depth + 1);
 
-        let simplified_param = &(param[COMPUTED_RANDOM_IDENTIFIER.len()..param.len() - 1]);
-        let splits : Vec<&str> = simplified_param.split("<").collect();
+        }
 
-        if splits.len() != 2
+        //Simplification case: If the first character is a +, then remove it and recurse. Happens
+        //because a leading - was replaced by +- in create_parametrization
+        if param.starts_with("+")
         {
 
-            return Err(ParametrizerError { param: param.to_string(), reason: "Random parametrization did not split into exactly two terms." });
+            return self.parse_node(&(param[1..]), depth + 1);
 
         }
 
-        let min = splits[0].parse();
-        let max = splits[1].parse();
-
-        let min = match min
+        //Recursive case: If there is an addition symbol, we may need to split. PROCESSED before
+        //multiplication so that multiplication is PERFORMED first
+        if param.contains('+')
         {
 
-            Ok(m) => m,
-            Err(_e) => return Err(ParametrizerError { param: param.to_string(), reason: "Could not parse the minimum value as a number for computed random generation."})
+            let terms = respectful_symbol_split(param, '+', '(', ')')?;
 
-        };
+            if terms.len() > 1 //If we actually split, then create a SequenceTerm adding up the values. If there is no split, continue to a different case
+            {
 
-        let max = match max
-        {
+                let mut sum_terms = Vec::new();
 
-            Ok(m) => m,
-            Err(_e) => return Err(ParametrizerError { param: param.to_string(), reason: "Could not parse the maximum value as a umber for computed random generation."})
+                for term in terms
+                {
 
-        };
+                    let new_term = self.parse_node(term, depth + 1)?;
+
+                    sum_terms.push(new_term);
+
+                }
+
+                return self.add(param, arenaterm::ArenaNode::Sequence(sequenceterm::SequenceOperations::Addition, sum_terms));
+
+            }
+
+        }
 
-        let constant = T::from_f64(rand::thread_rng().gen_range(min..max));
-        let constant = match constant
+        //Recursive case: If there is a multiplication symbol, we may need to split. PROCESSED after
+        //addition so that multiplication is PERFORMED first
+        if param.contains('*')
         {
 
-            Some(c) => c,
-            None => return Err(ParametrizerError {param: param.to_string(), reason: "Could not convert to the generic type T from f64 for computed random generation."})
+            let terms = respectful_symbol_split(param, '*', '(', ')')?;
 
-        };
+            if terms.len() > 1 //If we actually split, then create a SequenceTerm multiplying the values. If there is no split, continue to a different case
+            {
 
-        return Ok(Box::new(constantterm::ConstantTerm::new(constant)));
+                let mut product_terms = Vec::new();
 
-    }
+                for term in terms
+                {
 
-    return Err(ParametrizerError { param: param.to_string(), reason: "Did not match any cases. Do not forget to write multiplication explicitly, i.e. 'n*t' as opposed to 'nt'." });
+                    let new_term = self.parse_node(term, depth + 1)?;
 
-}
+                    product_terms.push(new_term);
 
-//Used to parse parentheses, ignoring everything between an instance of left and an instance of
-//right to be handled at a later step of the recursion.
-fn respectful_symbol_split<'a>(param: &'a str, splitter: char, left: char, right: char) -> Result<Vec<&'a str>, ParametrizerError>
-{
+                }
+
+                return self.add(param, arenaterm::ArenaNode::Sequence(sequenceterm::SequenceOperations::Multiplication, product_terms));
+
+            }
+
+        }
+
+        //Recursive case: Check for a division sign and use the splitting algorithm. If the split
+        //returns more than two terms, then we throw an error because division is not associative and
+        //we won't know how to proceed
+        if param.contains('/') 
+        {
+
+            let terms = respectful_symbol_split(param, '/', '(', ')')?;
+
+            if terms.len() > 1
+            {
+
+                if terms.len() > 2
+                {
+
+                    return Err(ParametrizerError { param: param.to_string(), reason: "More than one division symbol in a term.", kind: ParseErrorKind::Syntax });
+
+                }
+
+                let numerator = self.parse_node(terms[0], depth + 1)?;
+                let denominator = self.parse_node(terms[1], depth + 1)?;
+
+                return self.add(param, arenaterm::ArenaNode::Fraction(numerator, denominator));
+
+            }
+
+        }
+
+        //Recursive case: Check for a negative sign leading the term. As we have remove the top level
+        //of binary operations, negate the remaining term
+        if param.starts_with("-")
+        {
+
+            if super::overflow::is_unsigned::<T>()
+            {
+
+                return Err(ParametrizerError { param: param.to_string(), reason: "Cannot negate a term of an unsigned type.", kind: ParseErrorKind::Syntax });
+
+            }
+
+            let term = self.parse_node(&(param[1..]), depth + 1)?;
+
+            return self.add(param, arenaterm::ArenaNode::Scalar(term, T::zero() - T::one()));
+
+        }
+
+        //Recursive case: Check for a leading "rd", which designates a dynamic random value which
+        //changes each time evaluate is called. It is bounded between the first and second term.
+        if param.starts_with(DYNAMIC_RANDOM_IDENTIFIER) && param.ends_with(")")
+        {
+
+            self.check_random(param)?;
+            self.count_call(param)?;
+
+            let simplified_param = &(param[DYNAMIC_RANDOM_IDENTIFIER.len()..param.len() - 1]);
+            let splits = respectful_symbol_split(simplified_param, '<', '(', ')')?;
+
+            if splits.len() != 2
+            {
+
+                return Err(ParametrizerError { param: param.to_string(), reason: "Random parametrization did not split into exactly two terms.", kind: ParseErrorKind::Syntax });
+
+            }
+
+            let min = self.parse_node(splits[0], depth + 1)?;
+            let max = self.parse_node(splits[1], depth + 1)?;
+
+            return self.add(param, arenaterm::ArenaNode::Random(min, max));
+
+        }
+
+        //Recursive case:: Check for a leading predefined function shorthand and create a function term
+        //using it and the interior term
+        for function in self.functions
+        {
+
+            let shorthand = function.shorthand();
+            if param.starts_with(shorthand) && param.ends_with(")")
+            {
+
+                let simplified_param = &(param[shorthand.len()..param.len() - 1]);
+
+                if !self.policy.allows_function(function.identifier())
+                {
+
+                    return Err(ParametrizerError { param: param.to_string(), reason: "The function is not allowed by the policy.", kind: ParseErrorKind::ForbiddenFeature });
+
+                }
+
+                self.count_call(param)?;
+
+                let term = self.parse_node(simplified_param, depth + 1)?;
+
+                if let Some(derivative) = function.derivative()
+                {
+
+                    self.arena.add_derivative(function.identifier(), derivative);
+
+                }
+
+                if let Some(monotonicity) = function.monotonicity()
+                {
+
+                    self.arena.add_monotonicity(function.identifier(), monotonicity);
+
+                }
+
+                if let Some(domain) = function.domain()
+                {
+
+                    self.arena.add_domain(function.identifier(), domain);
+
+                }
+
+                if let Some(latex) = function.latex()
+                {
+
+                    self.arena.add_latex(function.identifier(), latex);
+
+                }
+
+                return self.add(param, arenaterm::ArenaNode::Function(term, function.function(), Some(function.identifier().to_string())));
+
+            }
+
+        }
+
+        //Recursive case: Check for a random distribution, whose comma separated parameters are terms
+        //which are evaluated each time a value is drawn
+        for distribution in distributionterm::Distribution::ALL.iter()
+        {
+
+            if let Some(arguments) = call_arguments(param, distribution.identifier())
+            {
+
+                self.check_random(param)?;
+                self.count_call(param)?;
+
+                let arguments = respectful_symbol_split(arguments, ',', '(', ')')?;
+
+                if arguments.len() != distribution.parameters()
+                {
+
+                    return Err(ParametrizerError { param: param.to_string(), reason: "Random distribution was given the wrong number of parameters.", kind: ParseErrorKind::Syntax });
+
+                }
+
+                let mut parameters = Vec::new();
+
+                for argument in arguments
+                {
+
+                    parameters.push(self.parse_node(argument, depth + 1)?);
+
+                }
+
+                return self.add(param, arenaterm::ArenaNode::Distribution(*distribution, parameters));
+
+            }
+
+        }
+
+        //Recursive case: Check for a random choice, which is a comma separated list of choices, each
+        //of which is a weight term and a choice term separated by a colon
+        if let Some(arguments) = call_arguments(param, CHOICE_IDENTIFIER)
+        {
+
+            self.check_random(param)?;
+            self.count_call(param)?;
+
+            let mut choices = Vec::new();
+
+            for argument in respectful_symbol_split(arguments, ',', '(', ')')?
+            {
+
+                let choice = respectful_symbol_split(argument, ':', '(', ')')?;
+
+                if choice.len() != 2
+                {
+
+                    return Err(ParametrizerError { param: argument.to_string(), reason: "Random choice did not split into a weight and a term.", kind: ParseErrorKind::Syntax });
+
+                }
+
+                let weight = self.parse_node(choice[0], depth + 1)?;
+                let term = self.parse_node(choice[1], depth + 1)?;
+
+                choices.push((weight, term));
+
+            }
+
+            return self.add(param, arenaterm::ArenaNode::Choice(choices));
+
+        }
+
+        //Recursive case: Check for coherent noise of a term. The noise is seeded from the random number
+        //generator when parsing, like a computed random value.
+        for kind in noiseterm::NoiseKind::ALL.iter()
+        {
+
+            if let Some(argument) = call_arguments(param, kind.identifier())
+            {
+
+                return self.parse_noise(param, argument, depth, |seed| { return noiseterm::Noise::new(*kind, seed); });
+
+            }
+
+        }
+
+        if let Some(argument) = call_arguments(param, NOISE_IDENTIFIER)
+        {
+
+            return self.parse_noise(param, argument, depth, |seed| { return noiseterm::Noise::new(noiseterm::NoiseKind::Perlin, seed); });
+
+        }
+
+        //Recursive case: Check for fractal Brownian motion of Perlin noise, whose arguments are the
+        //term, the number of octaves, and optionally the lacunarity and gain
+        if let Some(arguments) = call_arguments(param, FBM_IDENTIFIER)
+        {
+
+            let arguments = respectful_symbol_split(arguments, ',', '(', ')')?;
+
+            if arguments.len() != 2 && arguments.len() != 4
+            {
+
+                return Err(ParametrizerError { param: param.to_string(), reason: "Fractal noise takes a term and the number of octaves, optionally followed by the lacunarity and gain.", kind: ParseErrorKind::Syntax });
+
+            }
+
+            let octaves = match arguments[1].parse()
+            {
+
+                Ok(o) if o > 0 => o,
+                _ => return Err(ParametrizerError { param: param.to_string(), reason: "Could not parse the number of octaves as a positive integer for fractal noise.", kind: ParseErrorKind::Syntax })
+
+            };
+
+            let (lacunarity, gain) = if arguments.len() == 4
+            {
+
+                match (arguments[2].parse::<f64>(), arguments[3].parse::<f64>())
+                {
+
+                    (Ok(l), Ok(g)) if l.is_finite() && g.is_finite() => (l, g),
+                    _ => return Err(ParametrizerError { param: param.to_string(), reason: "Could not parse the lacunarity and gain as finite numbers for fractal noise.", kind: ParseErrorKind::Syntax })
+
+                }
+
+            }
+            else
+            {
+
+                (2.0, 0.5)
+
+            };
+
+            return self.parse_noise(param, arguments[0], depth, |seed| { return noiseterm::Noise::fractal(noiseterm::NoiseKind::Perlin, seed, octaves, lacunarity, gain); });
+
+        }
+
+        //Recursive case: Check for a leading "rc", which designates a computed random value which is
+        //calculated at parametrize time and never changes. Its bounds may be any terms which do not
+        //depend on t, which are folded into values. Integer types draw from the inclusive range
+        //between the bounds, while other types draw from the half-open range.
+        if param.starts_with(COMPUTED_RANDOM_IDENTIFIER) && param.ends_with(")")
+        {
+
+            self.check_random(param)?;
+            self.count_call(param)?;
+
+            let simplified_param = &(param[COMPUTED_RANDOM_IDENTIFIER.len()..param.len() - 1]);
+            let splits = respectful_symbol_split(simplified_param, '<', '(', ')')?;
+
+            if splits.len() != 2
+            {
+
+                return Err(ParametrizerError { param: param.to_string(), reason: "Random parametrization did not split into exactly two terms.", kind: ParseErrorKind::Syntax });
+
+            }
+
+            let min = self.fold_constant(param, splits[0], depth)?;
+            let max = self.fold_constant(param, splits[1], depth)?;
+
+            let constant = if super::overflow::is_integer::<T>()
+            {
+
+                match (min.to_i128(), max.to_i128())
+                {
+
+                    (Some(min), Some(max)) if min <= max => T::from_i128(self.arena.with_rng(|rng| { return rng.gen_range(min..=max); })),
+                    _ => None
+
+                }
+
+            }
+            else
+            {
+
+                match (min.to_f64(), max.to_f64())
+                {
+
+                    (Some(min), Some(max)) if min < max && (max - min).is_finite() => T::from_f64(self.arena.with_rng(|rng| { return rng.gen_range(min..max); })),
+                    _ => None
+
+                }
+
+            };
+
+            let constant = match constant
+            {
+
+                Some(c) => c,
+                None => return Err(ParametrizerError { param: param.to_string(), reason: "Could not generate a value between the bounds for computed random generation.", kind: ParseErrorKind::Syntax })
+
+            };
+
+            return self.add(param, arenaterm::ArenaNode::Constant(constant));
+
+        }
+
+        return Err(ParametrizerError { param: param.to_string(), reason: "Did not match any cases. Do not forget to write multiplication explicitly, i.e. 'n*t' as opposed to 'nt'.", kind: ParseErrorKind::Syntax });
+
+    }
+
+}
+
+//Whether the term always evaluates to the same value, i.e. does not depend on t or on dynamic random
+//values. Opaque terms are assumed to be variable.
+pub(crate) fn is_constant<T: Number>(term: Subterm<'_, T>) -> bool
+{
+
+    match term.to_view()
+    {
+
+        TermView::Constant(_) => return true,
+        TermView::Variable | TermView::Random(_, _) | TermView::Distribution(_, _) | TermView::Choice(_) | TermView::Piecewise(_, _) | TermView::Opaque => return false,
+        TermView::Sequence(_, terms) => return terms.into_iter().all(is_constant),
+        TermView::Scalar(subterm, _) | TermView::Function(subterm, _, _) | TermView::Noise(subterm, _) => return is_constant(subterm),
+        TermView::Fraction(numerator, denominator) => return is_constant(numerator) && is_constant(denominator)
+
+    }
+
+}
+
+//Whether the whole string is enclosed by a single pair of parentheses, rather than starting and
+//ending with different pairs as in "(t+1)*(t+2)"
+fn is_wrapped(param: &str) -> bool
+{
+
+    if !(param.starts_with("(") && param.ends_with(")"))
+    {
+
+        return false;
+
+    }
+
+    let mut balance = 0;
+
+    for (i, c) in param.char_indices()
+    {
+
+        match c
+        {
+
+            '(' => balance += 1,
+            ')' => balance -= 1,
+            _ => ()
+
+        }
+
+        if balance == 0
+        {
+
+            return i == param.len() - 1;
+
+        }
+
+    }
+
+    return false;
+
+}
+
+//Whether the string starts with a call of the given identifier, i.e. the identifier followed by an
+//opening parenthesis
+fn starts_with_call(param: &str, identifier: &str) -> bool
+{
+
+    return param.starts_with(identifier) && param[identifier.len()..].starts_with('(');
+
+}
+
+//Whether the string starts with a call of one of the built-in random distributions, choices, or
+//noise functions
+fn starts_with_builtin(param: &str) -> bool
+{
+
+    let distribution = distributionterm::Distribution::ALL.iter().any(|d| { return starts_with_call(param, d.identifier()); });
+    let noise = noiseterm::NoiseKind::ALL.iter().any(|n| { return starts_with_call(param, n.identifier()); });
+
+    return distribution || noise || [CHOICE_IDENTIFIER, NOISE_IDENTIFIER, FBM_IDENTIFIER].iter().any(|i| { return starts_with_call(param, i); });
+
+}
+
+//If the whole string is a single call of the given identifier, returns the string between its
+//parentheses. Calls followed by further terms, such as "f(t)*f(t)", are split before reaching here.
+fn call_arguments<'p>(param: &'p str, identifier: &str) -> Option<&'p str>
+{
+
+    if starts_with_call(param, identifier) && param.ends_with(')')
+    {
+
+        return Some(&(param[identifier.len() + 1..param.len() - 1]));
+
+    }
+
+    return None;
+
+}
+
+//Used to parse parentheses, ignoring everything between an instance of left and an instance of
+//right to be handled at a later step of the recursion.
+fn respectful_symbol_split<'a>(param: &'a str, splitter: char, left: char, right: char) -> Result<Vec<&'a str>, ParametrizerError>
+{
 
     //Counter used to keep track of "parentheses": We add one when we see left, and subtract one
     //when we see right. We only split if we encounter the splitting symbol when we are outside of
@@ -523,7 +1344,7 @@ fn respectful_symbol_split<'a>(param: &'a str, splitter: char, left: char, right
             if balance < 0 //More right than left at some point, which is a problem
             {
 
-                return Err(ParametrizerError { param: param.to_string(), reason: "Malformed split, right exceeded left." });
+                return Err(ParametrizerError { param: param.to_string(), reason: "Malformed split, right exceeded left.", kind: ParseErrorKind::Syntax });
 
             }
 
@@ -548,7 +1369,7 @@ fn respectful_symbol_split<'a>(param: &'a str, splitter: char, left: char, right
     if balance > 0 //There were more left than right, which is a problem
     {
 
-        return Err(ParametrizerError { param: param.to_string(), reason: "Malformed split, left exceeded right." });
+        return Err(ParametrizerError { param: param.to_string(), reason: "Malformed split, left exceeded right.", kind: ParseErrorKind::Syntax });
 
     }
     else
@@ -652,4 +1473,242 @@ mod term_tests
 
     }
 
+    #[test]
+    fn test_checked_paths ()
+    {
+
+        use super::super::EvalErrorKind;
+
+        let parsed = create_parametrization::<i32>("2+rd(1<t)*(5/(t-3))", &[]).unwrap();
+
+        let fraction = fractionterm::FractionTerm::new(Box::new(constantterm::ConstantTerm::new(5)), Box::new(sequenceterm::SequenceTerm::new(vec![
+
+            Box::new(variableterm::VariableTerm::new()),
+            Box::new(constantterm::ConstantTerm::new(-3))
+
+        ], sequenceterm::SequenceOperations::Addition)));
+        let random = randomterm::RandomTerm::new(Box::new(constantterm::ConstantTerm::new(1)), Box::new(variableterm::VariableTerm::new()));
+        let product = sequenceterm::SequenceTerm::new(vec![ Box::new(random), Box::new(fraction) ], sequenceterm::SequenceOperations::Multiplication);
+        let composed = sequenceterm::SequenceTerm::new(vec![ Box::new(constantterm::ConstantTerm::new(2)), Box::new(product) ], sequenceterm::SequenceOperations::Addition);
+
+        for term in [&*parsed as &dyn Term<i32>, &composed]
+        {
+
+            let random_error = term.try_evaluate(1).unwrap_err();
+            let fraction_error = term.try_evaluate(3).unwrap_err();
+
+            assert_eq!(EvalErrorKind::InvalidRandomRange, random_error.kind());
+            assert_eq!(&[1, 0], random_error.path());
+            assert_eq!(EvalErrorKind::DivisionByZero, fraction_error.kind());
+            assert_eq!(&[1, 1], fraction_error.path());
+            assert!(term.try_evaluate(5).is_ok());
+
+        }
+
+    }
+
+    #[test]
+    fn test_limits ()
+    {
+
+        let policy = Policy { limits: ParseLimits { max_depth: 64, ..ParseLimits::default() }, ..Policy::default() };
+
+        //Deep enough to overflow the stack without a depth limit
+        let nested = format!("{}t{}", "(".repeat(1000000), ")".repeat(1000000));
+        let negated = "-".repeat(1000000) + "t";
+
+        match create_arena::<f64>(&nested, &[], &policy, None)
+        {
+
+            Ok(_) => panic!("Expected nesting too deep error."),
+            Err(e) => assert_eq!(e.kind, ParseErrorKind::NestingTooDeep)
+
+        }
+
+        match quick_arena::<i64>(&negated, &[], &policy, None)
+        {
+
+            Ok(_) => panic!("Expected nesting too deep error."),
+            Err(e) => assert_eq!(e.kind, ParseErrorKind::NestingTooDeep)
+
+        }
+
+        match create_arena::<f64>("2t", &[], &policy, None)
+        {
+
+            Ok(_) => panic!("Expected syntax error."),
+            Err(e) => assert_eq!(e.kind, ParseErrorKind::Syntax)
+
+        }
+
+        //Every kind of call counts against the limit on function calls
+        let calls = Policy { limits: ParseLimits { max_function_calls: 1, ..ParseLimits::default() }, ..Policy::default() };
+
+        for (single, text) in [("rd(0<1)", "rd(0<1)*rc(0<1)"), ("normal(t, 1)", "normal(t, 1) + choice(1: t)"), ("perlin(t)", "perlin(t) - fbm(t, 2)")].iter()
+        {
+
+            assert!(create_arena::<f64>(single, &[], &calls, None).is_ok());
+
+            match create_arena::<f64>(text, &[], &calls, None)
+            {
+
+                Ok(_) => panic!("Expected too many function calls error for {}.", text),
+                Err(e) => assert_eq!(e.kind, ParseErrorKind::TooManyFunctionCalls)
+
+            }
+
+        }
+
+        //The nodes of folded bounds are discarded, leaving only the constant drawn between them
+        let nodes = Policy { limits: ParseLimits { max_nodes: 4, ..ParseLimits::default() }, ..Policy::default() };
+
+        assert!(create_arena::<f64>("rc(1+2+3 < 4*5*6) + t", &[], &nodes, None).is_ok());
+
+    }
+
+    #[test]
+    fn test_distributions ()
+    {
+
+        use super::super::EvalErrorKind;
+
+        //Identifiers starting with the piecewise identifier are not piecewise terms
+        let poisson = create_parametrization::<i32>("poisson(3)", &[]).expect("Failed to parse a top level distribution.");
+
+        assert!(poisson.evaluate(0) >= 0);
+
+        match create_parametrization::<f64>("normal(0, 1, 2)", &[])
+        {
+
+            Ok(_) => panic!("Expected wrong number of parameters error."),
+            Err(e) => assert_eq!(e.reason, "Random distribution was given the wrong number of parameters.")
+
+        }
+
+        match create_parametrization::<f64>("choice(1: t, 2)", &[])
+        {
+
+            Ok(_) => panic!("Expected missing weight error."),
+            Err(e) => assert_eq!(e.reason, "Random choice did not split into a weight and a term.")
+
+        }
+
+        let normal = create_parametrization::<f64>("2+normal(0, t-1)", &[]).unwrap();
+        let choice = create_parametrization::<f64>("choice(0: 5, t: 1/(t-2))", &[]).unwrap();
+
+        let error = normal.try_evaluate(0.0).unwrap_err();
+
+        assert_eq!(EvalErrorKind::InvalidDistribution, error.kind());
+        assert_eq!(&[1], error.path());
+
+        let error = choice.try_evaluate(2.0).unwrap_err();
+
+        assert_eq!(EvalErrorKind::DivisionByZero, error.kind());
+        assert_eq!(&[3], error.path());
+
+        let error = choice.try_evaluate(-1.0).unwrap_err();
+
+        assert_eq!(EvalErrorKind::InvalidDistribution, error.kind());
+        assert!(error.path().is_empty());
+
+    }
+
+    #[test]
+    fn test_computed_bounds ()
+    {
+
+        let angle = create_parametrization::<f64>("rc(0 < 2*pi)", &[]).unwrap();
+        let signed = create_parametrization::<f64>("rc(-1 < 1) + t", &[]).unwrap();
+        let nested = create_parametrization::<f64>("rc(rc(0<1) < (2+3)/5 + 1)", &[]).unwrap();
+
+        assert!((0.0..2.0 * std::f64::consts::PI).contains(&angle.evaluate(0.0)));
+        assert!((9.0..11.0).contains(&signed.evaluate(10.0)));
+        assert!((0.0..2.0).contains(&nested.evaluate(0.0)));
+        assert_eq!(signed.evaluate(0.0), signed.evaluate(0.0));
+
+        //Integer ranges include both bounds
+        let single = create_parametrization::<i32>("rc(3<3)", &[]).unwrap();
+        let die = create_parametrization::<u8>("rc(1<6)", &[]).unwrap();
+
+        assert_eq!(3, single.evaluate(0));
+        assert!((1..=6).contains(&die.evaluate(0)));
+
+        match create_parametrization::<f64>("rc(0<t)", &[])
+        {
+
+            Ok(_) => panic!("Expected variable bounds error."),
+            Err(e) => assert_eq!(e.reason, "The bounds for computed random generation must not depend on t or on dynamic random values.")
+
+        }
+
+        match create_parametrization::<i32>("rc(1/0<2)", &[])
+        {
+
+            Ok(_) => panic!("Expected failed bounds error."),
+            Err(e) => assert_eq!(e.reason, "Could not evaluate the bounds for computed random generation.")
+
+        }
+
+        match create_parametrization::<f64>("rc(2<1)", &[])
+        {
+
+            Ok(_) => panic!("Expected empty range error."),
+            Err(e) => assert_eq!(e.reason, "Could not generate a value between the bounds for computed random generation.")
+
+        }
+
+    }
+
+    #[test]
+    fn test_noise ()
+    {
+
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha8Rng;
+
+        let seeded = |text: &str, seed: u64| { return create_arena::<f64>(text, &[], &Policy::default(), Some(Box::new(ChaCha8Rng::seed_from_u64(seed)))).unwrap(); };
+
+        //Noise with the same seed is the same function of t, and different seeds give different ones
+        let perlin = seeded("perlin(t*0.5)", 3);
+        let fbm = seeded("fbm(t, 4, 2.5, 0.4)", 3);
+
+        for i in 0..20
+        {
+
+            let t = f64::from(i) * 0.37;
+
+            assert_eq!(perlin.evaluate(t), seeded("perlin(t*0.5)", 3).evaluate(t));
+            assert!(perlin.evaluate(t).abs() <= 1.0);
+            assert!(fbm.evaluate(t).abs() <= 1.0);
+
+        }
+
+        assert_ne!(perlin.evaluate(0.75), seeded("perlin(t*0.5)", 4).evaluate(0.75));
+
+        match create_parametrization::<f64>("fbm(t, 0)", &[])
+        {
+
+            Ok(_) => panic!("Expected invalid octaves error."),
+            Err(e) => assert_eq!(e.reason, "Could not parse the number of octaves as a positive integer for fractal noise.")
+
+        }
+
+        //Noise is seeded from the random number generator, so is forbidden along with random values
+        let policy = Policy { allow_random: false, ..Policy::default() };
+
+        for text in ["perlin(t)", "noise(t)", "t + fbm(t, 4)"].iter()
+        {
+
+            match create_arena::<f64>(text, &[], &policy, None)
+            {
+
+                Ok(_) => panic!("Expected forbidden noise error for {}.", text),
+                Err(e) => assert_eq!(e.kind, ParseErrorKind::ForbiddenFeature)
+
+            }
+
+        }
+
+    }
+
 }
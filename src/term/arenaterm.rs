@@ -0,0 +1,920 @@
+use std::fmt;
+use std::cell::RefCell;
+use std::sync::Mutex;
+
+use rand::RngCore;
+use rand_chacha::ChaCha8Rng;
+
+use crate::Number;
+use crate::EvalError;
+use crate::EvalErrorKind;
+use crate::OverflowPolicy;
+use crate::NonFinitePolicy;
+use crate::nonfinite;
+use crate::Dual;
+use crate::dual;
+use crate::standard;
+use crate::Monotonicity;
+use crate::Interval;
+use crate::format;
+use super::Term;
+use super::TermView;
+use super::Subterm;
+use super::sequenceterm::SequenceOperations;
+use super::fractionterm;
+use super::fractionterm::FractionTerm;
+use super::functionterm::FunctionTerm;
+use super::randomterm::RandomTerm;
+use super::distributionterm::Distribution;
+use super::distributionterm::DistributionTerm;
+use super::choiceterm::ChoiceTerm;
+use super::noiseterm::Noise;
+use super::noiseterm::NoiseTerm;
+use super::piecewiseterm;
+
+///A single node of an ArenaTerm, mirroring one of the built-in terms. Children are referred to by
+///their index in the arena.
+pub(crate) enum ArenaNode<T: Number>
+{
+
+    Constant(T),
+    Variable,
+    Sequence(SequenceOperations, Vec<usize>),
+    Scalar(usize, T),
+    Fraction(usize, usize),
+    Function(usize, fn(f64) -> f64, Option<String>),
+    Random(usize, usize),
+    Distribution(Distribution, Vec<usize>),
+    Choice(Vec<(usize, usize)>),
+    Noise(usize, Box<Noise>),
+    Piecewise(Vec<(usize, T)>, Option<T>),
+    Opaque(Box<dyn Term<T> + Send + Sync>)
+
+}
+
+//The identifier of a function paired with its derivative
+type FunctionDerivative = (String, fn(f64) -> f64);
+
+///A term which stores a whole tree of terms in a single vector of nodes, each referring to its
+///children by index. This avoids a heap allocation and a dynamic call for every node, and is what
+///the parser produces. Other terms, such as user-defined ones, can be stored as opaque leaves.
+pub struct ArenaTerm<T: Number>
+{
+
+    //Children are always added before their parents, so the last node is the root
+    nodes: Vec<ArenaNode<T>>,
+    overflow: OverflowPolicy,
+    non_finite: NonFinitePolicy<T>,
+    //The generator for random nodes, or None to use the thread-local generator
+    rng: Option<Mutex<Box<dyn RngCore + Send>>>,
+    //The derivatives given for functions, by identifier
+    derivatives: Vec<FunctionDerivative>,
+    //The monotonicity declared for functions, by identifier
+    monotonicity: Vec<(String, Monotonicity)>,
+    //The domains declared for functions, by identifier
+    domains: Vec<(String, Interval)>,
+    //The LaTeX names given for functions, by identifier
+    latex: Vec<(String, String)>
+
+}
+
+thread_local!
+{
+
+    //The generator which replaces an arena's own during ArenaTerm::evaluate_stream on this thread,
+    //with the address of that arena
+    static STREAM : RefCell<Option<(usize, ChaCha8Rng)>> = const { RefCell::new(None) };
+
+}
+
+impl<T: Number> ArenaTerm<T>
+{
+
+    pub(crate) fn new() -> ArenaTerm<T>
+    {
+
+        return ArenaTerm { nodes: Vec::new(), overflow: OverflowPolicy::Checked, non_finite: NonFinitePolicy::Propagate, rng: None, derivatives: Vec::new(), monotonicity: Vec::new(), domains: Vec::new(), latex: Vec::new() };
+
+    }
+
+    //Sets the overflow policy used by every node of the arena, other than opaque ones
+    pub(crate) fn set_overflow_policy(&mut self, policy: OverflowPolicy)
+    {
+
+        self.overflow = policy;
+
+    }
+
+    //Sets how NaN and infinite values produced by the nodes of the arena are handled
+    pub(crate) fn set_non_finite_policy(&mut self, policy: NonFinitePolicy<T>)
+    {
+
+        self.non_finite = policy;
+
+    }
+
+    pub(crate) fn overflow_policy(&self) -> OverflowPolicy
+    {
+
+        return self.overflow;
+
+    }
+
+    pub(crate) fn non_finite_policy(&self) -> NonFinitePolicy<T>
+    {
+
+        return self.non_finite;
+
+    }
+
+    //Creates an empty arena with the same policies and function hints, for building a new
+    //function from this one
+    pub(crate) fn derived(&self) -> ArenaTerm<T>
+    {
+
+        let mut arena = ArenaTerm::new();
+
+        arena.overflow = self.overflow;
+        arena.non_finite = self.non_finite;
+        arena.derivatives = self.derivatives.clone();
+        arena.monotonicity = self.monotonicity.clone();
+        arena.domains = self.domains.clone();
+        arena.latex = self.latex.clone();
+
+        return arena;
+
+    }
+
+    //Records the derivative given for the function with the given identifier
+    pub(crate) fn add_derivative(&mut self, identifier: &str, derivative: fn(f64) -> f64)
+    {
+
+        if self.function_derivative(identifier).is_none()
+        {
+
+            self.derivatives.push((identifier.to_string(), derivative));
+
+        }
+
+    }
+
+    pub(crate) fn function_derivative(&self, identifier: &str) -> Option<fn(f64) -> f64>
+    {
+
+        return self.derivatives.iter().find(|d| { return d.0 == identifier; }).map(|d| { return d.1; });
+
+    }
+
+    //Records the monotonicity declared for the function with the given identifier
+    pub(crate) fn add_monotonicity(&mut self, identifier: &str, monotonicity: Monotonicity)
+    {
+
+        if self.function_monotonicity(identifier).is_none()
+        {
+
+            self.monotonicity.push((identifier.to_string(), monotonicity));
+
+        }
+
+    }
+
+    pub(crate) fn function_monotonicity(&self, identifier: &str) -> Option<Monotonicity>
+    {
+
+        return self.monotonicity.iter().find(|m| { return m.0 == identifier; }).map(|m| { return m.1; });
+
+    }
+
+    //Records the domain declared for the function with the given identifier
+    pub(crate) fn add_domain(&mut self, identifier: &str, domain: Interval)
+    {
+
+        if self.function_domain(identifier).is_none()
+        {
+
+            self.domains.push((identifier.to_string(), domain));
+
+        }
+
+    }
+
+    pub(crate) fn function_domain(&self, identifier: &str) -> Option<Interval>
+    {
+
+        return self.domains.iter().find(|d| { return d.0 == identifier; }).map(|d| { return d.1; });
+
+    }
+
+    //Records the LaTeX name given for the function with the given identifier
+    pub(crate) fn add_latex(&mut self, identifier: &str, latex: &str)
+    {
+
+        if self.function_latex(identifier).is_none()
+        {
+
+            self.latex.push((identifier.to_string(), latex.to_string()));
+
+        }
+
+    }
+
+    pub(crate) fn function_latex(&self, identifier: &str) -> Option<&str>
+    {
+
+        return self.latex.iter().find(|l| { return l.0 == identifier; }).map(|l| { return l.1.as_str(); });
+
+    }
+
+    //Sets the generator used by random nodes, or the thread-local generator if None
+    pub(crate) fn set_rng(&mut self, rng: Option<Box<dyn RngCore + Send>>)
+    {
+
+        self.rng = rng.map(Mutex::new);
+
+    }
+
+    //Runs the closure with the arena's generator, or the thread-local generator if it has none. A
+    //stream set up by ArenaTerm::evaluate_stream for this arena replaces its generator.
+    pub(crate) fn with_rng<R, F: FnOnce(&mut dyn RngCore) -> R>(&self, f: F) -> R
+    {
+
+        let address = self as *const ArenaTerm<T> as usize;
+
+        return STREAM.with(|stream|
+        {
+
+            match (&self.rng, stream.borrow_mut().as_mut())
+            {
+
+                (Some(_), Some((arena, rng))) if *arena == address => return f(rng),
+                (Some(rng), _) =>
+                {
+
+                    //A panic while generating, e.g. due to an invalid range, leaves the generator in
+                    //a usable state, so a poisoned lock can be recovered
+                    let mut rng = rng.lock().unwrap_or_else(|e| { return e.into_inner(); });
+
+                    return f(&mut **rng);
+
+                },
+                (None, _) => return f(&mut rand::thread_rng())
+
+            }
+
+        });
+
+    }
+
+    //Draws a seed for ArenaTerm::evaluate_stream from the arena's generator, or returns None if it
+    //uses the thread-local generator
+    #[cfg(feature = "rayon")]
+    pub(crate) fn stream_seed(&self) -> Option<u64>
+    {
+
+        return self.rng.as_ref().map(|_| { return self.with_rng(|rng| { return rng.next_u64(); }); });
+
+    }
+
+    //Evaluates the tree with a ChaCha generator on the given stream of the seed in place of the
+    //arena's generator, so that the values drawn do not depend on which thread evaluates the tree or
+    //when. Any stream set up before is restored afterwards, even if evaluation panics.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn evaluate_stream(&self, seed: u64, stream: u64, t: T) -> T
+    {
+
+        use rand::SeedableRng;
+
+        struct Restore(Option<(usize, ChaCha8Rng)>);
+
+        impl Drop for Restore
+        {
+
+            fn drop(&mut self)
+            {
+
+                let previous = self.0.take();
+
+                STREAM.with(|stream| { stream.replace(previous); });
+
+            }
+
+        }
+
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+        rng.set_stream(stream);
+
+        let _restore = Restore(STREAM.with(|s| { return s.replace(Some((self as *const ArenaTerm<T> as usize, rng))); }));
+
+        return Term::evaluate(self, t);
+
+    }
+
+    //Replaces a non-finite result of the whole tree under the fallback policy
+    fn finish(&self, value: T) -> T
+    {
+
+        if let NonFinitePolicy::Fallback(fallback) = self.non_finite
+        {
+
+            if !nonfinite::is_finite(value)
+            {
+
+                return fallback;
+
+            }
+
+        }
+
+        return value;
+
+    }
+
+    //Adds a node to the arena, returning its index. All of its children must already be present.
+    pub(crate) fn add(&mut self, node: ArenaNode<T>) -> usize
+    {
+
+        self.nodes.push(node);
+
+        return self.nodes.len() - 1;
+
+    }
+
+    //Removes every node from the given index onwards, such as nodes only needed while parsing
+    pub(crate) fn truncate(&mut self, length: usize)
+    {
+
+        self.nodes.truncate(length);
+
+    }
+
+    pub(crate) fn node_count(&self) -> usize
+    {
+
+        return self.nodes.len();
+
+    }
+
+    //Copies the whole of the given term into the arena, returning the index of its top node, or None
+    //if it contains an opaque term, which cannot be copied
+    pub(crate) fn copy(&mut self, term: Subterm<'_, T>) -> Option<usize>
+    {
+
+        let node = match term.to_view()
+        {
+
+            TermView::Constant(c) => ArenaNode::Constant(c),
+            TermView::Variable => ArenaNode::Variable,
+            TermView::Sequence(operation, terms) => ArenaNode::Sequence(operation, terms.into_iter().map(|term| { return self.copy(term); }).collect::<Option<Vec<usize>>>()?),
+            TermView::Scalar(subterm, scale) => ArenaNode::Scalar(self.copy(subterm)?, scale),
+            TermView::Fraction(numerator, denominator) => ArenaNode::Fraction(self.copy(numerator)?, self.copy(denominator)?),
+            TermView::Function(subterm, function, name) => ArenaNode::Function(self.copy(subterm)?, function, name.map(str::to_string)),
+            TermView::Random(min, max) => ArenaNode::Random(self.copy(min)?, self.copy(max)?),
+            TermView::Distribution(distribution, parameters) => ArenaNode::Distribution(distribution, parameters.into_iter().map(|term| { return self.copy(term); }).collect::<Option<Vec<usize>>>()?),
+            TermView::Choice(choices) => ArenaNode::Choice(choices.into_iter().map(|choice| { return Some((self.copy(choice.0)?, self.copy(choice.1)?)); }).collect::<Option<Vec<(usize, usize)>>>()?),
+            TermView::Noise(subterm, noise) => ArenaNode::Noise(self.copy(subterm)?, Box::new(noise.clone())),
+            TermView::Piecewise(parts, cycle) => ArenaNode::Piecewise(parts.into_iter().map(|part| { return Some((self.copy(part.0)?, part.1)); }).collect::<Option<Vec<(usize, T)>>>()?, cycle),
+            TermView::Opaque => return None
+
+        };
+
+        return Some(self.add(node));
+
+    }
+
+    ///Returns a reference to the top-level node of the tree
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::term::arenaterm::ArenaTerm;
+    /// use crate::parametrizer::term::constantterm::ConstantTerm;
+    /// use crate::parametrizer::term::Term;
+    /// use crate::parametrizer::term::TermView;
+    ///
+    /// let arena = ArenaTerm::from(Box::new(ConstantTerm::new(4)) as Box<dyn Term<i32> + Send + Sync>);
+    ///
+    /// assert_eq!(4, arena.root().evaluate(9));
+    ///
+    /// match arena.root().to_view()
+    /// {
+    ///
+    ///     TermView::Constant(c) => assert_eq!(4, c),
+    ///     _ => panic!("Expected the view of the stored term.")
+    ///
+    /// }
+    /// ```
+    pub fn root(&self) -> Subterm<'_, T>
+    {
+
+        return Subterm::node(self, self.nodes.len() - 1);
+
+    }
+
+    pub(crate) fn evaluate_node(&self, index: usize, t: T) -> T
+    {
+
+        match &self.nodes[index]
+        {
+
+            ArenaNode::Constant(c) => return *c,
+            ArenaNode::Variable => return t,
+            ArenaNode::Sequence(operation, children) =>
+            {
+
+                let mut computed = operation.unit();
+
+                for child in children
+                {
+
+                    computed = operation.compound(computed, self.evaluate_node(*child, t), self.overflow).expect("Integer overflow in parametrized SequenceTerm.");
+
+                }
+
+                return computed;
+
+            },
+            ArenaNode::Scalar(child, scale) => return self.overflow.multiply(*scale, self.evaluate_node(*child, t)).expect("Integer overflow in parametrized ScalarTerm."),
+            ArenaNode::Fraction(numerator, denominator) =>
+            {
+
+                let d = self.evaluate_node(*denominator, t);
+
+                return FractionTerm::divide(self.evaluate_node(*numerator, t), d, self.overflow);
+
+            },
+            ArenaNode::Function(child, function, _) => return FunctionTerm::apply(*function, self.evaluate_node(*child, t)),
+            ArenaNode::Random(min, max) =>
+            {
+
+                let min = self.evaluate_node(*min, t);
+                let max = self.evaluate_node(*max, t);
+
+                return self.with_rng(|rng| { return RandomTerm::generate_with(rng, min, max); });
+
+            },
+            ArenaNode::Distribution(distribution, children) =>
+            {
+
+                let parameters : Vec<T> = children.iter().map(|child| { return self.evaluate_node(*child, t); }).collect();
+
+                return self.with_rng(|rng| { return DistributionTerm::generate_with(rng, *distribution, &parameters); });
+
+            },
+            ArenaNode::Choice(choices) =>
+            {
+
+                //The generator is locked while choosing, so the chosen node is evaluated afterwards
+                let weights : Vec<T> = choices.iter().map(|choice| { return self.evaluate_node(choice.0, t); }).collect();
+                let i = self.with_rng(|rng| { return ChoiceTerm::choose_with(rng, &weights); });
+
+                return self.evaluate_node(choices[i].1, t);
+
+            },
+            ArenaNode::Noise(child, noise) => return NoiseTerm::apply(noise, self.evaluate_node(*child, t)),
+            ArenaNode::Piecewise(parts, cycle) =>
+            {
+
+                let t = piecewiseterm::wrap(t, *cycle);
+
+                return match piecewiseterm::select(parts.iter().map(|part| { return part.1; }), t)
+                {
+
+                    Some(i) => self.evaluate_node(parts[i].0, t),
+                    None => T::zero()
+
+                };
+
+            },
+            ArenaNode::Opaque(term) => return term.evaluate(t)
+
+        }
+
+    }
+
+    //Checks the value of every node under the error policy, so that the first node to produce a
+    //non-finite value is the one reported
+    pub(crate) fn try_evaluate_node(&self, index: usize, t: T) -> Result<T, EvalError>
+    {
+
+        let value = self.try_compute_node(index, t)?;
+
+        if self.non_finite == NonFinitePolicy::Error && !nonfinite::is_finite(value)
+        {
+
+            return Err(EvalError::new(EvalErrorKind::NonFinite));
+
+        }
+
+        return Ok(value);
+
+    }
+
+    fn try_compute_node(&self, index: usize, t: T) -> Result<T, EvalError>
+    {
+
+        match &self.nodes[index]
+        {
+
+            ArenaNode::Constant(c) => return Ok(*c),
+            ArenaNode::Variable => return Ok(t),
+            ArenaNode::Sequence(operation, children) =>
+            {
+
+                let mut computed = operation.unit();
+
+                for (i, child) in children.iter().enumerate()
+                {
+
+                    let value = self.try_evaluate_node(*child, t).map_err(|e| { return e.within(i); })?;
+
+                    computed = operation.compound(computed, value, self.overflow).ok_or(EvalError::new(EvalErrorKind::Overflow))?;
+
+                }
+
+                return Ok(computed);
+
+            },
+            ArenaNode::Scalar(child, scale) =>
+            {
+
+                let value = self.try_evaluate_node(*child, t).map_err(|e| { return e.within(0); })?;
+
+                return self.overflow.multiply(*scale, value).ok_or(EvalError::new(EvalErrorKind::Overflow));
+
+            },
+            ArenaNode::Fraction(numerator, denominator) =>
+            {
+
+                let d = self.try_evaluate_node(*denominator, t).map_err(|e| { return e.within(1); })?;
+                let n = self.try_evaluate_node(*numerator, t).map_err(|e| { return e.within(0); })?;
+
+                return FractionTerm::try_divide(n, d, self.overflow);
+
+            },
+            ArenaNode::Function(child, function, _) =>
+            {
+
+                let value = self.try_evaluate_node(*child, t).map_err(|e| { return e.within(0); })?;
+
+                return FunctionTerm::try_apply(*function, value);
+
+            },
+            ArenaNode::Random(min, max) =>
+            {
+
+                let min = self.try_evaluate_node(*min, t).map_err(|e| { return e.within(0); })?;
+                let max = self.try_evaluate_node(*max, t).map_err(|e| { return e.within(1); })?;
+
+                return self.with_rng(|rng| { return RandomTerm::try_generate_with(rng, min, max); });
+
+            },
+            ArenaNode::Distribution(distribution, children) =>
+            {
+
+                let mut parameters = Vec::with_capacity(children.len());
+
+                for (i, child) in children.iter().enumerate()
+                {
+
+                    parameters.push(self.try_evaluate_node(*child, t).map_err(|e| { return e.within(i); })?);
+
+                }
+
+                return self.with_rng(|rng| { return DistributionTerm::try_generate_with(rng, *distribution, &parameters); });
+
+            },
+            ArenaNode::Choice(choices) =>
+            {
+
+                let mut weights = Vec::with_capacity(choices.len());
+
+                for (i, choice) in choices.iter().enumerate()
+                {
+
+                    weights.push(self.try_evaluate_node(choice.0, t).map_err(|e| { return e.within(2 * i); })?);
+
+                }
+
+                let i = self.with_rng(|rng| { return ChoiceTerm::try_choose_with(rng, &weights); })?;
+
+                return self.try_evaluate_node(choices[i].1, t).map_err(|e| { return e.within(2 * i + 1); });
+
+            },
+            ArenaNode::Noise(child, noise) =>
+            {
+
+                let value = self.try_evaluate_node(*child, t).map_err(|e| { return e.within(0); })?;
+
+                return NoiseTerm::try_apply(noise, value);
+
+            },
+            ArenaNode::Piecewise(parts, cycle) =>
+            {
+
+                let t = piecewiseterm::try_wrap(t, *cycle)?;
+
+                return match piecewiseterm::select(parts.iter().map(|part| { return part.1; }), t)
+                {
+
+                    Some(i) => self.try_evaluate_node(parts[i].0, t).map_err(|e| { return e.within(i); }),
+                    None => Ok(T::zero())
+
+                };
+
+            },
+            ArenaNode::Opaque(term) => return term.try_evaluate(t)
+
+        }
+
+    }
+
+    //Evaluates the node on a dual number, computing its value and derivative together. Random
+    //values are treated as constants once drawn, and functions without a known derivative, noise,
+    //and opaque terms are differentiated by a central difference.
+    pub(crate) fn evaluate_dual_node(&self, index: usize, t: Dual<T>) -> Dual<T>
+    {
+
+        match &self.nodes[index]
+        {
+
+            ArenaNode::Constant(c) => return Dual::constant(*c),
+            ArenaNode::Variable => return t,
+            ArenaNode::Sequence(operation, children) =>
+            {
+
+                let mut computed = Dual::constant(operation.unit());
+
+                for child in children
+                {
+
+                    let value = self.evaluate_dual_node(*child, t);
+
+                    computed = match operation
+                    {
+
+                        SequenceOperations::Addition => computed.add_with(value, self.overflow),
+                        SequenceOperations::Multiplication => computed.multiply_with(value, self.overflow)
+
+                    }.expect("Integer overflow in parametrized SequenceTerm.");
+
+                }
+
+                return computed;
+
+            },
+            ArenaNode::Scalar(child, scale) => return self.evaluate_dual_node(*child, t).scale_with(*scale, self.overflow).expect("Integer overflow in parametrized ScalarTerm."),
+            ArenaNode::Fraction(numerator, denominator) =>
+            {
+
+                let d = self.evaluate_dual_node(*denominator, t);
+                let n = self.evaluate_dual_node(*numerator, t);
+
+                //Panics in the same way as evaluation
+                if d.value() == T::zero()
+                {
+
+                    panic!("{}", fractionterm::DIVISION_BY_ZERO);
+
+                }
+
+                return n.divide_with(d, self.overflow).expect("Integer overflow in parametrized FractionTerm.");
+
+            },
+            ArenaNode::Function(child, function, identifier) =>
+            {
+
+                let inner = self.evaluate_dual_node(*child, t);
+                let x = inner.value().to_f64().expect("Unable to convert generic type to f64 for FunctionTerm");
+
+                let known = identifier.as_deref().and_then(|i|
+                {
+
+                    match self.function_derivative(i)
+                    {
+
+                        Some(derivative) => return Some(derivative(x)),
+                        None => return standard::find(i).map(|standard| { return standard.derivative.evaluate(x); })
+
+                    }
+
+                });
+
+                let outer = known.unwrap_or_else(|| { return dual::slope::<T, _>(function, x); });
+
+                let outer = T::from_f64(outer).expect("Unable to create generic type T value from f64 for FunctionTerm");
+
+                return Dual::new(FunctionTerm::apply(*function, inner.value()), self.overflow.multiply(outer, inner.derivative()).expect("Integer overflow in parametrized FunctionTerm."));
+
+            },
+            ArenaNode::Noise(child, noise) =>
+            {
+
+                let inner = self.evaluate_dual_node(*child, t);
+                let x = inner.value().to_f64().expect("Unable to convert generic type to f64 for NoiseTerm");
+
+                let outer = T::from_f64(dual::slope::<T, _>(|x| { return noise.sample(x); }, x)).expect("Unable to create generic type T value from f64 for NoiseTerm");
+
+                return Dual::new(NoiseTerm::apply(noise, inner.value()), self.overflow.multiply(outer, inner.derivative()).expect("Integer overflow in parametrized NoiseTerm."));
+
+            },
+            ArenaNode::Choice(choices) =>
+            {
+
+                let weights : Vec<T> = choices.iter().map(|choice| { return self.evaluate_node(choice.0, t.value()); }).collect();
+                let i = self.with_rng(|rng| { return ChoiceTerm::choose_with(rng, &weights); });
+
+                return self.evaluate_dual_node(choices[i].1, t);
+
+            },
+            ArenaNode::Random(_, _) | ArenaNode::Distribution(_, _) => return Dual::constant(self.evaluate_node(index, t.value())),
+            ArenaNode::Piecewise(parts, cycle) =>
+            {
+
+                //Wrapping subtracts a multiple of the cycle, which leaves the derivative unchanged
+                let t = Dual::new(piecewiseterm::wrap(t.value(), *cycle), t.derivative());
+
+                return match piecewiseterm::select(parts.iter().map(|part| { return part.1; }), t.value())
+                {
+
+                    Some(i) => self.evaluate_dual_node(parts[i].0, t),
+                    None => Dual::constant(T::zero())
+
+                };
+
+            },
+            ArenaNode::Opaque(term) =>
+            {
+
+                let x = t.value().to_f64().expect("Unable to convert generic type to f64 for differentiation");
+
+                let outer = dual::slope::<T, _>(|x| { return term.evaluate(T::from_f64(x).expect("Unable to create generic type T value from f64 for differentiation")).to_f64().unwrap_or(f64::NAN); }, x);
+                let outer = T::from_f64(outer).expect("Unable to create generic type T value from f64 for differentiation");
+
+                return Dual::new(term.evaluate(t.value()), self.overflow.multiply(outer, t.derivative()).expect("Integer overflow in parametrized term."));
+
+            }
+
+        }
+
+    }
+
+    //Evaluates the whole tree on a dual number, applying the non-finite policy to the value. A
+    //fallback value is constant, so has a derivative of 0.
+    pub(crate) fn evaluate_dual(&self, t: Dual<T>) -> Dual<T>
+    {
+
+        let computed = self.evaluate_dual_node(self.nodes.len() - 1, t);
+
+        if self.non_finite == NonFinitePolicy::Error && !(nonfinite::is_finite(computed.value()) && nonfinite::is_finite(computed.derivative()))
+        {
+
+            panic!("Non-finite value in parametrized function. Use try_evaluate to find the term which produced it.");
+
+        }
+
+        if let NonFinitePolicy::Fallback(fallback) = self.non_finite
+        {
+
+            if !nonfinite::is_finite(computed.value())
+            {
+
+                return Dual::constant(fallback);
+
+            }
+
+        }
+
+        return computed;
+
+    }
+
+    //Writes a node, letting opaque terms write themselves
+    pub(crate) fn format_node(&self, index: usize, f: &mut fmt::Formatter) -> fmt::Result
+    {
+
+        match &self.nodes[index]
+        {
+
+            ArenaNode::Opaque(term) => return term.format(f),
+            _ => return format::write_view(self.view_node(index), f)
+
+        }
+
+    }
+
+    pub(crate) fn view_node(&self, index: usize) -> TermView<'_, T>
+    {
+
+        match &self.nodes[index]
+        {
+
+            ArenaNode::Constant(c) => return TermView::Constant(*c),
+            ArenaNode::Variable => return TermView::Variable,
+            ArenaNode::Sequence(operation, children) => return TermView::Sequence(*operation, children.iter().map(|child| { return Subterm::node(self, *child); }).collect()),
+            ArenaNode::Scalar(child, scale) => return TermView::Scalar(Subterm::node(self, *child), *scale),
+            ArenaNode::Fraction(numerator, denominator) => return TermView::Fraction(Subterm::node(self, *numerator), Subterm::node(self, *denominator)),
+            ArenaNode::Function(child, function, name) => return TermView::Function(Subterm::node(self, *child), *function, name.as_deref()),
+            ArenaNode::Random(min, max) => return TermView::Random(Subterm::node(self, *min), Subterm::node(self, *max)),
+            ArenaNode::Distribution(distribution, children) => return TermView::Distribution(*distribution, children.iter().map(|child| { return Subterm::node(self, *child); }).collect()),
+            ArenaNode::Noise(child, noise) => return TermView::Noise(Subterm::node(self, *child), noise),
+            ArenaNode::Choice(choices) => return TermView::Choice(choices.iter().map(|choice| { return (Subterm::node(self, choice.0), Subterm::node(self, choice.1)); }).collect()),
+            ArenaNode::Piecewise(parts, cycle) => return TermView::Piecewise(parts.iter().map(|part| { return (Subterm::node(self, part.0), part.1); }).collect(), *cycle),
+            ArenaNode::Opaque(term) => return term.view()
+
+        }
+
+    }
+
+}
+
+impl<T: Number> From<Box<dyn Term<T> + Send + Sync>> for ArenaTerm<T>
+{
+
+    ///Creates an arena whose only node is the given term, stored as an opaque leaf
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::term::arenaterm::ArenaTerm;
+    /// use crate::parametrizer::term::Term;
+    ///
+    /// struct Doubled;
+    ///
+    /// impl Term<i32> for Doubled
+    /// {
+    ///
+    ///     fn evaluate(&self, t: i32) -> i32
+    ///     {
+    ///
+    ///         return 2 * t;
+    ///
+    ///     }
+    ///
+    /// }
+    ///
+    /// let arena = ArenaTerm::from(Box::new(Doubled) as Box<dyn Term<i32> + Send + Sync>);
+    ///
+    /// assert_eq!(8, arena.evaluate(4));
+    /// ```
+    fn from(term: Box<dyn Term<T> + Send + Sync>) -> ArenaTerm<T>
+    {
+
+        let mut arena = ArenaTerm::new();
+
+        arena.add(ArenaNode::Opaque(term));
+
+        return arena;
+
+    }
+
+}
+
+impl<T: Number> Term<T> for ArenaTerm<T>
+{
+
+    ///Evaluates the tree starting from its top-level node
+    ///
+    /// # Panics
+    /// Panics if the result is not finite under NonFinitePolicy::Error
+    fn evaluate(&self, t: T) -> T
+    {
+
+        let value = self.evaluate_node(self.nodes.len() - 1, t);
+
+        if self.non_finite == NonFinitePolicy::Error && !nonfinite::is_finite(value)
+        {
+
+            panic!("Non-finite value in parametrized function. Use try_evaluate to find the term which produced it.");
+
+        }
+
+        return self.finish(value);
+
+    }
+
+    ///Evaluates the tree starting from its top-level node, returning an error instead of panicking
+    fn try_evaluate(&self, t: T) -> Result<T, EvalError>
+    {
+
+        return Ok(self.finish(self.try_evaluate_node(self.nodes.len() - 1, t)?));
+
+    }
+
+    ///Views the top-level node of the tree
+    fn view(&self) -> TermView<'_, T>
+    {
+
+        return self.view_node(self.nodes.len() - 1);
+
+    }
+
+    ///Writes the tree starting from its top-level node
+    fn format(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+
+        return self.format_node(self.nodes.len() - 1, f);
+
+    }
+
+}
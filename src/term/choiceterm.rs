@@ -0,0 +1,238 @@
+use rand::Rng;
+use crate::Number;
+use crate::EvalError;
+use crate::EvalErrorKind;
+use super::Term;
+use super::TermView;
+use super::Subterm;
+
+///A struct pairing one of the choices of a ChoiceTerm with the term giving its weight
+struct WeightedChoice<T: Number>
+{
+
+    weight: Box<dyn Term<T> + Send + Sync>,
+    term: Box<dyn Term<T> + Send + Sync>
+
+}
+
+///A term which randomly picks one of its choices each time it is called, with probability
+///proportional to the choice's weight, and evaluates only that choice. Weights are terms, so may
+///depend on t.
+pub struct ChoiceTerm<T: Number>
+{
+
+    choices: Vec<WeightedChoice<T>>
+
+}
+
+impl<T: Number> ChoiceTerm<T>
+{
+
+    ///Creates a ChoiceTerm, which is initialized to contain no choices. Choices must be added using
+    ///add_choice
+    pub fn new() -> ChoiceTerm<T>
+    {
+
+        return ChoiceTerm { choices: Vec::new() };
+
+    }
+
+    ///Adds a choice with the given weight
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::term::choiceterm::ChoiceTerm;
+    /// use crate::parametrizer::term::constantterm::ConstantTerm;
+    /// use crate::parametrizer::term::variableterm::VariableTerm;
+    /// use crate::parametrizer::term::Term;
+    ///
+    /// let mut choice = ChoiceTerm::new();
+    ///
+    /// choice.add_choice(Box::new(ConstantTerm::new(0)), Box::new(ConstantTerm::new(1)));
+    /// choice.add_choice(Box::new(VariableTerm::new()), Box::new(ConstantTerm::new(2)));
+    ///
+    /// assert_eq!(2, choice.evaluate(5));
+    /// assert!(choice.try_evaluate(0).is_err());
+    /// ```
+    pub fn add_choice(&mut self, weight: Box<dyn Term<T> + Send + Sync>, term: Box<dyn Term<T> + Send + Sync>)
+    {
+
+        self.choices.push(WeightedChoice::<T> { weight, term });
+
+    }
+
+    ///Picks the index of a choice with already evaluated weights using the thread-local random
+    ///number generator
+    ///
+    /// # Panics
+    /// Panics if a weight is negative or not finite, or if the weights sum to 0
+    pub fn choose(weights: &[T]) -> usize
+    {
+
+        return ChoiceTerm::choose_with(&mut rand::thread_rng(), weights);
+
+    }
+
+    ///Picks the index of a choice with already evaluated weights using the given random number
+    ///generator
+    ///
+    /// # Panics
+    /// Panics if a weight is negative or not finite, or if the weights sum to 0
+    pub fn choose_with<R: Rng + ?Sized>(rng: &mut R, weights: &[T]) -> usize
+    {
+
+        return ChoiceTerm::try_choose_with(rng, weights).expect("Invalid weights when choosing in parametrized ChoiceTerm.");
+
+    }
+
+    ///Picks the index of a choice like ChoiceTerm::choose_with, returning an error if the weights
+    ///are invalid
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::term::choiceterm::ChoiceTerm;
+    /// use crate::parametrizer::EvalErrorKind;
+    ///
+    /// let mut rng = rand::thread_rng();
+    ///
+    /// assert_eq!(Ok(1), ChoiceTerm::try_choose_with(&mut rng, &[0.0, 2.5, 0.0]));
+    /// assert_eq!(EvalErrorKind::InvalidDistribution, ChoiceTerm::try_choose_with(&mut rng, &[1.0, -1.0]).unwrap_err().kind());
+    /// assert_eq!(EvalErrorKind::InvalidDistribution, ChoiceTerm::<f64>::try_choose_with(&mut rng, &[]).unwrap_err().kind());
+    /// ```
+    pub fn try_choose_with<R: Rng + ?Sized>(rng: &mut R, weights: &[T]) -> Result<usize, EvalError>
+    {
+
+        let mut converted = Vec::with_capacity(weights.len());
+
+        for w in weights
+        {
+
+            let w = w.to_f64().ok_or(EvalError::new(EvalErrorKind::RandomConversion))?;
+
+            if !(w >= 0.0 && w.is_finite())
+            {
+
+                return Err(EvalError::new(EvalErrorKind::InvalidDistribution));
+
+            }
+
+            converted.push(w);
+
+        }
+
+        let total : f64 = converted.iter().sum();
+
+        if !(total > 0.0 && total.is_finite())
+        {
+
+            return Err(EvalError::new(EvalErrorKind::InvalidDistribution));
+
+        }
+
+        let mut remaining = rng.gen_range(0.0..total);
+        let mut chosen = 0;
+
+        //Rounding may leave a little of the total unassigned, in which case the last choice with a
+        //positive weight is used
+        for (i, w) in converted.iter().enumerate()
+        {
+
+            if *w > 0.0
+            {
+
+                chosen = i;
+
+                if remaining < *w
+                {
+
+                    break;
+
+                }
+
+                remaining -= w;
+
+            }
+
+        }
+
+        return Ok(chosen);
+
+    }
+
+}
+
+impl<T: Number> Default for ChoiceTerm<T>
+{
+
+    fn default() -> ChoiceTerm<T>
+    {
+
+        return ChoiceTerm::new();
+
+    }
+
+}
+
+impl<T: Number> Term<T> for ChoiceTerm<T>
+{
+
+    ///Evaluates every weight, then evaluates a randomly picked choice. Returns 0 if there are no
+    ///choices.
+    ///
+    /// # Panics
+    /// Panics if a weight is negative or not finite, or if the weights sum to 0
+    fn evaluate(&self, t: T) -> T
+    {
+
+        if self.choices.is_empty()
+        {
+
+            return T::zero();
+
+        }
+
+        let weights : Vec<T> = self.choices.iter().map(|choice| { return choice.weight.evaluate(t); }).collect();
+
+        return self.choices[ChoiceTerm::choose(&weights)].term.evaluate(t);
+
+    }
+
+    ///Evaluates a randomly picked choice like ChoiceTerm::evaluate, returning an error if the
+    ///weights are invalid. The weight of the choice at index i is the subterm at index 2 * i, and
+    ///the choice itself is at index 2 * i + 1.
+    fn try_evaluate(&self, t: T) -> Result<T, EvalError>
+    {
+
+        if self.choices.is_empty()
+        {
+
+            return Ok(T::zero());
+
+        }
+
+        let mut weights = Vec::with_capacity(self.choices.len());
+
+        for (i, choice) in self.choices.iter().enumerate()
+        {
+
+            weights.push(choice.weight.try_evaluate(t).map_err(|e| { return e.within(2 * i); })?);
+
+        }
+
+        let i = ChoiceTerm::try_choose_with(&mut rand::thread_rng(), &weights)?;
+
+        return self.choices[i].term.try_evaluate(t).map_err(|e| { return e.within(2 * i + 1); });
+
+    }
+
+    ///Views the term as its weights and choices, in the order they were added
+    fn view(&self) -> TermView<'_, T>
+    {
+
+        return TermView::Choice(self.choices.iter().map(|choice| { return (Subterm::from(&*choice.weight), Subterm::from(&*choice.term)); }).collect());
+
+    }
+
+}
@@ -1,5 +1,6 @@
 use crate::Number;
 use super::Term;
+use super::TermView;
 
 /// A Term that returns a constant value no matter what value is passed in
 pub struct ConstantTerm<T: Number>
@@ -48,4 +49,12 @@ impl<T: Number> Term<T> for ConstantTerm<T>
 
     }
 
+    ///Views the term as its constant value
+    fn view(&self) -> TermView<'_, T>
+    {
+
+        return TermView::Constant(self.c);
+
+    }
+
 }
@@ -0,0 +1,281 @@
+use rand::Rng;
+use rand_distr::Distribution as Sampler;
+use rand_distr::Normal;
+use rand_distr::LogNormal;
+use rand_distr::Exp;
+use rand_distr::Triangular;
+use rand_distr::Poisson;
+use rand_distr::Bernoulli;
+use crate::Number;
+use crate::EvalError;
+use crate::EvalErrorKind;
+use super::Term;
+use super::TermView;
+use super::Subterm;
+
+///The random distributions which can be sampled by a DistributionTerm. In strings, each is written
+///as its identifier followed by its comma separated parameters, e.g. "normal(0, t)".
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Distribution
+{
+
+    ///The normal distribution with a mean and standard deviation, written "normal(mean, sd)"
+    Normal,
+    ///The log-normal distribution, whose logarithm is normally distributed with the given mean and
+    ///standard deviation, written "lognormal(mean, sd)"
+    LogNormal,
+    ///The exponential distribution with a rate, written "exponential(rate)"
+    Exponential,
+    ///The triangular distribution with a minimum, maximum, and mode, written
+    ///"triangular(min, max, mode)"
+    Triangular,
+    ///The Poisson distribution with a mean, written "poisson(mean)"
+    Poisson,
+    ///The Bernoulli distribution, which is 1 with the given probability and 0 otherwise, written
+    ///"bernoulli(p)"
+    Bernoulli
+
+}
+
+impl Distribution
+{
+
+    ///Every distribution, in the order they are declared
+    pub const ALL : [Distribution; 6] = [Distribution::Normal, Distribution::LogNormal, Distribution::Exponential, Distribution::Triangular, Distribution::Poisson, Distribution::Bernoulli];
+
+    ///The identifier of the distribution in strings
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::term::distributionterm::Distribution;
+    ///
+    /// assert_eq!("lognormal", Distribution::LogNormal.identifier());
+    /// ```
+    pub fn identifier(self) -> &'static str
+    {
+
+        match self
+        {
+
+            Distribution::Normal => return "normal",
+            Distribution::LogNormal => return "lognormal",
+            Distribution::Exponential => return "exponential",
+            Distribution::Triangular => return "triangular",
+            Distribution::Poisson => return "poisson",
+            Distribution::Bernoulli => return "bernoulli"
+
+        }
+
+    }
+
+    ///The number of parameters the distribution takes
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::term::distributionterm::Distribution;
+    ///
+    /// assert_eq!(2, Distribution::Normal.parameters());
+    /// assert_eq!(3, Distribution::Triangular.parameters());
+    /// ```
+    pub fn parameters(self) -> usize
+    {
+
+        match self
+        {
+
+            Distribution::Normal | Distribution::LogNormal => return 2,
+            Distribution::Exponential | Distribution::Poisson | Distribution::Bernoulli => return 1,
+            Distribution::Triangular => return 3
+
+        }
+
+    }
+
+    //Draws a value using already converted parameters, returning None if they are invalid
+    fn sample<R: Rng + ?Sized>(self, rng: &mut R, p: &[f64]) -> Option<f64>
+    {
+
+        //rand_distr accepts negative standard deviations, which are rejected here for consistency
+        //with the other distributions
+        if (self == Distribution::Normal || self == Distribution::LogNormal) && p[1] < 0.0
+        {
+
+            return None;
+
+        }
+
+        match self
+        {
+
+            Distribution::Normal => return Normal::new(p[0], p[1]).ok().map(|d| { return d.sample(rng); }),
+            Distribution::LogNormal => return LogNormal::new(p[0], p[1]).ok().map(|d| { return d.sample(rng); }),
+            Distribution::Exponential => return Exp::new(p[0]).ok().map(|d| { return d.sample(rng); }),
+            Distribution::Triangular => return Triangular::new(p[0], p[1], p[2]).ok().map(|d| { return d.sample(rng); }),
+            Distribution::Poisson => return Poisson::new(p[0]).ok().map(|d| { return d.sample(rng); }),
+            Distribution::Bernoulli => return Bernoulli::new(p[0]).ok().map(|d| { return if d.sample(rng) { 1.0 } else { 0.0 }; })
+
+        }
+
+    }
+
+}
+
+///A term which draws a value from a random distribution each time it is called, using the values of
+///its parameter terms as the distribution's parameters. For integer types, the drawn value is
+///truncated towards zero.
+pub struct DistributionTerm<T: Number>
+{
+
+    distribution: Distribution,
+    parameters: Vec<Box<dyn Term<T> + Send + Sync>>
+
+}
+
+impl<T: Number> DistributionTerm<T>
+{
+
+    ///A term which draws values from the given distribution, with parameters given in the order
+    ///documented for each distribution
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::term::distributionterm::DistributionTerm;
+    /// use crate::parametrizer::term::distributionterm::Distribution;
+    /// use crate::parametrizer::term::variableterm::VariableTerm;
+    /// use crate::parametrizer::term::constantterm::ConstantTerm;
+    /// use crate::parametrizer::term::Term;
+    ///
+    /// let triangular = DistributionTerm::new(Distribution::Triangular, vec![Box::new(ConstantTerm::new(1.0)), Box::new(VariableTerm::new()), Box::new(ConstantTerm::new(2.0))]);
+    /// let certain = DistributionTerm::new(Distribution::Bernoulli, vec![Box::new(ConstantTerm::new(1.0))]);
+    ///
+    /// assert!(triangular.evaluate(3.0) >= 1.0);
+    /// assert!(triangular.evaluate(3.0) <= 3.0);
+    /// assert_eq!(1.0, certain.evaluate(0.0));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the number of parameters does not match the distribution
+    pub fn new(distribution: Distribution, parameters: Vec<Box<dyn Term<T> + Send + Sync>>) -> DistributionTerm<T>
+    {
+
+        if parameters.len() != distribution.parameters()
+        {
+
+            panic!("Wrong number of parameters for the distribution of a DistributionTerm.");
+
+        }
+
+        return DistributionTerm { distribution, parameters };
+
+    }
+
+    ///Draws a value from the distribution with already evaluated parameters using the thread-local
+    ///random number generator
+    ///
+    /// # Panics
+    /// Panics if the parameters are invalid for the distribution
+    pub fn generate(distribution: Distribution, parameters: &[T]) -> T
+    {
+
+        return DistributionTerm::generate_with(&mut rand::thread_rng(), distribution, parameters);
+
+    }
+
+    ///Draws a value from the distribution with already evaluated parameters using the given random
+    ///number generator
+    ///
+    /// # Panics
+    /// Panics if the parameters are invalid for the distribution
+    pub fn generate_with<R: Rng + ?Sized>(rng: &mut R, distribution: Distribution, parameters: &[T]) -> T
+    {
+
+        let parameters : Vec<f64> = parameters.iter().map(|p| { return p.to_f64().expect("Unable to convert generic type to f64 for random generation."); }).collect();
+
+        let random = distribution.sample(rng, &parameters).expect("Invalid parameters for the distribution in parametrized DistributionTerm.");
+
+        return T::from_f64(random).expect("Unable to convert f64 to generic type after random generation.");
+
+    }
+
+    ///Draws a value from the distribution like DistributionTerm::generate_with, returning an error
+    ///if the parameters are invalid for the distribution or cannot be converted to f64
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::term::distributionterm::DistributionTerm;
+    /// use crate::parametrizer::term::distributionterm::Distribution;
+    /// use crate::parametrizer::EvalErrorKind;
+    ///
+    /// let mut rng = rand::thread_rng();
+    ///
+    /// assert!(DistributionTerm::try_generate_with(&mut rng, Distribution::Poisson, &[4.0]).unwrap() >= 0.0);
+    /// assert_eq!(EvalErrorKind::InvalidDistribution, DistributionTerm::try_generate_with(&mut rng, Distribution::Normal, &[0.0, -1.0]).unwrap_err().kind());
+    /// assert_eq!(EvalErrorKind::InvalidDistribution, DistributionTerm::try_generate_with(&mut rng, Distribution::Bernoulli, &[2]).unwrap_err().kind());
+    /// ```
+    pub fn try_generate_with<R: Rng + ?Sized>(rng: &mut R, distribution: Distribution, parameters: &[T]) -> Result<T, EvalError>
+    {
+
+        let mut converted = Vec::with_capacity(parameters.len());
+
+        for p in parameters
+        {
+
+            converted.push(p.to_f64().ok_or(EvalError::new(EvalErrorKind::RandomConversion))?);
+
+        }
+
+        let random = distribution.sample(rng, &converted).ok_or(EvalError::new(EvalErrorKind::InvalidDistribution))?;
+
+        return T::from_f64(random).ok_or(EvalError::new(EvalErrorKind::RandomConversion));
+
+    }
+
+}
+
+impl<T: Number> Term<T> for DistributionTerm<T>
+{
+
+    ///Draws a value from the distribution using the values of the parameter terms
+    ///
+    /// # Panics
+    /// Panics if the parameters are invalid for the distribution
+    fn evaluate(&self, t: T) -> T
+    {
+
+        let parameters : Vec<T> = self.parameters.iter().map(|p| { return p.evaluate(t); }).collect();
+
+        return DistributionTerm::generate(self.distribution, &parameters);
+
+    }
+
+    ///Draws a value from the distribution, returning an error if the parameters are invalid. Each
+    ///parameter is the subterm at its index in the distribution's parameter list.
+    fn try_evaluate(&self, t: T) -> Result<T, EvalError>
+    {
+
+        let mut parameters = Vec::with_capacity(self.parameters.len());
+
+        for (i, p) in self.parameters.iter().enumerate()
+        {
+
+            parameters.push(p.try_evaluate(t).map_err(|e| { return e.within(i); })?);
+
+        }
+
+        return DistributionTerm::try_generate_with(&mut rand::thread_rng(), self.distribution, &parameters);
+
+    }
+
+    ///Views the term as its distribution and parameter terms
+    fn view(&self) -> TermView<'_, T>
+    {
+
+        return TermView::Distribution(self.distribution, self.parameters.iter().map(|p| { return Subterm::from(&**p); }).collect());
+
+    }
+
+}
@@ -1,12 +1,21 @@
 use crate::Number;
+use crate::EvalError;
+use crate::EvalErrorKind;
+use crate::OverflowPolicy;
 use super::Term;
+use super::TermView;
+use super::Subterm;
+
+//The message of the panic when dividing by 0
+pub(crate) const DIVISION_BY_ZERO : &str = "Cannot divide by 0 in parametrized InverseTerm. Make sure the function you set as your denominator is never zero on your inputs.";
 
 ///A term which divides one stored term by another
 pub struct FractionTerm<T: Number>
 {
 
     numerator: Box<dyn Term<T> + Send + Sync>,
-    denominator: Box<dyn Term<T> + Send + Sync> 
+    denominator: Box<dyn Term<T> + Send + Sync>,
+    policy: OverflowPolicy
 
 }
 
@@ -50,7 +59,67 @@ impl<T: Number> FractionTerm<T>
     pub fn new(numerator: Box<dyn Term<T> + Send + Sync>, denominator: Box<dyn Term<T> + Send + Sync>) -> FractionTerm<T>
     {
 
-        return FractionTerm::<T> { numerator, denominator };
+        return FractionTerm::<T> { numerator, denominator, policy: OverflowPolicy::Checked };
+
+    }
+
+    ///Sets how integer overflow is handled when dividing, which is checked by default. Overflow
+    ///only occurs when dividing the minimum value of a signed type by -1.
+    pub fn set_overflow_policy(&mut self, policy: OverflowPolicy)
+    {
+
+        self.policy = policy;
+
+    }
+
+    ///Divides two already evaluated values
+    ///
+    /// # Panics
+    /// Panics if the denominator is 0, or if the result overflows under OverflowPolicy::Checked
+    pub fn divide(numerator: T, denominator: T, policy: OverflowPolicy) -> T
+    {
+
+        if denominator == T::zero() //If the denominator is 0, panic
+        {
+
+            panic!("{}", DIVISION_BY_ZERO);
+
+        }
+        else
+        {
+
+            return policy.divide(numerator, denominator).expect("Integer overflow in parametrized FractionTerm.");
+
+        }
+
+    }
+
+    ///Divides two already evaluated values, returning an error if the denominator is 0 or the
+    ///result overflows under OverflowPolicy::Checked
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::term::fractionterm::FractionTerm;
+    /// use crate::parametrizer::EvalErrorKind;
+    /// use crate::parametrizer::OverflowPolicy;
+    ///
+    /// assert_eq!(Ok(3), FractionTerm::try_divide(6, 2, OverflowPolicy::Checked));
+    /// assert_eq!(EvalErrorKind::DivisionByZero, FractionTerm::try_divide(6, 0, OverflowPolicy::Checked).unwrap_err().kind());
+    /// assert_eq!(EvalErrorKind::Overflow, FractionTerm::try_divide(i8::MIN, -1, OverflowPolicy::Checked).unwrap_err().kind());
+    /// assert_eq!(Ok(i8::MIN), FractionTerm::try_divide(i8::MIN, -1, OverflowPolicy::Wrapping));
+    /// ```
+    pub fn try_divide(numerator: T, denominator: T, policy: OverflowPolicy) -> Result<T, EvalError>
+    {
+
+        if denominator == T::zero()
+        {
+
+            return Err(EvalError::new(EvalErrorKind::DivisionByZero));
+
+        }
+
+        return policy.divide(numerator, denominator).ok_or(EvalError::new(EvalErrorKind::Overflow));
 
     }
 
@@ -62,24 +131,34 @@ impl<T: Number> Term<T> for FractionTerm<T>
     ///Divides the numerator by the denominator.
     ///
     /// # Panics
-    /// Panics if the denominator evaluates to 0
+    /// Panics if the denominator evaluates to 0, or if the result overflows under
+    /// OverflowPolicy::Checked
     fn evaluate(&self, t: T) -> T
     {
 
         let d = self.denominator.evaluate(t);
 
-        if d == T::zero() //If the denominator is 0, panic
-        {
+        return FractionTerm::divide(self.numerator.evaluate(t), d, self.policy);
 
-            panic!("Cannot divide by 0 in parametrized InverseTerm. Make sure the function you set as your denominator is never zero on your inputs.");
+    }
 
-        }
-        else
-        {
+    ///Divides the numerator by the denominator, returning an error if the denominator evaluates to
+    ///0. The numerator is the subterm at index 0 and the denominator at index 1.
+    fn try_evaluate(&self, t: T) -> Result<T, EvalError>
+    {
 
-            return self.numerator.evaluate(t) / d; 
+        let d = self.denominator.try_evaluate(t).map_err(|e| { return e.within(1); })?;
+        let n = self.numerator.try_evaluate(t).map_err(|e| { return e.within(0); })?;
 
-        }
+        return FractionTerm::try_divide(n, d, self.policy);
+
+    }
+
+    ///Views the term as its numerator and denominator
+    fn view(&self) -> TermView<'_, T>
+    {
+
+        return TermView::Fraction(Subterm::from(&*self.numerator), Subterm::from(&*self.denominator));
 
     }
 
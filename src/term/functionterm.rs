@@ -1,12 +1,17 @@
 use crate::Number;
+use crate::EvalError;
+use crate::EvalErrorKind;
 use super::Term;
+use super::TermView;
+use super::Subterm;
 
 ///A term which applies the stored function to the stored term evaluated at the given number
 pub struct FunctionTerm<T: Number>
 {
 
     term: Box<dyn Term<T> + Send + Sync>,
-    function: fn(f64) -> f64
+    function: fn(f64) -> f64,
+    name: Option<String>
 
 }
 
@@ -35,7 +40,69 @@ impl<T: Number> FunctionTerm<T>
     pub fn new(term: Box<dyn Term<T> + Send + Sync>, function: fn(f64) -> f64) -> FunctionTerm<T>
     {
 
-        return FunctionTerm::<T> { term, function };
+        return FunctionTerm::<T> { term, function, name: None };
+
+    }
+
+    ///Creates a function term which also remembers the identifier of its function, as done when
+    ///parsing a ParametrizerFunction
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::term::functionterm::FunctionTerm;
+    /// use crate::parametrizer::term::variableterm::VariableTerm;
+    /// use crate::parametrizer::term::Term;
+    /// use crate::parametrizer::term::TermView;
+    ///
+    /// let sin = FunctionTerm::named(Box::new(VariableTerm::new()), f64::sin, "sin".to_string());
+    ///
+    /// assert_eq!(2.0_f64.sin(), sin.evaluate(2.0));
+    ///
+    /// match sin.view()
+    /// {
+    ///
+    ///     TermView::Function(_, _, name) => assert_eq!(Some("sin"), name),
+    ///     _ => panic!("Expected a function view.")
+    ///
+    /// }
+    /// ```
+    pub fn named(term: Box<dyn Term<T> + Send + Sync>, function: fn(f64) -> f64, name: String) -> FunctionTerm<T>
+    {
+
+        return FunctionTerm::<T> { term, function, name: Some(name) };
+
+    }
+
+    ///Applies the function to an already evaluated value, converting it to f64 and back
+    ///
+    /// # Panics
+    /// Panics if the generic type T cannot be successfully converted to f64
+    pub fn apply(function: fn(f64) -> f64, value: T) -> T
+    {
+
+        return T::from_f64(function(value.to_f64().expect("Unable to convert generic type to f64 for FunctionTerm"))).expect("Unable to create generic type T value from f64 for FunctionTerm");
+
+    }
+
+    ///Applies the function to an already evaluated value, returning an error if the value or the
+    ///function's output cannot be converted between T and f64
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::term::functionterm::FunctionTerm;
+    /// use crate::parametrizer::EvalErrorKind;
+    ///
+    /// assert_eq!(Ok(3), FunctionTerm::try_apply(f64::sqrt, 9));
+    /// assert_eq!(EvalErrorKind::FunctionConversion, FunctionTerm::try_apply(f64::sqrt, -9).unwrap_err().kind());
+    /// ```
+    pub fn try_apply(function: fn(f64) -> f64, value: T) -> Result<T, EvalError>
+    {
+
+        let input = value.to_f64().ok_or(EvalError::new(EvalErrorKind::FunctionConversion))?;
+
+        return T::from_f64(function(input)).ok_or(EvalError::new(EvalErrorKind::FunctionConversion));
 
     }
 
@@ -51,7 +118,27 @@ impl<T: Number> Term<T> for FunctionTerm<T>
     fn evaluate(&self, t: T) -> T
     {
 
-        return T::from_f64((self.function)(self.term.evaluate(t).to_f64().expect("Unable to convert generic type to f64 for FunctionTerm"))).expect("Unable to create generic type T value from f64 for FunctionTerm");
+        return FunctionTerm::apply(self.function, self.term.evaluate(t));
+
+    }
+
+    ///Evaluates the function at the term evaluated for the given value of t, returning an error if
+    ///the conversion to or from f64 fails. The term is the subterm at index 0.
+    fn try_evaluate(&self, t: T) -> Result<T, EvalError>
+    {
+
+        let value = self.term.try_evaluate(t).map_err(|e| { return e.within(0); })?;
+
+        return FunctionTerm::try_apply(self.function, value);
+
+    }
+
+    ///Views the term as its subterm, the function applied to it, and the function's identifier if
+    ///it has one
+    fn view(&self) -> TermView<'_, T>
+    {
+
+        return TermView::Function(Subterm::from(&*self.term), self.function, self.name.as_deref());
 
     }
 
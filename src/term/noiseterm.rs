@@ -0,0 +1,368 @@
+use rand::SeedableRng;
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha8Rng;
+use crate::Number;
+use crate::EvalError;
+use crate::EvalErrorKind;
+use super::Term;
+use super::TermView;
+use super::Subterm;
+
+///The kinds of one dimensional coherent noise which can be computed by a Noise. Every kind is 0 or
+///smoothly interpolated between integer inputs, and stays within -1 and 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NoiseKind
+{
+
+    ///Gradient noise, which is 0 at every integer, written "perlin(x)" or "noise(x)"
+    Perlin,
+    ///Simplex noise, which is 0 at every integer and cheaper than Perlin noise in higher
+    ///dimensions, written "simplex(x)"
+    Simplex,
+    ///Noise interpolating between random values at every integer, written "valuenoise(x)"
+    Value
+
+}
+
+impl NoiseKind
+{
+
+    ///Every kind of noise, in the order they are declared
+    pub const ALL : [NoiseKind; 3] = [NoiseKind::Perlin, NoiseKind::Simplex, NoiseKind::Value];
+
+    ///The identifier of the kind of noise in strings
+    pub fn identifier(self) -> &'static str
+    {
+
+        match self
+        {
+
+            NoiseKind::Perlin => return "perlin",
+            NoiseKind::Simplex => return "simplex",
+            NoiseKind::Value => return "valuenoise"
+
+        }
+
+    }
+
+}
+
+///A seeded one dimensional noise function, optionally summing several octaves of noise as
+///fractal Brownian motion. Noises with the same parameters and seed always produce the same values.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Noise
+{
+
+    kind: NoiseKind,
+    seed: u64,
+    octaves: u32,
+    lacunarity: f64,
+    gain: f64,
+    //A shuffle of 0 to 255, used to hash the integers surrounding the input
+    permutation: [u8; 256]
+
+}
+
+//Smooths the interpolation between lattice points so that the noise has continuous first and
+//second derivatives
+fn fade(t: f64) -> f64
+{
+
+    return t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+
+}
+
+impl Noise
+{
+
+    ///Creates a single octave of noise of the given kind, seeded with the given seed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::term::noiseterm::Noise;
+    /// use crate::parametrizer::term::noiseterm::NoiseKind;
+    ///
+    /// let perlin = Noise::new(NoiseKind::Perlin, 12);
+    /// let value = Noise::new(NoiseKind::Value, 12);
+    ///
+    /// assert_eq!(0.0, perlin.sample(3.0));
+    /// assert_eq!(value.sample(2.5), Noise::new(NoiseKind::Value, 12).sample(2.5));
+    /// assert!((perlin.sample(1.5) - perlin.sample(1.501)).abs() < 0.01);
+    /// ```
+    pub fn new(kind: NoiseKind, seed: u64) -> Noise
+    {
+
+        return Noise::fractal(kind, seed, 1, 2.0, 0.5);
+
+    }
+
+    ///Creates fractal Brownian motion, which sums the given number of octaves of noise. Each octave
+    ///multiplies the frequency of the previous one by the lacunarity and its amplitude by the gain.
+    ///The sum is divided by the total amplitude, so that it stays within -1 and 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::term::noiseterm::Noise;
+    /// use crate::parametrizer::term::noiseterm::NoiseKind;
+    ///
+    /// let single = Noise::new(NoiseKind::Simplex, 5);
+    /// let fbm = Noise::fractal(NoiseKind::Simplex, 5, 4, 2.0, 0.5);
+    ///
+    /// assert_ne!(single.sample(0.3), fbm.sample(0.3));
+    /// assert!(fbm.sample(0.3).abs() <= 1.0);
+    /// ```
+    pub fn fractal(kind: NoiseKind, seed: u64, octaves: u32, lacunarity: f64, gain: f64) -> Noise
+    {
+
+        let mut permutation = [0; 256];
+
+        for (i, p) in permutation.iter_mut().enumerate()
+        {
+
+            *p = i as u8;
+
+        }
+
+        permutation.shuffle(&mut ChaCha8Rng::seed_from_u64(seed));
+
+        return Noise { kind, seed, octaves, lacunarity, gain, permutation };
+
+    }
+
+    ///The kind of noise of each octave
+    pub fn kind(&self) -> NoiseKind
+    {
+
+        return self.kind;
+
+    }
+
+    ///The seed the noise was created with
+    pub fn seed(&self) -> u64
+    {
+
+        return self.seed;
+
+    }
+
+    ///The number of octaves summed
+    pub fn octaves(&self) -> u32
+    {
+
+        return self.octaves;
+
+    }
+
+    ///The factor by which the frequency increases with each octave
+    pub fn lacunarity(&self) -> f64
+    {
+
+        return self.lacunarity;
+
+    }
+
+    ///The factor by which the amplitude decreases with each octave
+    pub fn gain(&self) -> f64
+    {
+
+        return self.gain;
+
+    }
+
+    ///Computes the noise at x
+    pub fn sample(&self, x: f64) -> f64
+    {
+
+        let mut sum = 0.0;
+        let mut total = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+
+        for _ in 0..self.octaves
+        {
+
+            sum += amplitude * self.octave(x * frequency);
+            total += amplitude;
+
+            frequency *= self.lacunarity;
+            amplitude *= self.gain;
+
+        }
+
+        if total == 0.0
+        {
+
+            return 0.0;
+
+        }
+
+        return sum / total;
+
+    }
+
+    //Hashes an integer to a value between 0 and 255
+    fn hash(&self, i: f64) -> u8
+    {
+
+        return self.permutation[((i as i64) & 255) as usize];
+
+    }
+
+    //A random value between -1 and 1 for the integer
+    fn lattice(&self, i: f64) -> f64
+    {
+
+        return f64::from(self.hash(i)) / 127.5 - 1.0;
+
+    }
+
+    fn octave(&self, x: f64) -> f64
+    {
+
+        let i = x.floor();
+        let f = x - i;
+
+        match self.kind
+        {
+
+            NoiseKind::Perlin =>
+            {
+
+                //Each integer has a random gradient, and the noise at most reaches 0.5 halfway
+                //between two integers with opposite gradients
+                let left = self.lattice(i) * f;
+                let right = self.lattice(i + 1.0) * (f - 1.0);
+
+                return 2.0 * (left + fade(f) * (right - left));
+
+            },
+            NoiseKind::Simplex =>
+            {
+
+                //Gustavson's formulation, whose maximum of 2.53125 is scaled to 1
+                let gradient = |hash: u8, d: f64|
+                {
+
+                    let g = 1.0 + f64::from(hash & 7);
+
+                    return if hash & 8 == 0 { g * d } else { -g * d };
+
+                };
+
+                let left = (1.0 - f * f).powi(4) * gradient(self.hash(i), f);
+                let right = (1.0 - (f - 1.0) * (f - 1.0)).powi(4) * gradient(self.hash(i + 1.0), f - 1.0);
+
+                return 0.395 * (left + right);
+
+            },
+            NoiseKind::Value =>
+            {
+
+                let left = self.lattice(i);
+                let right = self.lattice(i + 1.0);
+
+                return left + fade(f) * (right - left);
+
+            }
+
+        }
+
+    }
+
+}
+
+///A term which computes a seeded noise function at the value of its subterm, giving smooth random
+///motion in t
+pub struct NoiseTerm<T: Number>
+{
+
+    term: Box<dyn Term<T> + Send + Sync>,
+    noise: Noise
+
+}
+
+impl<T: Number> NoiseTerm<T>
+{
+
+    ///Creates a noise term from the given term and noise
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::term::noiseterm::NoiseTerm;
+    /// use crate::parametrizer::term::noiseterm::Noise;
+    /// use crate::parametrizer::term::noiseterm::NoiseKind;
+    /// use crate::parametrizer::term::variableterm::VariableTerm;
+    /// use crate::parametrizer::term::Term;
+    ///
+    /// let noise = NoiseTerm::new(Box::new(VariableTerm::new()), Noise::new(NoiseKind::Perlin, 3));
+    ///
+    /// assert_eq!(Noise::new(NoiseKind::Perlin, 3).sample(0.7), noise.evaluate(0.7));
+    /// ```
+    pub fn new(term: Box<dyn Term<T> + Send + Sync>, noise: Noise) -> NoiseTerm<T>
+    {
+
+        return NoiseTerm { term, noise };
+
+    }
+
+    ///Computes the noise at an already evaluated value, converting it to f64 and back
+    ///
+    /// # Panics
+    /// Panics if the generic type T cannot be successfully converted to f64
+    pub fn apply(noise: &Noise, value: T) -> T
+    {
+
+        return T::from_f64(noise.sample(value.to_f64().expect("Unable to convert generic type to f64 for NoiseTerm"))).expect("Unable to create generic type T value from f64 for NoiseTerm");
+
+    }
+
+    ///Computes the noise at an already evaluated value, returning an error if the value or the
+    ///noise cannot be converted between T and f64
+    pub fn try_apply(noise: &Noise, value: T) -> Result<T, EvalError>
+    {
+
+        let input = value.to_f64().ok_or(EvalError::new(EvalErrorKind::FunctionConversion))?;
+
+        return T::from_f64(noise.sample(input)).ok_or(EvalError::new(EvalErrorKind::FunctionConversion));
+
+    }
+
+}
+
+impl<T: Number> Term<T> for NoiseTerm<T>
+{
+
+    ///Computes the noise at the term evaluated for the given value of t
+    ///
+    /// # Panics
+    /// Panics if the generic type T cannot be successfully converted to f64
+    fn evaluate(&self, t: T) -> T
+    {
+
+        return NoiseTerm::apply(&self.noise, self.term.evaluate(t));
+
+    }
+
+    ///Computes the noise at the term evaluated for the given value of t, returning an error if the
+    ///conversion to or from f64 fails. The term is the subterm at index 0.
+    fn try_evaluate(&self, t: T) -> Result<T, EvalError>
+    {
+
+        let value = self.term.try_evaluate(t).map_err(|e| { return e.within(0); })?;
+
+        return NoiseTerm::try_apply(&self.noise, value);
+
+    }
+
+    ///Views the term as its subterm and noise
+    fn view(&self) -> TermView<'_, T>
+    {
+
+        return TermView::Noise(Subterm::from(&*self.term), &self.noise);
+
+    }
+
+}
@@ -1,5 +1,89 @@
 use crate::Number;
+use crate::EvalError;
+use crate::EvalErrorKind;
 use super::Term;
+use super::TermView;
+use super::Subterm;
+
+//The most loops of a looping piecewise term whose breakpoints are listed, beyond which its loops
+//are treated like any other discontinuity
+const MAX_LOOPS : f64 = 4096.0;
+
+//Finds the values of t strictly between start and end at which any piecewise term within the term
+//switches parts or loops, in increasing order. Nested piecewise terms are treated as though t were
+//not wrapped by their parents.
+pub(crate) fn breakpoints<T: Number>(term: Subterm<'_, T>, start: f64, end: f64) -> Vec<f64>
+{
+
+    let mut points = Vec::new();
+
+    collect_breakpoints(term, start, end, &mut points);
+
+    points.sort_by(|a, b| { return a.total_cmp(b); });
+    points.dedup();
+
+    return points;
+
+}
+
+fn collect_breakpoints<T: Number>(term: Subterm<'_, T>, start: f64, end: f64, points: &mut Vec<f64>)
+{
+
+    let view = term.to_view();
+
+    if let TermView::Piecewise(parts, cycle) = &view
+    {
+
+        let afters : Vec<f64> = parts.iter().skip(1).filter_map(|part| { return part.1.to_f64(); }).collect();
+        let mut push = |point: f64|
+        {
+
+            if point > start && point < end
+            {
+
+                points.push(point);
+
+            }
+
+        };
+
+        match cycle.and_then(|c| { return c.to_f64(); })
+        {
+
+            Some(c) if c > 0.0 && (end.max(0.0) - start.max(0.0)) / c <= MAX_LOOPS =>
+            {
+
+                //Up to the loop value, t is unwrapped, and past it t repeats every loop
+                afters.iter().filter(|after| { return **after <= c; }).for_each(|after| { push(*after); });
+
+                let first = (start / c).floor().max(1.0) as u64;
+                let last = (end / c).ceil().max(0.0) as u64;
+
+                for k in first..=last
+                {
+
+                    let offset = k as f64 * c;
+
+                    push(offset);
+                    afters.iter().filter(|after| { return **after > 0.0 && **after < c; }).for_each(|after| { push(offset + *after); });
+
+                }
+
+            },
+            _ => afters.iter().for_each(|after| { push(*after); })
+
+        }
+
+    }
+
+    for subterm in view.subterms()
+    {
+
+        collect_breakpoints(subterm, start, end, points);
+
+    }
+
+}
 
 ///A struct assigning to each piece of the function a time after which it is applicable. The term
 ///will be the evluated one until t passes the next part's after value
@@ -11,6 +95,100 @@ struct PiecewisePair<T: Number>
 
 }
 
+//Reduces t to its remainder with respect to the loop value for looping piecewise terms
+pub(crate) fn wrap<T: Number>(t: T, cycle: Option<T>) -> T
+{
+
+    if let Some(c) = cycle
+    {
+
+        if t > c
+        {
+
+            return t % c;
+
+        }
+
+    }
+
+    return t;
+
+}
+
+//Like wrap, but returns an error for a loop value of 0 rather than panicking on integer types
+pub(crate) fn try_wrap<T: Number>(t: T, cycle: Option<T>) -> Result<T, EvalError>
+{
+
+    if cycle == Some(T::zero()) && t > T::zero()
+    {
+
+        return Err(EvalError::new(EvalErrorKind::DivisionByZero));
+
+    }
+
+    return Ok(wrap(t, cycle));
+
+}
+
+//Finds the index of the part which applies at t, given the after values of the parts in the order
+//they were added. The first part applies until t reaches the next part's after value, and so on.
+//Returns None if there are no parts.
+pub(crate) fn select<T: Number, I: Iterator<Item = T>>(afters: I, t: T) -> Option<usize>
+{
+
+    let mut current = None;
+
+    for (i, after) in afters.enumerate()
+    {
+
+        if current.is_none() || t >= after
+        {
+
+            current = Some(i);
+
+        }
+        else
+        {
+
+            break;
+
+        }
+
+    }
+
+    return current;
+
+}
+
+//Finds the index of the part which applies just before t, i.e. like select but for values slightly
+//less than t, so a part whose after value is exactly t does not yet apply
+pub(crate) fn select_before<T: Number, I: Iterator<Item = T>>(afters: I, t: T) -> Option<usize>
+{
+
+    let mut current = None;
+
+    for (i, after) in afters.enumerate()
+    {
+
+        if current.is_none() || t > after
+        {
+
+            current = Some(i);
+
+        }
+        else
+        {
+
+            break;
+
+        }
+
+    }
+
+    return current;
+
+}
+
 ///Struct containing a list of terms and times which split the number line into intervals during
 ///which different terms are applied
 pub struct PiecewiseTerm<T: Number>
@@ -95,49 +273,40 @@ impl<T: Number> Term<T> for PiecewiseTerm<T>
     fn evaluate(&self, time: T) -> T
     {
 
-        let mut iter = self.parts.iter();
+        let t = wrap(time, self.cycle);
 
-        let mut current = match iter.next()
+        return match select(self.parts.iter().map(|part| { return part.after; }), t)
         {
 
-            Some(t) => &t.term,
-            None => return T::zero()
+            Some(i) => self.parts[i].term.evaluate(t),
+            None => T::zero()
 
         };
 
-        let mut t = time;
-
-        if let Some(c) = self.cycle
-        {
-
-            if t > c
-            {
-
-                t = t % c;
+    }
 
-            }
+    ///Evaluates the term assigned to the interval containing t like PiecewiseTerm::evaluate,
+    ///returning an error if that term fails. Parts are indexed in the order they were added.
+    fn try_evaluate(&self, time: T) -> Result<T, EvalError>
+    {
 
-        }
+        let t = try_wrap(time, self.cycle)?;
 
-        for part in iter
+        return match select(self.parts.iter().map(|part| { return part.after; }), t)
         {
 
-            if t >= part.after
-            {
-
-                current = &part.term;
+            Some(i) => self.parts[i].term.try_evaluate(t).map_err(|e| { return e.within(i); }),
+            None => Ok(T::zero())
 
-            }
-            else
-            {
-
-                return current.evaluate(t);
+        };
 
-            }
+    }
 
-        }
+    ///Views the term as its parts, in the order they were added, and its loop value
+    fn view(&self) -> TermView<'_, T>
+    {
 
-        return current.evaluate(t);
+        return TermView::Piecewise(self.parts.iter().map(|part| { return (Subterm::from(&*part.term), part.after); }).collect(), self.cycle);
 
     }
 
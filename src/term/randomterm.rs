@@ -1,6 +1,10 @@
 use rand::Rng;
 use crate::Number;
+use crate::EvalError;
+use crate::EvalErrorKind;
 use super::Term;
+use super::TermView;
+use super::Subterm;
 
 ///A term which computes a random value each time it is called
 pub struct RandomTerm<T: Number>
@@ -44,22 +48,41 @@ impl<T: Number> RandomTerm<T>
 
     }
 
-}
+    ///Generates a random value between already evaluated minimum and maximum values using the
+    ///thread-local random number generator
+    ///
+    /// # Panics
+    /// Panics if min is not less than max
+    pub fn generate(min: T, max: T) -> T
+    {
 
-impl<T: Number> Term<T> for RandomTerm<T>
-{
+        return RandomTerm::generate_with(&mut rand::thread_rng(), min, max);
+
+    }
 
-    ///Generates a random value between the min and max terms
+    ///Generates a random value between already evaluated minimum and maximum values using the given
+    ///random number generator, so that seeded generators produce reproducible values
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rand::SeedableRng;
+    /// use rand_chacha::ChaCha8Rng;
+    /// use crate::parametrizer::term::randomterm::RandomTerm;
+    ///
+    /// let mut first = ChaCha8Rng::seed_from_u64(7);
+    /// let mut second = ChaCha8Rng::seed_from_u64(7);
+    ///
+    /// assert_eq!(RandomTerm::generate_with(&mut first, 0.0, 10.0), RandomTerm::generate_with(&mut second, 0.0, 10.0));
+    /// ```
     ///
     /// # Panics
     /// Panics if min is not less than max
-    fn evaluate(&self, t: T) -> T
+    pub fn generate_with<R: Rng + ?Sized>(rng: &mut R, min: T, max: T) -> T
     {
 
-        let mut rng = rand::thread_rng();
-
-        let minimum = self.min.evaluate(t).to_f64().expect("Unable to convert generic type to f64 for random generation.");
-        let maximum = self.max.evaluate(t).to_f64().expect("Unable to convert generic type to f64 for random generation.");
+        let minimum = min.to_f64().expect("Unable to convert generic type to f64 for random generation.");
+        let maximum = max.to_f64().expect("Unable to convert generic type to f64 for random generation.");
 
         if minimum >= maximum
         {
@@ -74,4 +97,85 @@ impl<T: Number> Term<T> for RandomTerm<T>
 
     }
 
+    ///Generates a random value between already evaluated minimum and maximum values, returning an
+    ///error if min is not less than max, the range between them is not finite, or the values cannot
+    ///be converted to and from f64
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::term::randomterm::RandomTerm;
+    /// use crate::parametrizer::EvalErrorKind;
+    ///
+    /// assert!(RandomTerm::try_generate(1.0, 2.0).unwrap() >= 1.0);
+    /// assert_eq!(EvalErrorKind::InvalidRandomRange, RandomTerm::try_generate(2, 2).unwrap_err().kind());
+    /// assert_eq!(EvalErrorKind::InvalidRandomRange, RandomTerm::try_generate(f64::NAN, 2.0).unwrap_err().kind());
+    /// assert_eq!(EvalErrorKind::InvalidRandomRange, RandomTerm::try_generate(0.0, f64::INFINITY).unwrap_err().kind());
+    /// ```
+    pub fn try_generate(min: T, max: T) -> Result<T, EvalError>
+    {
+
+        return RandomTerm::try_generate_with(&mut rand::thread_rng(), min, max);
+
+    }
+
+    ///Generates a random value like RandomTerm::try_generate using the given random number generator
+    pub fn try_generate_with<R: Rng + ?Sized>(rng: &mut R, min: T, max: T) -> Result<T, EvalError>
+    {
+
+        let minimum = min.to_f64().ok_or(EvalError::new(EvalErrorKind::RandomConversion))?;
+        let maximum = max.to_f64().ok_or(EvalError::new(EvalErrorKind::RandomConversion))?;
+
+        //NaN bounds produce a NaN range, which is rejected as the generator would panic on it
+        if minimum >= maximum || !(maximum - minimum).is_finite()
+        {
+
+            return Err(EvalError::new(EvalErrorKind::InvalidRandomRange));
+
+        }
+
+        let random = rng.gen_range(minimum..maximum);
+
+        return T::from_f64(random).ok_or(EvalError::new(EvalErrorKind::RandomConversion));
+
+    }
+
+}
+
+impl<T: Number> Term<T> for RandomTerm<T>
+{
+
+    ///Generates a random value between the min and max terms using the thread-local random number
+    ///generator. Parsed random terms instead use the Parametrizer's generator if it has one, see
+    ///Parametrizer::with_seed.
+    ///
+    /// # Panics
+    /// Panics if min is not less than max
+    fn evaluate(&self, t: T) -> T
+    {
+
+        return RandomTerm::generate(self.min.evaluate(t), self.max.evaluate(t));
+
+    }
+
+    ///Generates a random value between the min and max terms, returning an error if min is not less
+    ///than max. The min term is the subterm at index 0 and the max term at index 1.
+    fn try_evaluate(&self, t: T) -> Result<T, EvalError>
+    {
+
+        let min = self.min.try_evaluate(t).map_err(|e| { return e.within(0); })?;
+        let max = self.max.try_evaluate(t).map_err(|e| { return e.within(1); })?;
+
+        return RandomTerm::try_generate(min, max);
+
+    }
+
+    ///Views the term as its min and max terms
+    fn view(&self) -> TermView<'_, T>
+    {
+
+        return TermView::Random(Subterm::from(&*self.min), Subterm::from(&*self.max));
+
+    }
+
 }
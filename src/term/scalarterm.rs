@@ -1,12 +1,18 @@
 use crate::Number;
+use crate::EvalError;
+use crate::EvalErrorKind;
+use crate::OverflowPolicy;
 use super::Term;
+use super::TermView;
+use super::Subterm;
 
 ///A term which multiplies a given term by a constant number. Especially useful for - signs
 pub struct ScalarTerm<T: Number>
 {
 
     term: Box<dyn Term<T> + Send + Sync>,
-    scale: T
+    scale: T,
+    policy: OverflowPolicy
 
 }
 
@@ -35,7 +41,32 @@ impl<T: Number> ScalarTerm<T>
     pub fn new(term: Box<dyn Term<T> + Send + Sync>, scale: T) -> ScalarTerm<T>
     {
 
-        return ScalarTerm { term, scale };
+        return ScalarTerm { term, scale, policy: OverflowPolicy::Checked };
+
+    }
+
+    ///Sets how integer overflow is handled when scaling the subterm, which is checked by default
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::term::scalarterm::ScalarTerm;
+    /// use crate::parametrizer::term::variableterm::VariableTerm;
+    /// use crate::parametrizer::term::Term;
+    /// use crate::parametrizer::OverflowPolicy;
+    ///
+    /// let mut negation = ScalarTerm::new(Box::new(VariableTerm::new()), -1);
+    ///
+    /// assert!(negation.try_evaluate(i32::MIN).is_err());
+    ///
+    /// negation.set_overflow_policy(OverflowPolicy::Wrapping);
+    ///
+    /// assert_eq!(i32::MIN, negation.evaluate(i32::MIN));
+    /// ```
+    pub fn set_overflow_policy(&mut self, policy: OverflowPolicy)
+    {
+
+        self.policy = policy;
 
     }
 
@@ -45,10 +76,31 @@ impl<T: Number> Term<T> for ScalarTerm<T>
 {
 
     ///Multiplies the subterm by the given constant
+    ///
+    /// # Panics
+    /// Panics if the result overflows under OverflowPolicy::Checked
     fn evaluate(&self, t: T) -> T
     {
 
-        return self.scale * self.term.evaluate(t);
+        return self.policy.multiply(self.scale, self.term.evaluate(t)).expect("Integer overflow in parametrized ScalarTerm.");
+
+    }
+
+    ///Multiplies the subterm by the given constant, passing on any error from the subterm at index 0
+    fn try_evaluate(&self, t: T) -> Result<T, EvalError>
+    {
+
+        let value = self.term.try_evaluate(t).map_err(|e| { return e.within(0); })?;
+
+        return self.policy.multiply(self.scale, value).ok_or(EvalError::new(EvalErrorKind::Overflow));
+
+    }
+
+    ///Views the term as its subterm and scale
+    fn view(&self) -> TermView<'_, T>
+    {
+
+        return TermView::Scalar(Subterm::from(&*self.term), self.scale);
 
     }
 
@@ -1,7 +1,13 @@
 use crate::Number;
+use crate::EvalError;
+use crate::EvalErrorKind;
+use crate::OverflowPolicy;
 use super::Term;
+use super::TermView;
+use super::Subterm;
 
 ///An enum defining the different operations supported by sequence terms
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum SequenceOperations
 {
 
@@ -10,12 +16,58 @@ pub enum SequenceOperations
 
 }
 
+impl SequenceOperations
+{
+
+    ///The value a sequence with no terms evaluates to, i.e. 0 for addition and 1 for multiplication
+    pub fn unit<T: Number>(&self) -> T
+    {
+
+        match self
+        {
+
+            SequenceOperations::Addition => T::zero(),
+            SequenceOperations::Multiplication => T::one()
+
+        }
+
+    }
+
+    ///Combines two values using the operation, returning None if the result overflows under the
+    ///given policy
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::term::sequenceterm::SequenceOperations;
+    /// use crate::parametrizer::OverflowPolicy;
+    ///
+    /// assert_eq!(Some(12), SequenceOperations::Multiplication.compound(3, 4, OverflowPolicy::Checked));
+    /// assert_eq!(None, SequenceOperations::Addition.compound(u8::MAX, 1, OverflowPolicy::Checked));
+    /// assert_eq!(Some(0), SequenceOperations::Addition.compound(u8::MAX, 1, OverflowPolicy::Wrapping));
+    /// ```
+    pub fn compound<T: Number>(&self, l: T, r: T, policy: OverflowPolicy) -> Option<T>
+    {
+
+        match self
+        {
+
+            SequenceOperations::Addition => policy.add(l, r),
+            SequenceOperations::Multiplication => policy.multiply(l, r)
+
+        }
+
+    }
+
+}
+
 ///A term which takes in a vector of terms and combines them together with an operator (i.e. +, *)
 pub struct SequenceTerm<T: Number>
 {
 
     terms: Vec<Box<dyn Term<T> + Send + Sync>>,
-    operation: SequenceOperations
+    operation: SequenceOperations,
+    policy: OverflowPolicy
 
 }
 
@@ -66,55 +118,88 @@ impl<T: Number> SequenceTerm<T>
     pub fn new(terms: Vec<Box<dyn Term<T> + Send + Sync>>, operation: SequenceOperations) -> SequenceTerm<T>
     {
 
-        return SequenceTerm {terms, operation};
+        return SequenceTerm {terms, operation, policy: OverflowPolicy::Checked};
 
     }
 
-    fn unit(&self) -> T
+    ///Sets how integer overflow is handled when combining the terms, which is checked by default
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crate::parametrizer::term::constantterm::ConstantTerm;
+    /// use crate::parametrizer::term::variableterm::VariableTerm;
+    /// use crate::parametrizer::term::sequenceterm::SequenceOperations;
+    /// use crate::parametrizer::term::sequenceterm::SequenceTerm;
+    /// use crate::parametrizer::term::Term;
+    /// use crate::parametrizer::OverflowPolicy;
+    ///
+    /// let terms : Vec<Box<dyn Term<i8> + Send + Sync>> = vec![Box::new(ConstantTerm::new(100)), Box::new(VariableTerm::new())];
+    ///
+    /// let mut addition = SequenceTerm::new(terms, SequenceOperations::Addition);
+    ///
+    /// assert!(addition.try_evaluate(50).is_err());
+    ///
+    /// addition.set_overflow_policy(OverflowPolicy::Saturating);
+    ///
+    /// assert_eq!(127, addition.evaluate(50));
+    /// ```
+    pub fn set_overflow_policy(&mut self, policy: OverflowPolicy)
     {
 
-        match self.operation
-        {
+        self.policy = policy;
 
-            SequenceOperations::Addition => T::zero(),
-            SequenceOperations::Multiplication => T::one()
+    }
 
-        }
+}
 
-    }
+impl<T: Number> Term<T> for SequenceTerm<T>
+{
 
-    fn compound(&self, l: T, r: T) -> T
+    ///Adds/multiplies together all of the terms
+    ///
+    /// # Panics
+    /// Panics if the result overflows under OverflowPolicy::Checked
+    fn evaluate(&self, t: T) -> T
     {
 
-        match self.operation
+        let mut computed = self.operation.unit();
+
+        for term in &self.terms
         {
 
-            SequenceOperations::Addition => l + r,
-            SequenceOperations::Multiplication => l * r
+            computed = self.operation.compound(computed, term.evaluate(t), self.policy).expect("Integer overflow in parametrized SequenceTerm.");
 
         }
 
-    }
-
-}
+        return computed;
 
-impl<T: Number> Term<T> for SequenceTerm<T>
-{
+    }
 
-    ///Adds/multiplies together all of the terms
-    fn evaluate(&self, t: T) -> T
+    ///Adds/multiplies together all of the terms, stopping at the first one which fails
+    fn try_evaluate(&self, t: T) -> Result<T, EvalError>
     {
 
-        let mut computed = self.unit();
+        let mut computed = self.operation.unit();
 
-        for term in &self.terms
+        for (i, term) in self.terms.iter().enumerate()
         {
 
-            computed = self.compound(computed, term.evaluate(t));
+            let value = term.try_evaluate(t).map_err(|e| { return e.within(i); })?;
+
+            computed = self.operation.compound(computed, value, self.policy).ok_or(EvalError::new(EvalErrorKind::Overflow))?;
 
         }
 
-        return computed;
+        return Ok(computed);
+
+    }
+
+    ///Views the term as its operation and the terms it combines
+    fn view(&self) -> TermView<'_, T>
+    {
+
+        return TermView::Sequence(self.operation, self.terms.iter().map(|term| { return Subterm::from(&**term); }).collect());
 
     }
 
@@ -1,5 +1,6 @@
 use crate::Number;
 use super::Term;
+use super::TermView;
 
 ///A term which always returns the value of the parameter, t
 pub struct VariableTerm
@@ -43,4 +44,12 @@ impl<T: Number> Term<T> for VariableTerm
 
     }
 
+    ///Views the term as the variable t
+    fn view(&self) -> TermView<'_, T>
+    {
+
+        return TermView::Variable;
+
+    }
+
 }